@@ -0,0 +1,163 @@
+use crate::{
+    error::Result,
+    hash,
+    sync::{self, CommitId, FileBlame},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+///
+#[derive(Hash, Clone, PartialEq)]
+pub struct BlameParams {
+    /// path to the file to blame
+    pub file_path: String,
+    /// commit to blame at, HEAD if `None`
+    pub commit_id: Option<CommitId>,
+}
+
+struct Request<R, A>(R, Option<A>);
+
+///
+pub struct AsyncBlame {
+    current: Arc<Mutex<Request<u64, FileBlame>>>,
+    last_error: Arc<Mutex<Option<String>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncBlame {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(Request(0, None))),
+            last_error: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// error of the last failed blame, reset by taking it
+    pub fn take_last_error(&self) -> Result<Option<String>> {
+        Ok(self.last_error.lock()?.take())
+    }
+
+    /// returns the blame if it is already available for `params`,
+    /// otherwise starts blaming in the background.
+    /// a failed blame is not kept, see `take_last_error`
+    pub fn request(
+        &mut self,
+        params: BlameParams,
+    ) -> Result<Option<FileBlame>> {
+        log::trace!("request");
+
+        // blaming HEAD has to be redone once HEAD moves
+        let params = BlameParams {
+            commit_id: Some(match params.commit_id {
+                Some(id) => id,
+                None => sync::get_head(CWD)?,
+            }),
+            ..params
+        };
+
+        let hash = hash(&params);
+
+        {
+            let mut current = self.current.lock()?;
+
+            if current.0 == hash {
+                return Ok(current.1.clone());
+            }
+
+            current.0 = hash;
+            current.1 = None;
+        }
+
+        let arc_current = Arc::clone(&self.current);
+        let arc_error = Arc::clone(&self.last_error);
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+
+        self.pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            let notify = match AsyncBlame::get_blame_helper(
+                params,
+                Arc::clone(&arc_current),
+                hash,
+            ) {
+                Ok(notify) => notify,
+                Err(e) => {
+                    log::error!("blame error: {}", e);
+                    AsyncBlame::set_error(
+                        &arc_current,
+                        &arc_error,
+                        hash,
+                        e.to_string(),
+                    )
+                }
+            };
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(if notify {
+                    AsyncNotification::Blame
+                } else {
+                    AsyncNotification::FinishUnchanged
+                })
+                .expect("error sending blame");
+        });
+
+        Ok(None)
+    }
+
+    fn get_blame_helper(
+        params: BlameParams,
+        arc_current: Arc<Mutex<Request<u64, FileBlame>>>,
+        hash: u64,
+    ) -> Result<bool> {
+        let res = sync::blame_file(
+            CWD,
+            &params.file_path,
+            params.commit_id,
+        )?;
+
+        let mut current = arc_current.lock()?;
+        if current.0 == hash {
+            current.1 = Some(res);
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// forgets the failed request so it is tried again next time,
+    /// returns whether it still was the current one
+    fn set_error(
+        arc_current: &Arc<Mutex<Request<u64, FileBlame>>>,
+        arc_error: &Arc<Mutex<Option<String>>>,
+        hash: u64,
+        error: String,
+    ) -> bool {
+        if let Ok(mut current) = arc_current.lock() {
+            if current.0 == hash {
+                current.0 = 0;
+                if let Ok(mut last_error) = arc_error.lock() {
+                    *last_error = Some(error);
+                }
+                return true;
+            }
+        }
+
+        false
+    }
+}
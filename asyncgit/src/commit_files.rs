@@ -1,7 +1,7 @@
 use crate::{
     error::Result,
     sync::{self, CommitId},
-    AsyncNotification, StatusItem, CWD,
+    AsyncNotification, DiffOptions, StatusItem, CWD,
 };
 use crossbeam_channel::Sender;
 use std::sync::{
@@ -12,9 +12,32 @@ use std::sync::{
 type ResultType = Vec<StatusItem>;
 struct Request<R, A>(R, A);
 
+/// what to list the changed files of
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CommitFilesParams {
+    /// files changed in a commit
+    Commit(CommitId),
+    /// files changed from the first to the second commit
+    Compare(CommitId, CommitId),
+    /// files changed between a commit and the working dir
+    CompareWorkdir(CommitId),
+}
+
+impl From<CommitId> for CommitFilesParams {
+    fn from(id: CommitId) -> Self {
+        Self::Commit(id)
+    }
+}
+
 ///
 pub struct AsyncCommitFiles {
-    current: Arc<Mutex<Option<Request<CommitId, ResultType>>>>,
+    current: Arc<
+        Mutex<
+            Option<
+                Request<(CommitFilesParams, DiffOptions), ResultType>,
+            >,
+        >,
+    >,
     sender: Sender<AsyncNotification>,
     pending: Arc<AtomicUsize>,
 }
@@ -32,11 +55,12 @@ impl AsyncCommitFiles {
     ///
     pub fn current(
         &mut self,
-    ) -> Result<Option<(CommitId, ResultType)>> {
+    ) -> Result<Option<(CommitFilesParams, DiffOptions, ResultType)>>
+    {
         let c = self.current.lock()?;
 
         if let Some(c) = c.as_ref() {
-            Ok(Some((c.0, c.1.clone())))
+            Ok(Some(((c.0).0, (c.0).1, c.1.clone())))
         } else {
             Ok(None)
         }
@@ -47,18 +71,22 @@ impl AsyncCommitFiles {
         self.pending.load(Ordering::Relaxed) > 0
     }
 
-    ///
-    pub fn fetch(&mut self, id: CommitId) -> Result<()> {
+    /// `options` decide whether renames are detected
+    pub fn fetch(
+        &mut self,
+        params: CommitFilesParams,
+        options: DiffOptions,
+    ) -> Result<()> {
         if self.is_pending() {
             return Ok(());
         }
 
-        log::trace!("request: {}", id.to_string());
+        log::trace!("request: {:?}", params);
 
         {
             let current = self.current.lock()?;
             if let Some(c) = &*current {
-                if c.0 == id {
+                if c.0 == (params, options) {
                     return Ok(());
                 }
             }
@@ -71,7 +99,7 @@ impl AsyncCommitFiles {
         self.pending.fetch_add(1, Ordering::Relaxed);
 
         rayon_core::spawn(move || {
-            Self::fetch_helper(id, arc_current)
+            Self::fetch_helper(params, options, arc_current)
                 .expect("failed to fetch");
 
             arc_pending.fetch_sub(1, Ordering::Relaxed);
@@ -85,22 +113,44 @@ impl AsyncCommitFiles {
     }
 
     fn fetch_helper(
-        id: CommitId,
+        params: CommitFilesParams,
+        options: DiffOptions,
         arc_current: Arc<
-            Mutex<Option<Request<CommitId, ResultType>>>,
+            Mutex<
+                Option<
+                    Request<
+                        (CommitFilesParams, DiffOptions),
+                        ResultType,
+                    >,
+                >,
+            >,
         >,
     ) -> Result<()> {
-        let res = sync::get_commit_files(CWD, id)?;
+        let res = match params {
+            CommitFilesParams::Commit(id) => {
+                sync::get_commit_files(CWD, id, Some(options))?
+            }
+            CommitFilesParams::Compare(old, new) => {
+                sync::get_compare_commits_files(
+                    CWD,
+                    (old, new),
+                    Some(options),
+                )?
+            }
+            CommitFilesParams::CompareWorkdir(id) => {
+                sync::get_compare_workdir_files(
+                    CWD,
+                    id,
+                    Some(options),
+                )?
+            }
+        };
 
-        log::trace!(
-            "get_commit_files: {} ({})",
-            id.to_string(),
-            res.len()
-        );
+        log::trace!("get_commit_files: {:?} ({})", params, res.len());
 
         {
             let mut current = arc_current.lock()?;
-            *current = Some(Request(id, res));
+            *current = Some(Request((params, options), res));
         }
 
         Ok(())
@@ -1,7 +1,7 @@
 use crate::{
     error::Result,
     hash,
-    sync::{self, CommitId},
+    sync::{self, diff::DiffOptions, CommitId},
     AsyncNotification, FileDiff, CWD,
 };
 use crossbeam_channel::Sender;
@@ -22,6 +22,10 @@ pub enum DiffType {
     Stage,
     /// diff against file in workdir
     WorkDir,
+    /// diff from the first to the second commit
+    Compare(CommitId, CommitId),
+    /// diff between a commit and the workdir
+    CompareWorkdir(CommitId),
 }
 
 ///
@@ -31,6 +35,8 @@ pub struct DiffParams {
     pub path: String,
     /// what kind of diff
     pub diff_type: DiffType,
+    /// how to diff
+    pub options: DiffOptions,
 }
 
 struct Request<R, A>(R, Option<A>);
@@ -144,17 +150,40 @@ impl AsyncDiff {
         hash: u64,
     ) -> Result<bool> {
         let res = match params.diff_type {
-            DiffType::Stage => {
-                sync::diff::get_diff(CWD, params.path.clone(), true)?
-            }
-            DiffType::WorkDir => {
-                sync::diff::get_diff(CWD, params.path.clone(), false)?
-            }
+            DiffType::Stage => sync::diff::get_diff(
+                CWD,
+                params.path.clone(),
+                true,
+                Some(params.options),
+            )?,
+            DiffType::WorkDir => sync::diff::get_diff(
+                CWD,
+                params.path.clone(),
+                false,
+                Some(params.options),
+            )?,
             DiffType::Commit(id) => sync::diff::get_diff_commit(
                 CWD,
                 id,
                 params.path.clone(),
+                Some(params.options),
             )?,
+            DiffType::Compare(old, new) => {
+                sync::diff::get_diff_commits(
+                    CWD,
+                    (old, new),
+                    params.path.clone(),
+                    Some(params.options),
+                )?
+            }
+            DiffType::CompareWorkdir(id) => {
+                sync::diff::get_diff_commit_workdir(
+                    CWD,
+                    id,
+                    params.path.clone(),
+                    Some(params.options),
+                )?
+            }
         };
 
         let mut notify = false;
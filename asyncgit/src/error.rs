@@ -1,24 +1,43 @@
+//! asyncgit error type
+
+use crate::sync::BasicAuthCredential;
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
+///
 #[derive(Error, Debug)]
 pub enum Error {
+    ///
     #[error("`{0}`")]
     Generic(String),
 
+    ///
     #[error("git: no head found")]
     NoHead,
 
+    /// operation stopped because of conflicts in these paths
+    #[error("conflicts in:\n{}", .0.join("\n"))]
+    Conflicts(Vec<String>),
+
+    /// the remote needs username/password and the credential helpers
+    /// do not know (all of) it, holds what they know
+    #[error("credentials missing")]
+    MissingCredential(BasicAuthCredential),
+
+    ///
     #[error("io error:{0}")]
     Io(#[from] std::io::Error),
 
+    ///
     #[error("git error:{0}")]
     Git(#[from] git2::Error),
 
+    ///
     #[error("utf8 error:{0}")]
     Utf8Error(#[from] FromUtf8Error),
 }
 
+///
 pub type Result<T> = std::result::Result<T, Error>;
 
 impl<T> From<std::sync::PoisonError<T>> for Error {
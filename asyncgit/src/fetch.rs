@@ -0,0 +1,169 @@
+use crate::{
+    error::{Error, Result},
+    sync::{self, BasicAuthCredential},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{Arc, Mutex};
+
+///
+#[derive(Default, Clone, Debug)]
+pub struct FetchRequest {
+    ///
+    pub remote: String,
+    ///
+    pub branch: String,
+    /// username/password for http(s) remotes
+    pub basic_credential: Option<BasicAuthCredential>,
+    /// merge the upstream into `branch` after fetching (pull)
+    pub merge: bool,
+}
+
+///
+pub struct AsyncFetch {
+    state: Arc<Mutex<Option<FetchRequest>>>,
+    last_result: Arc<Mutex<Option<String>>>,
+    missing_credential: Arc<Mutex<Option<BasicAuthCredential>>>,
+    sender: Sender<AsyncNotification>,
+}
+
+impl AsyncFetch {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(None)),
+            last_result: Arc::new(Mutex::new(None)),
+            missing_credential: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> Result<bool> {
+        let state = self.state.lock()?;
+        Ok(state.is_some())
+    }
+
+    /// error message of the last fetch (or merge),
+    /// `None` if it succeeded
+    pub fn last_result(&self) -> Result<Option<String>> {
+        let res = self.last_result.lock()?;
+        Ok(res.clone())
+    }
+
+    /// what the credential helpers know if the last fetch stopped
+    /// because the user has to complete the credential
+    pub fn missing_credential(
+        &self,
+    ) -> Result<Option<BasicAuthCredential>> {
+        let cred = self.missing_credential.lock()?;
+        Ok(cred.clone())
+    }
+
+    ///
+    pub fn request(&mut self, params: FetchRequest) -> Result<()> {
+        log::trace!("request");
+
+        if self.is_pending()? {
+            return Ok(());
+        }
+
+        self.set_request(&params)?;
+
+        let arc_state = Arc::clone(&self.state);
+        let arc_res = Arc::clone(&self.last_result);
+        let arc_cred = Arc::clone(&self.missing_credential);
+        let sender = self.sender.clone();
+
+        rayon_core::spawn(move || {
+            let res = sync::resolve_credential(
+                CWD,
+                params.remote.as_str(),
+                params.basic_credential.clone(),
+            )
+            .and_then(|cred| {
+                sync::fetch(
+                    CWD,
+                    params.remote.as_str(),
+                    params.branch.as_str(),
+                    cred,
+                )
+            })
+            .and_then(|bytes| {
+                if params.merge {
+                    let res = sync::merge_upstream(
+                        CWD,
+                        params.branch.as_str(),
+                    )?;
+                    log::trace!("pull: {:?}", res);
+                }
+
+                Ok(bytes)
+            });
+
+            Self::set_result(arc_res, arc_cred, res)
+                .expect("result error");
+
+            Self::clear_request(arc_state).expect("clear error");
+
+            sender
+                .send(AsyncNotification::Fetch)
+                .expect("error sending fetch");
+        });
+
+        Ok(())
+    }
+
+    fn set_request(&self, params: &FetchRequest) -> Result<()> {
+        let mut state = self.state.lock()?;
+
+        if state.is_some() {
+            return Err(Error::Generic("pending request".into()));
+        }
+
+        *state = Some(params.clone());
+
+        Ok(())
+    }
+
+    fn clear_request(
+        state: Arc<Mutex<Option<FetchRequest>>>,
+    ) -> Result<()> {
+        let mut state = state.lock()?;
+
+        *state = None;
+
+        Ok(())
+    }
+
+    fn set_result(
+        arc_result: Arc<Mutex<Option<String>>>,
+        arc_cred: Arc<Mutex<Option<BasicAuthCredential>>>,
+        res: Result<usize>,
+    ) -> Result<()> {
+        let mut last_res = arc_result.lock()?;
+        let mut missing_cred = arc_cred.lock()?;
+        *missing_cred = None;
+
+        *last_res = match res {
+            Ok(bytes) => {
+                log::trace!("fetched: {} B", bytes);
+                None
+            }
+            Err(Error::MissingCredential(cred)) => {
+                *missing_cred = Some(cred);
+                None
+            }
+            Err(Error::Conflicts(paths)) => Some(format!(
+                "conflicts (merge is in progress):\n{}",
+                paths.join("\n")
+            )),
+            Err(e) => {
+                log::error!("fetch error: {}", e);
+                Some(e.to_string())
+            }
+        };
+
+        Ok(())
+    }
+}
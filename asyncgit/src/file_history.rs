@@ -0,0 +1,132 @@
+use crate::{
+    error::Result,
+    sync::{utils::repo, FileHistoryEntry, FileLogWalker},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use scopetime::scope_time;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+static LIMIT_COUNT: usize = 3000;
+
+/// walks the history of a single file in the background
+pub struct AsyncFileHistory {
+    current: Arc<Mutex<Vec<FileHistoryEntry>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicBool>,
+    generation: Arc<AtomicUsize>,
+}
+
+impl AsyncFileHistory {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(Vec::new())),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicBool::new(false)),
+            generation: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    ///
+    pub fn count(&self) -> Result<usize> {
+        Ok(self.current.lock()?.len())
+    }
+
+    ///
+    pub fn get_slice(
+        &self,
+        start_index: usize,
+        amount: usize,
+    ) -> Result<Vec<FileHistoryEntry>> {
+        let list = self.current.lock()?;
+        let list_len = list.len();
+        let min = start_index.min(list_len);
+        let max = min.saturating_add(amount).min(list_len);
+        Ok(list[min..max].to_vec())
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// (re)starts walking the history of `path`,
+    /// a walk still running for a previous request is cancelled
+    pub fn fetch(&mut self, path: String) -> Result<()> {
+        self.current.lock()?.clear();
+
+        let generation =
+            self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+
+        let arc_current = Arc::clone(&self.current);
+        let arc_generation = Arc::clone(&self.generation);
+        let arc_pending = Arc::clone(&self.pending);
+        let sender = self.sender.clone();
+
+        self.pending.store(true, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            scope_time!("async::file_history");
+
+            if let Err(e) = Self::fetch_helper(
+                &path,
+                &arc_current,
+                &arc_generation,
+                generation,
+                &sender,
+            ) {
+                log::error!("file history error: {}", e);
+            }
+
+            if arc_generation.load(Ordering::Relaxed) == generation {
+                arc_pending.store(false, Ordering::Relaxed);
+                Self::notify(&sender);
+            }
+        });
+
+        Ok(())
+    }
+
+    fn fetch_helper(
+        path: &str,
+        arc_current: &Arc<Mutex<Vec<FileHistoryEntry>>>,
+        arc_generation: &Arc<AtomicUsize>,
+        generation: usize,
+        sender: &Sender<AsyncNotification>,
+    ) -> Result<()> {
+        let r = repo(CWD)?;
+        let mut walker = FileLogWalker::new(&r, path);
+        let mut entries = Vec::new();
+
+        loop {
+            entries.clear();
+            let walked = walker.read(&mut entries, LIMIT_COUNT)?;
+
+            {
+                let mut current = arc_current.lock()?;
+                if arc_generation.load(Ordering::Relaxed)
+                    != generation
+                {
+                    return Ok(());
+                }
+                current.extend(entries.drain(..));
+            }
+
+            if walked < LIMIT_COUNT {
+                return Ok(());
+            }
+
+            Self::notify(sender);
+        }
+    }
+
+    fn notify(sender: &Sender<AsyncNotification>) {
+        sender
+            .send(AsyncNotification::FileHistory)
+            .expect("error sending");
+    }
+}
@@ -7,10 +7,14 @@
 #![deny(clippy::panic)]
 #![deny(clippy::perf)]
 
+mod blame;
 pub mod cached;
 mod commit_files;
 mod diff;
-mod error;
+pub mod error;
+mod fetch;
+mod file_history;
+mod log_search;
 mod push;
 mod revlog;
 mod status;
@@ -18,13 +22,17 @@ pub mod sync;
 mod tags;
 
 pub use crate::{
-    commit_files::AsyncCommitFiles,
+    blame::{AsyncBlame, BlameParams},
+    commit_files::{AsyncCommitFiles, CommitFilesParams},
     diff::{AsyncDiff, DiffParams, DiffType},
-    push::{AsyncPush, PushRequest},
+    fetch::{AsyncFetch, FetchRequest},
+    file_history::AsyncFileHistory,
+    log_search::AsyncLogSearch,
+    push::{AsyncPush, PushProgress, PushProgressState, PushRequest},
     revlog::{AsyncLog, FetchStatus},
     status::{AsyncStatus, StatusParams},
     sync::{
-        diff::{DiffLine, DiffLineType, FileDiff},
+        diff::{DiffLine, DiffLineType, DiffOptions, FileDiff},
         status::{StatusItem, StatusItemType},
     },
     tags::AsyncTags,
@@ -51,6 +59,14 @@ pub enum AsyncNotification {
     Tags,
     ///
     Push,
+    ///
+    Fetch,
+    ///
+    Blame,
+    ///
+    FileHistory,
+    ///
+    LogSearch,
 }
 
 /// current working director `./`
@@ -0,0 +1,135 @@
+use crate::{
+    error::Result,
+    sync::{utils::repo, CommitId, LogSearch, LogWalker},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use scopetime::scope_time;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+static LIMIT_COUNT: usize = 3000;
+
+/// searches the whole history from HEAD in the background
+pub struct AsyncLogSearch {
+    /// position in the log (as walked by `AsyncLog`) and id
+    current: Arc<Mutex<Vec<(usize, CommitId)>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicBool>,
+    generation: Arc<AtomicUsize>,
+}
+
+impl AsyncLogSearch {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(Vec::new())),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicBool::new(false)),
+            generation: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// matches found so far as (position in log, id), in log order
+    pub fn matches(&self) -> Result<Vec<(usize, CommitId)>> {
+        Ok(self.current.lock()?.clone())
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// cancels a running search and drops its matches
+    pub fn clear(&mut self) -> Result<()> {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.pending.store(false, Ordering::Relaxed);
+        self.current.lock()?.clear();
+        Ok(())
+    }
+
+    /// (re)starts searching the log for `search`
+    pub fn fetch(&mut self, search: LogSearch) -> Result<()> {
+        self.clear()?;
+
+        let generation = self.generation.load(Ordering::Relaxed);
+
+        let arc_current = Arc::clone(&self.current);
+        let arc_generation = Arc::clone(&self.generation);
+        let arc_pending = Arc::clone(&self.pending);
+        let sender = self.sender.clone();
+
+        self.pending.store(true, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            scope_time!("async::log_search");
+
+            if let Err(e) = Self::fetch_helper(
+                &search,
+                &arc_current,
+                &arc_generation,
+                generation,
+                &sender,
+            ) {
+                log::error!("log search error: {}", e);
+            }
+
+            if arc_generation.load(Ordering::Relaxed) == generation {
+                arc_pending.store(false, Ordering::Relaxed);
+                Self::notify(&sender);
+            }
+        });
+
+        Ok(())
+    }
+
+    fn fetch_helper(
+        search: &LogSearch,
+        arc_current: &Arc<Mutex<Vec<(usize, CommitId)>>>,
+        arc_generation: &Arc<AtomicUsize>,
+        generation: usize,
+        sender: &Sender<AsyncNotification>,
+    ) -> Result<()> {
+        let r = repo(CWD)?;
+        let mut walker = LogWalker::new(&r);
+        let mut ids = Vec::with_capacity(LIMIT_COUNT);
+        let mut position = 0_usize;
+
+        loop {
+            ids.clear();
+            walker.read(&mut ids, LIMIT_COUNT)?;
+
+            let mut found = Vec::new();
+            for id in &ids {
+                if search.matches(&r.find_commit((*id).into())?) {
+                    found.push((position, *id));
+                }
+                position += 1;
+            }
+
+            {
+                let mut current = arc_current.lock()?;
+                if arc_generation.load(Ordering::Relaxed)
+                    != generation
+                {
+                    return Ok(());
+                }
+                current.extend(found);
+            }
+
+            if ids.len() < LIMIT_COUNT {
+                return Ok(());
+            }
+
+            Self::notify(sender);
+        }
+    }
+
+    fn notify(sender: &Sender<AsyncNotification>) {
+        sender
+            .send(AsyncNotification::LogSearch)
+            .expect("error sending");
+    }
+}
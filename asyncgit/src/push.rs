@@ -1,20 +1,68 @@
 use crate::{
     error::{Error, Result},
-    sync, AsyncNotification, CWD,
+    sync::{self, BasicAuthCredential, ProgressNotification},
+    AsyncNotification, CWD,
 };
-use crossbeam_channel::Sender;
+use crossbeam_channel::{unbounded, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 
-#[derive(Clone, Debug)]
-enum PushStates {
-    None,
-    // Packing,
-    // Pushing(usize, usize),
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PushProgressState {
+    ///
+    Packing,
+    ///
+    Pushing,
+}
+
+/// progress of a running push, see `AsyncPush::progress`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PushProgress {
+    ///
+    pub state: PushProgressState,
+    /// objects packed so far and total amount of objects to pack
+    pub packed: (usize, usize),
+    /// objects sent so far and total amount of objects to send
+    pub pushed: (usize, usize),
+    /// bytes sent so far
+    pub bytes: usize,
 }
 
-impl Default for PushStates {
+impl Default for PushProgress {
     fn default() -> Self {
-        PushStates::None
+        Self {
+            state: PushProgressState::Packing,
+            packed: (0, 0),
+            pushed: (0, 0),
+            bytes: 0,
+        }
+    }
+}
+
+impl PushProgress {
+    fn apply(&mut self, notification: ProgressNotification) {
+        match notification {
+            ProgressNotification::PackingAddingObject {
+                current,
+                total,
+            }
+            | ProgressNotification::PackingDeltafication {
+                current,
+                total,
+            } => {
+                self.state = PushProgressState::Packing;
+                self.packed = (current, total);
+            }
+            ProgressNotification::PushTransfer {
+                current,
+                total,
+                bytes,
+            } => {
+                self.state = PushProgressState::Pushing;
+                self.pushed = (current, total);
+                self.bytes = bytes;
+            }
+        }
     }
 }
 
@@ -25,18 +73,23 @@ pub struct PushRequest {
     pub remote: String,
     ///
     pub branch: String,
+    /// push this tag instead of `branch`
+    pub tag: Option<String>,
+    /// username/password for http(s) remotes
+    pub basic_credential: Option<BasicAuthCredential>,
 }
 
 #[derive(Default, Clone, Debug)]
 struct PushState {
     request: PushRequest,
-    state: PushStates,
+    progress: PushProgress,
 }
 
 ///
 pub struct AsyncPush {
     state: Arc<Mutex<Option<PushState>>>,
     last_result: Arc<Mutex<Option<String>>>,
+    missing_credential: Arc<Mutex<Option<BasicAuthCredential>>>,
     sender: Sender<AsyncNotification>,
 }
 
@@ -46,6 +99,7 @@ impl AsyncPush {
         Self {
             state: Arc::new(Mutex::new(None)),
             last_result: Arc::new(Mutex::new(None)),
+            missing_credential: Arc::new(Mutex::new(None)),
             sender: sender.clone(),
         }
     }
@@ -62,6 +116,21 @@ impl AsyncPush {
         Ok(res.clone())
     }
 
+    /// what the credential helpers know if the last push stopped
+    /// because the user has to complete the credential
+    pub fn missing_credential(
+        &self,
+    ) -> Result<Option<BasicAuthCredential>> {
+        let cred = self.missing_credential.lock()?;
+        Ok(cred.clone())
+    }
+
+    /// progress of the currently running push (if any)
+    pub fn progress(&self) -> Result<Option<PushProgress>> {
+        let state = self.state.lock()?;
+        Ok(state.as_ref().map(|state| state.progress))
+    }
+
     ///
     pub fn request(&mut self, params: PushRequest) -> Result<()> {
         log::trace!("request");
@@ -74,17 +143,39 @@ impl AsyncPush {
 
         let arc_state = Arc::clone(&self.state);
         let arc_res = Arc::clone(&self.last_result);
+        let arc_cred = Arc::clone(&self.missing_credential);
         let sender = self.sender.clone();
 
+        let (progress_sender, progress_receiver) = unbounded();
+
         rayon_core::spawn(move || {
-            //TODO: use channels to communicate progress
-            let res = sync::push_origin(
+            let res = sync::resolve_credential(
                 CWD,
                 params.remote.as_str(),
-                params.branch.as_str(),
-            );
+                params.basic_credential.clone(),
+            )
+            .and_then(|cred| {
+                if let Some(tag) = params.tag {
+                    sync::push_tag(
+                        CWD,
+                        params.remote.as_str(),
+                        tag.as_str(),
+                        cred,
+                        Some(progress_sender),
+                    )
+                } else {
+                    sync::push_origin(
+                        CWD,
+                        params.remote.as_str(),
+                        params.branch.as_str(),
+                        cred,
+                        Some(progress_sender),
+                    )
+                }
+            });
 
-            Self::set_result(arc_res, res).expect("result error");
+            Self::set_result(arc_res, arc_cred, res)
+                .expect("result error");
 
             Self::clear_request(arc_state).expect("clear error");
 
@@ -93,6 +184,46 @@ impl AsyncPush {
                 .expect("error sending push");
         });
 
+        // spawned after the push so a single threaded pool runs the
+        // push first instead of blocking on the receiver forever
+        Self::spawn_progress_receiver(
+            progress_receiver,
+            Arc::clone(&self.state),
+            self.sender.clone(),
+        );
+
+        Ok(())
+    }
+
+    /// applies progress updates to the shared state until
+    /// the sending side (the push callbacks) is dropped
+    fn spawn_progress_receiver(
+        receiver: Receiver<ProgressNotification>,
+        arc_state: Arc<Mutex<Option<PushState>>>,
+        sender: Sender<AsyncNotification>,
+    ) {
+        rayon_core::spawn(move || {
+            while let Ok(notification) = receiver.recv() {
+                Self::set_progress(&arc_state, notification)
+                    .expect("progress error");
+
+                sender
+                    .send(AsyncNotification::Push)
+                    .expect("error sending push");
+            }
+        });
+    }
+
+    fn set_progress(
+        state: &Arc<Mutex<Option<PushState>>>,
+        notification: ProgressNotification,
+    ) -> Result<()> {
+        let mut state = state.lock()?;
+
+        if let Some(state) = state.as_mut() {
+            state.progress.apply(notification);
+        }
+
         Ok(())
     }
 
@@ -123,12 +254,19 @@ impl AsyncPush {
 
     fn set_result(
         arc_result: Arc<Mutex<Option<String>>>,
+        arc_cred: Arc<Mutex<Option<BasicAuthCredential>>>,
         res: Result<()>,
     ) -> Result<()> {
         let mut last_res = arc_result.lock()?;
+        let mut missing_cred = arc_cred.lock()?;
+        *missing_cred = None;
 
         *last_res = match res {
             Ok(_) => None,
+            Err(Error::MissingCredential(cred)) => {
+                *missing_cred = Some(cred);
+                None
+            }
             Err(e) => {
                 log::error!("push error: {}", e);
                 Some(e.to_string())
@@ -138,3 +276,32 @@ impl AsyncPush {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_apply() {
+        let mut progress = PushProgress::default();
+
+        progress.apply(ProgressNotification::PackingAddingObject {
+            current: 1,
+            total: 4,
+        });
+
+        assert_eq!(progress.state, PushProgressState::Packing);
+        assert_eq!(progress.packed, (1, 4));
+
+        progress.apply(ProgressNotification::PushTransfer {
+            current: 2,
+            total: 4,
+            bytes: 100,
+        });
+
+        assert_eq!(progress.state, PushProgressState::Pushing);
+        assert_eq!(progress.packed, (1, 4));
+        assert_eq!(progress.pushed, (2, 4));
+        assert_eq!(progress.bytes, 100);
+    }
+}
@@ -0,0 +1,166 @@
+//! sync git api for blaming files
+
+use super::{utils, CommitId};
+use crate::error::{Error, Result};
+use git2::BlameOptions;
+use scopetime::scope_time;
+use std::path::Path;
+
+/// a line of a blamed file
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlameLine {
+    /// commit that last changed this line
+    pub commit_id: CommitId,
+    /// author name of that commit
+    pub author: String,
+    /// author time in secs since Unix epoch
+    pub time: i64,
+    /// line content (without line ending)
+    pub content: String,
+}
+
+/// blame of a whole file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileBlame {
+    /// commit the file was blamed at
+    pub commit_id: CommitId,
+    ///
+    pub path: String,
+    ///
+    pub lines: Vec<BlameLine>,
+}
+
+/// blames `file_path` as of `commit_id` (or HEAD if `None`)
+pub fn blame_file(
+    repo_path: &str,
+    file_path: &str,
+    commit_id: Option<CommitId>,
+) -> Result<FileBlame> {
+    scope_time!("blame_file");
+
+    let repo = utils::repo(repo_path)?;
+
+    let commit_id = match commit_id {
+        Some(id) => id,
+        None => utils::get_head_repo(&repo)?,
+    };
+
+    let commit = repo.find_commit(commit_id.into())?;
+    let blob = commit
+        .tree()?
+        .get_path(Path::new(file_path))?
+        .to_object(&repo)?
+        .peel_to_blob()?;
+
+    if blob.is_binary() {
+        return Err(Error::Generic(
+            "cannot blame binary file".to_string(),
+        ));
+    }
+
+    let mut opts = BlameOptions::new();
+    opts.newest_commit(commit_id.into());
+
+    let blame =
+        repo.blame_file(Path::new(file_path), Some(&mut opts))?;
+
+    let content = String::from_utf8_lossy(blob.content());
+
+    let lines = content
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            let hunk = blame.get_line(idx + 1).ok_or_else(|| {
+                Error::Generic(format!("no blame for line {}", idx))
+            })?;
+            let signature = hunk.final_signature();
+
+            Ok(BlameLine {
+                commit_id: hunk.final_commit_id().into(),
+                author: signature.name().unwrap_or("").to_string(),
+                time: signature.when().seconds(),
+                content: line.trim_end_matches('\r').to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(FileBlame {
+        commit_id,
+        path: file_path.to_string(),
+        lines,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{commit, stage_add_file, tests::repo_init};
+    use std::{fs::File, io::Write};
+
+    fn commit_content(
+        repo_path: &str,
+        content: &str,
+        msg: &str,
+    ) -> CommitId {
+        let root = Path::new(repo_path);
+        File::create(root.join("foo.txt"))
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+        stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+        commit(repo_path, msg).unwrap()
+    }
+
+    #[test]
+    fn test_blame() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = commit_content(repo_path, "a\nb\n", "first");
+        let second = commit_content(repo_path, "a\nB\nc\n", "second");
+
+        let blame = blame_file(repo_path, "foo.txt", None).unwrap();
+
+        assert_eq!(blame.commit_id, second);
+        assert_eq!(
+            blame
+                .lines
+                .iter()
+                .map(|l| (l.commit_id, l.content.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(first, "a"), (second, "B"), (second, "c")]
+        );
+        assert_eq!(blame.lines[0].author, "name");
+    }
+
+    #[test]
+    fn test_blame_at_commit() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = commit_content(repo_path, "a\nb\n", "first");
+        commit_content(repo_path, "a\nB\nc\n", "second");
+
+        let blame =
+            blame_file(repo_path, "foo.txt", Some(first)).unwrap();
+
+        assert_eq!(blame.lines.len(), 2);
+        assert!(blame.lines.iter().all(|l| l.commit_id == first));
+    }
+
+    #[test]
+    fn test_blame_untracked() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join("bar.txt"))
+            .unwrap()
+            .write_all(b"untracked")
+            .unwrap();
+
+        assert!(blame_file(repo_path, "bar.txt", None).is_err());
+    }
+}
@@ -2,10 +2,11 @@
 
 use crate::{
     error::{Error, Result},
-    sync::utils,
+    sync::{commits_info::get_message, utils, CommitId},
 };
+use git2::{build::CheckoutBuilder, Branch, BranchType, Repository};
 use scopetime::scope_time;
-use utils::get_head_repo;
+use utils::{get_head_repo, has_uncommitted_changes};
 
 /// returns the branch-name head is currently pointing to
 /// this might be expensive, see `cached::BranchName`
@@ -35,9 +36,54 @@ pub fn create_branch(repo_path: &str, name: &str) -> Result<()> {
     let repo = utils::repo(repo_path)?;
 
     let head_id = get_head_repo(&repo)?;
-    let head_commit = repo.find_commit(head_id.into())?;
 
-    let branch = repo.branch(name, &head_commit, false)?;
+    create_branch_repo(&repo, name, head_id, false)
+}
+
+/// creates a new branch pointing to commit `id` and checks it out.
+/// unless `id` is the HEAD commit this requires a clean working dir
+pub fn create_branch_at(
+    repo_path: &str,
+    name: &str,
+    id: CommitId,
+) -> Result<()> {
+    scope_time!("create_branch_at");
+
+    let repo = utils::repo(repo_path)?;
+
+    let needs_checkout = id != get_head_repo(&repo)?;
+
+    if needs_checkout && has_uncommitted_changes(&repo)? {
+        return Err(Error::Generic(
+            "cannot create branch: there are uncommitted changes"
+                .to_string(),
+        ));
+    }
+
+    create_branch_repo(&repo, name, id, needs_checkout)
+}
+
+fn create_branch_repo(
+    repo: &Repository,
+    name: &str,
+    id: CommitId,
+    checkout: bool,
+) -> Result<()> {
+    let commit = repo.find_commit(id.into())?;
+
+    let mut branch = repo.branch(name, &commit, false)?;
+
+    if checkout {
+        if let Err(e) = repo.checkout_tree(
+            commit.as_object(),
+            Some(CheckoutBuilder::new().safe()),
+        ) {
+            // do not leave a branch behind that was never checked out
+            branch.delete()?;
+            return Err(Error::Git(e));
+        }
+    }
+
     let branch_ref = branch.into_reference();
     let branch_ref_name =
         String::from_utf8(branch_ref.name_bytes().to_vec())?;
@@ -46,6 +92,197 @@ pub fn create_branch(repo_path: &str, name: &str) -> Result<()> {
     Ok(())
 }
 
+/// checks out commit `id` as a detached HEAD, requires a clean
+/// working dir
+pub fn checkout_commit(repo_path: &str, id: CommitId) -> Result<()> {
+    scope_time!("checkout_commit");
+
+    let repo = utils::repo(repo_path)?;
+
+    if has_uncommitted_changes(&repo)? {
+        return Err(Error::Generic(
+            "cannot checkout commit: there are uncommitted changes"
+                .to_string(),
+        ));
+    }
+
+    let commit = repo.find_commit(id.into())?;
+
+    repo.checkout_tree(
+        commit.as_object(),
+        Some(CheckoutBuilder::new().safe()),
+    )?;
+    repo.set_head_detached(commit.id())?;
+
+    Ok(())
+}
+
+/// how far a local branch is ahead/behind of its upstream
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BranchCompare {
+    ///
+    pub ahead: usize,
+    ///
+    pub behind: usize,
+}
+
+///
+#[derive(Debug, Clone)]
+pub struct BranchInfo {
+    /// short name (`master`)
+    pub name: String,
+    /// full reference (`refs/heads/master`)
+    pub reference: String,
+    /// first line of the message of the branch tip
+    pub top_commit_message: String,
+    ///
+    pub top_commit: CommitId,
+    /// true if HEAD points to this branch
+    pub is_head: bool,
+    /// short name of the upstream branch (`origin/master`) if any
+    pub upstream: Option<String>,
+    /// tip of the upstream branch `compare` is relative to
+    pub upstream_top: Option<CommitId>,
+    /// only set if the branch has an upstream and it was asked for
+    pub compare: Option<BranchCompare>,
+}
+
+/// used to limit the commit message we keep per branch
+const TOP_COMMIT_MSG_LIMIT: usize = 100;
+
+/// returns all local branches with their upstream tracking info.
+/// `with_compare` also counts commits ahead/behind of the upstream,
+/// which walks the history and can be expensive
+pub fn get_branches_info(
+    repo_path: &str,
+    with_compare: bool,
+) -> Result<Vec<BranchInfo>> {
+    scope_time!("get_branches_info");
+
+    let repo = utils::repo(repo_path)?;
+
+    let mut branches = Vec::new();
+
+    for b in repo.branches(Some(BranchType::Local))? {
+        let branch = b?.0;
+
+        let top_commit = branch.get().peel_to_commit()?;
+
+        let (upstream, upstream_top, compare) = match branch
+            .upstream()
+        {
+            Ok(upstream) => {
+                let name = bytes2string(upstream.name_bytes()?)?;
+                let upstream_top = upstream.get().target();
+                let compare = match upstream_top {
+                    Some(upstream_id) if with_compare => {
+                        let (ahead, behind) = repo
+                            .graph_ahead_behind(
+                                top_commit.id(),
+                                upstream_id,
+                            )?;
+                        Some(BranchCompare { ahead, behind })
+                    }
+                    _ => None,
+                };
+                (Some(name), upstream_top.map(CommitId::new), compare)
+            }
+            Err(_) => (None, None, None),
+        };
+
+        branches.push(BranchInfo {
+            name: bytes2string(branch.name_bytes()?)?,
+            reference: bytes2string(branch.get().name_bytes())?,
+            top_commit_message: get_message(
+                &top_commit,
+                Some(TOP_COMMIT_MSG_LIMIT),
+            ),
+            top_commit: top_commit.id().into(),
+            is_head: branch.is_head(),
+            upstream,
+            upstream_top,
+            compare,
+        });
+    }
+
+    Ok(branches)
+}
+
+/// switches HEAD to the local branch `branch_ref` (`refs/heads/...`)
+/// and updates the workdir accordingly.
+/// refuses to do so if there are local (staged or unstaged) changes.
+pub fn checkout_branch(
+    repo_path: &str,
+    branch_ref: &str,
+) -> Result<()> {
+    scope_time!("checkout_branch");
+
+    let repo = utils::repo(repo_path)?;
+
+    if has_uncommitted_changes(&repo)? {
+        return Err(Error::Generic(
+            "cannot checkout branch: there are uncommitted changes"
+                .to_string(),
+        ));
+    }
+
+    let target = repo.find_reference(branch_ref)?.peel_to_commit()?;
+
+    // update the workdir before moving HEAD: if the checkout fails
+    // HEAD (a branch or a detached commit) stays untouched
+    repo.checkout_tree(
+        target.as_object(),
+        Some(CheckoutBuilder::new().safe()),
+    )?;
+    repo.set_head(branch_ref)?;
+
+    Ok(())
+}
+
+/// renames the local branch `branch_ref` to `new_name`
+pub fn rename_branch(
+    repo_path: &str,
+    branch_ref: &str,
+    new_name: &str,
+) -> Result<()> {
+    scope_time!("rename_branch");
+
+    let repo = utils::repo(repo_path)?;
+
+    let mut branch = Branch::wrap(repo.find_reference(branch_ref)?);
+
+    branch.rename(new_name, false)?;
+
+    Ok(())
+}
+
+/// deletes the local branch `branch_ref`, fails if it is the current HEAD
+pub fn delete_branch(
+    repo_path: &str,
+    branch_ref: &str,
+) -> Result<()> {
+    scope_time!("delete_branch");
+
+    let repo = utils::repo(repo_path)?;
+
+    let mut branch = Branch::wrap(repo.find_reference(branch_ref)?);
+
+    if branch.is_head() {
+        return Err(Error::Generic(
+            "cannot delete the currently checked out branch"
+                .to_string(),
+        ));
+    }
+
+    branch.delete()?;
+
+    Ok(())
+}
+
+fn bytes2string(bytes: &[u8]) -> Result<String> {
+    Ok(String::from_utf8(bytes.to_vec())?)
+}
+
 #[cfg(test)]
 mod tests_branch_name {
     use super::*;
@@ -79,7 +316,8 @@ mod tests_branch_name {
 #[cfg(test)]
 mod tests_create_branch {
     use super::*;
-    use crate::sync::tests::repo_init;
+    use crate::sync::{commit, stage_add_file, tests::repo_init};
+    use std::{fs::File, io::Write, path::Path};
 
     #[test]
     fn test_smoke() {
@@ -94,4 +332,343 @@ mod tests_create_branch {
             "branch1"
         );
     }
+
+    #[test]
+    fn test_create_at_commit() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = get_head_repo(&repo).unwrap();
+
+        File::create(&root.join("file.txt"))
+            .unwrap()
+            .write_all(b"a")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("file.txt")).unwrap();
+        commit(repo_path, "add file").unwrap();
+
+        create_branch_at(repo_path, "branch1", first).unwrap();
+
+        assert_eq!(
+            get_branch_name(repo_path).unwrap().as_str(),
+            "branch1"
+        );
+        assert_eq!(get_head_repo(&repo).unwrap(), first);
+        assert!(!root.join("file.txt").exists());
+    }
+
+    #[test]
+    fn test_create_at_commit_dirty() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = get_head_repo(&repo).unwrap();
+
+        File::create(&root.join("file.txt"))
+            .unwrap()
+            .write_all(b"a")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("file.txt")).unwrap();
+        commit(repo_path, "add file").unwrap();
+
+        File::create(&root.join("file.txt"))
+            .unwrap()
+            .write_all(b"b")
+            .unwrap();
+
+        assert!(
+            create_branch_at(repo_path, "branch1", first).is_err()
+        );
+        assert_eq!(
+            get_branch_name(repo_path).unwrap().as_str(),
+            "master"
+        );
+        assert!(repo
+            .find_branch("branch1", BranchType::Local)
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod tests_checkout_commit {
+    use super::*;
+    use crate::sync::{commit, stage_add_file, tests::repo_init};
+    use std::{fs::File, io::Write, path::Path};
+
+    #[test]
+    fn test_smoke() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = get_head_repo(&repo).unwrap();
+
+        File::create(&root.join("file.txt"))
+            .unwrap()
+            .write_all(b"a")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("file.txt")).unwrap();
+        commit(repo_path, "add file").unwrap();
+
+        checkout_commit(repo_path, first).unwrap();
+
+        assert!(repo.head_detached().unwrap());
+        assert_eq!(get_head_repo(&repo).unwrap(), first);
+        assert!(!root.join("file.txt").exists());
+    }
+}
+
+#[cfg(test)]
+mod tests_branches_info {
+    use super::*;
+    use crate::sync::tests::repo_init;
+
+    #[test]
+    fn test_smoke() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let branches = get_branches_info(repo_path, true).unwrap();
+
+        assert_eq!(branches.len(), 1);
+        assert_eq!(branches[0].name.as_str(), "master");
+        assert_eq!(
+            branches[0].reference.as_str(),
+            "refs/heads/master"
+        );
+        assert_eq!(
+            branches[0].top_commit_message.as_str(),
+            "initial"
+        );
+        assert!(branches[0].is_head);
+        assert_eq!(branches[0].upstream, None);
+        assert_eq!(branches[0].compare, None);
+    }
+
+    #[test]
+    fn test_multiple() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        create_branch(repo_path, "test").unwrap();
+
+        let branches = get_branches_info(repo_path, true).unwrap();
+
+        assert_eq!(
+            branches
+                .iter()
+                .map(|b| (b.name.as_str(), b.is_head))
+                .collect::<Vec<_>>(),
+            vec![("master", false), ("test", true)]
+        );
+    }
+
+    #[test]
+    fn test_upstream_compare() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        create_branch(repo_path, "feature").unwrap();
+        repo.find_branch("feature", BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("master"))
+            .unwrap();
+
+        let sig = repo.signature().unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            "on feature",
+            &head.tree().unwrap(),
+            &[&head],
+        )
+        .unwrap();
+
+        let branches = get_branches_info(repo_path, true).unwrap();
+        let feature =
+            branches.iter().find(|b| b.name == "feature").unwrap();
+
+        assert_eq!(feature.upstream, Some(String::from("master")));
+        assert_eq!(feature.upstream_top, Some(head.id().into()));
+        assert_eq!(
+            feature.compare,
+            Some(BranchCompare {
+                ahead: 1,
+                behind: 0
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_checkout_branch {
+    use super::*;
+    use crate::sync::{commit, stage_add_file, tests::repo_init};
+    use std::{fs::File, io::Write, path::Path};
+
+    #[test]
+    fn test_smoke() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        create_branch(repo_path, "test").unwrap();
+
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+
+        assert_eq!(
+            get_branch_name(repo_path).unwrap().as_str(),
+            "master"
+        );
+    }
+
+    #[test]
+    fn test_fails_with_changes() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let file_path = Path::new("foo.txt");
+        File::create(&root.join(file_path))
+            .unwrap()
+            .write_all(b"test")
+            .unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(file_path).unwrap();
+        index.write().unwrap();
+
+        create_branch(repo_path, "test").unwrap();
+
+        assert!(
+            checkout_branch(repo_path, "refs/heads/master").is_err()
+        );
+        assert_eq!(
+            get_branch_name(repo_path).unwrap().as_str(),
+            "test"
+        );
+    }
+
+    #[test]
+    fn test_updates_workdir() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        create_branch(repo_path, "test").unwrap();
+        let file_path = Path::new("foo.txt");
+        File::create(&root.join(file_path))
+            .unwrap()
+            .write_all(b"test")
+            .unwrap();
+        stage_add_file(repo_path, file_path).unwrap();
+        commit(repo_path, "add foo").unwrap();
+
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+        assert!(!root.join(file_path).exists());
+
+        checkout_branch(repo_path, "refs/heads/test").unwrap();
+        assert!(root.join(file_path).exists());
+    }
+
+    #[test]
+    fn test_fails_on_detached_head() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let initial = repo.head().unwrap().target().unwrap();
+
+        create_branch(repo_path, "test").unwrap();
+        let file_path = Path::new("foo.txt");
+        File::create(&root.join(file_path))
+            .unwrap()
+            .write_all(b"test")
+            .unwrap();
+        stage_add_file(repo_path, file_path).unwrap();
+        commit(repo_path, "add foo").unwrap();
+
+        repo.set_head_detached(initial).unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+
+        // untracked file the checkout would overwrite
+        File::create(&root.join(file_path))
+            .unwrap()
+            .write_all(b"untracked")
+            .unwrap();
+
+        assert!(
+            checkout_branch(repo_path, "refs/heads/test").is_err()
+        );
+        assert!(repo.head_detached().unwrap());
+        assert_eq!(repo.head().unwrap().target(), Some(initial));
+    }
+}
+
+#[cfg(test)]
+mod tests_rename_branch {
+    use super::*;
+    use crate::sync::tests::repo_init;
+
+    #[test]
+    fn test_smoke() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        create_branch(repo_path, "branch1").unwrap();
+
+        rename_branch(repo_path, "refs/heads/branch1", "AnotherName")
+            .unwrap();
+
+        assert_eq!(
+            get_branch_name(repo_path).unwrap().as_str(),
+            "AnotherName"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_delete_branch {
+    use super::*;
+    use crate::sync::tests::repo_init;
+
+    #[test]
+    fn test_smoke() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        create_branch(repo_path, "branch1").unwrap();
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+
+        delete_branch(repo_path, "refs/heads/branch1").unwrap();
+
+        assert_eq!(
+            get_branches_info(repo_path, true).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_refuses_head() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert!(
+            delete_branch(repo_path, "refs/heads/master").is_err()
+        );
+        assert_eq!(
+            get_branches_info(repo_path, true).unwrap().len(),
+            1
+        );
+    }
 }
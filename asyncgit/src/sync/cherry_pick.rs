@@ -0,0 +1,281 @@
+//! applying (or reverting) the changes of single commits onto HEAD
+
+use super::{
+    commit::signature_allow_undefined_name,
+    commits_info::get_message,
+    merge::conflicted_paths,
+    utils::{self, has_uncommitted_changes},
+    CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{build::CheckoutBuilder, Commit, Repository, Tree};
+use scopetime::scope_time;
+
+/// applies the changes of commit `id` onto HEAD and commits them
+/// with the original message and author.
+///
+/// nothing is changed if the changes do not apply cleanly, the
+/// conflicting paths are returned as `Error::Conflicts` instead.
+pub fn cherry_pick(
+    repo_path: &str,
+    id: CommitId,
+) -> Result<CommitId> {
+    scope_time!("cherry_pick");
+
+    let repo = utils::repo(repo_path)?;
+    let commit = repo.find_commit(id.into())?;
+
+    let base = parent_tree(&repo, &commit)?;
+    let tree = merge_onto_head(&repo, &base, &commit.tree()?)?;
+
+    let author = commit.author();
+    let committer = signature_allow_undefined_name(&repo)?;
+    let message = get_message(&commit, None);
+
+    commit_onto_head(
+        &repo,
+        &author,
+        &committer,
+        message.as_str(),
+        &tree,
+    )
+}
+
+/// commits the inverse of the changes of commit `id` onto HEAD using
+/// the message `git revert` would generate.
+///
+/// conflicts are reported like in `cherry_pick`.
+pub fn revert_commit(
+    repo_path: &str,
+    id: CommitId,
+) -> Result<CommitId> {
+    scope_time!("revert_commit");
+
+    let repo = utils::repo(repo_path)?;
+    let commit = repo.find_commit(id.into())?;
+
+    let parent = parent_tree(&repo, &commit)?;
+    let tree = merge_onto_head(&repo, &commit.tree()?, &parent)?;
+
+    let message = format!(
+        "Revert \"{}\"\n\nThis reverts commit {}.\n",
+        commit.summary().unwrap_or_default(),
+        commit.id()
+    );
+
+    let signature = signature_allow_undefined_name(&repo)?;
+
+    commit_onto_head(
+        &repo,
+        &signature,
+        &signature,
+        message.as_str(),
+        &tree,
+    )
+}
+
+/// tree of the only parent of `commit` (empty tree for root commits)
+fn parent_tree<'a>(
+    repo: &'a Repository,
+    commit: &Commit,
+) -> Result<Tree<'a>> {
+    match commit.parent_count() {
+        0 => Ok(repo.find_tree(repo.treebuilder(None)?.write()?)?),
+        1 => Ok(repo.find_tree(commit.parent(0)?.tree_id())?),
+        _ => Err(Error::Generic(
+            "merge commits are not supported".to_string(),
+        )),
+    }
+}
+
+/// three-way merges the change from `base` to `theirs` into the HEAD
+/// tree and checks the result out
+fn merge_onto_head<'a>(
+    repo: &'a Repository,
+    base: &Tree,
+    theirs: &Tree,
+) -> Result<Tree<'a>> {
+    if has_uncommitted_changes(repo)? {
+        return Err(Error::Generic(
+            "there are uncommitted changes".to_string(),
+        ));
+    }
+
+    let head_tree = repo.head()?.peel_to_tree()?;
+
+    let mut index =
+        repo.merge_trees(base, &head_tree, theirs, None)?;
+
+    if index.has_conflicts() {
+        return Err(Error::Conflicts(conflicted_paths(&index)?));
+    }
+
+    let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+
+    if tree.id() == head_tree.id() {
+        return Err(Error::Generic(
+            "nothing to commit, the changes are already applied"
+                .to_string(),
+        ));
+    }
+
+    repo.checkout_tree(
+        tree.as_object(),
+        Some(CheckoutBuilder::new().safe()),
+    )?;
+
+    Ok(tree)
+}
+
+fn commit_onto_head(
+    repo: &Repository,
+    author: &git2::Signature,
+    committer: &git2::Signature,
+    message: &str,
+    tree: &Tree,
+) -> Result<CommitId> {
+    let head = repo.head()?.peel_to_commit()?;
+
+    Ok(repo
+        .commit(
+            Some("HEAD"),
+            author,
+            committer,
+            message,
+            tree,
+            &[&head],
+        )?
+        .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, get_commit_details, stage_add_file, tests::repo_init,
+        utils::get_head_repo,
+    };
+    use git2::ResetType;
+    use std::{
+        fs::{self, File},
+        io::Write,
+        path::Path,
+    };
+
+    fn commit_file(
+        repo_path: &str,
+        file: &str,
+        content: &str,
+        msg: &str,
+    ) -> CommitId {
+        let root = Path::new(repo_path);
+        File::create(&root.join(file))
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+        stage_add_file(repo_path, Path::new(file)).unwrap();
+        commit(repo_path, msg).unwrap()
+    }
+
+    fn reset_hard(repo: &Repository, id: CommitId) {
+        let commit = repo.find_commit(id.into()).unwrap();
+        repo.reset(commit.as_object(), ResetType::Hard, None)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_cherry_pick() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = commit_file(repo_path, "a.txt", "a", "add a");
+        let second = commit_file(repo_path, "b.txt", "b", "add b");
+
+        reset_hard(&repo, first);
+        assert!(!root.join("b.txt").exists());
+
+        let picked = cherry_pick(repo_path, second).unwrap();
+
+        assert_eq!(get_head_repo(&repo).unwrap(), picked);
+        assert_eq!(
+            fs::read_to_string(root.join("b.txt")).unwrap(),
+            "b"
+        );
+
+        let details = get_commit_details(repo_path, picked).unwrap();
+        assert_eq!(details.message.unwrap().subject, "add b");
+
+        let picked = repo.find_commit(picked.into()).unwrap();
+        assert_eq!(picked.parent_id(0).unwrap(), first.into());
+    }
+
+    #[test]
+    fn test_cherry_pick_conflict() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = commit_file(repo_path, "a.txt", "a", "add a");
+        let second = commit_file(repo_path, "a.txt", "b", "change a");
+
+        reset_hard(&repo, first);
+        let third = commit_file(repo_path, "a.txt", "c", "change a");
+
+        let res = cherry_pick(repo_path, second);
+
+        assert!(matches!(
+            res,
+            Err(Error::Conflicts(paths)) if paths == vec!["a.txt"]
+        ));
+        assert_eq!(get_head_repo(&repo).unwrap(), third);
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            "c"
+        );
+    }
+
+    #[test]
+    fn test_revert() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        commit_file(repo_path, "a.txt", "a", "add a");
+        let second = commit_file(repo_path, "a.txt", "b", "change a");
+        commit_file(repo_path, "b.txt", "b", "add b");
+
+        let reverted = revert_commit(repo_path, second).unwrap();
+
+        assert_eq!(get_head_repo(&repo).unwrap(), reverted);
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            "a"
+        );
+        assert!(root.join("b.txt").exists());
+
+        let details =
+            get_commit_details(repo_path, reverted).unwrap();
+        assert_eq!(
+            details.message.unwrap().subject,
+            "Revert \"change a\""
+        );
+    }
+
+    #[test]
+    fn test_revert_dirty() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = commit_file(repo_path, "a.txt", "a", "add a");
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"changed")
+            .unwrap();
+
+        assert!(revert_commit(repo_path, first).is_err());
+        assert_eq!(get_head_repo(&repo).unwrap(), first);
+    }
+}
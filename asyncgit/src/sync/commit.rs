@@ -1,7 +1,16 @@
-use super::{get_head, utils::repo, CommitId};
-use crate::error::Result;
-use git2::{ErrorCode, ObjectType, Repository, Signature};
+use super::{
+    get_head,
+    sign::{gpg_sign, signature_header},
+    utils::repo,
+    CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{
+    Config, ErrorCode, ObjectType, Oid, Reference, Repository,
+    RepositoryState, Signature,
+};
 use scopetime::scope_time;
+use std::fs;
 
 ///
 pub fn amend(
@@ -30,10 +39,43 @@ pub fn amend(
     Ok(CommitId::new(new_id))
 }
 
+/// contents of the file configured as `commit.template` (a relative
+/// path is resolved against the working dir), `None` if there is none
+pub fn commit_template(repo_path: &str) -> Result<Option<String>> {
+    scope_time!("commit_template");
+
+    let repo = repo(repo_path)?;
+
+    read_commit_template(&repo, &repo.config()?)
+}
+
+fn read_commit_template(
+    repo: &Repository,
+    config: &Config,
+) -> Result<Option<String>> {
+    let path = match config.get_path("commit.template") {
+        Ok(path) => path,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let path =
+        repo.workdir().map_or(path.clone(), |wd| wd.join(&path));
+
+    let template = fs::read_to_string(&path).map_err(|e| {
+        Error::Generic(format!(
+            "could not read commit template '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    Ok(Some(template))
+}
+
 /// Wrap Repository::signature to allow unknown user.name.
 ///
 /// See <https://github.com/extrawurst/gitui/issues/79>.
-fn signature_allow_undefined_name(
+pub(crate) fn signature_allow_undefined_name(
     repo: &Repository,
 ) -> std::result::Result<Signature<'_>, git2::Error> {
     match repo.signature() {
@@ -49,7 +91,9 @@ fn signature_allow_undefined_name(
     }
 }
 
-/// this does not run any git hooks
+/// this does not run any git hooks.
+/// if a merge is in progress the merged commit (`MERGE_HEAD`) becomes
+/// the second parent and the merge state is cleaned up afterwards
 pub fn commit(repo_path: &str, msg: &str) -> Result<CommitId> {
     scope_time!("commit");
 
@@ -60,27 +104,38 @@ pub fn commit(repo_path: &str, msg: &str) -> Result<CommitId> {
     let tree_id = index.write_tree()?;
     let tree = repo.find_tree(tree_id)?;
 
-    let parents = if let Ok(id) = get_head(repo_path) {
+    let mut parents = if let Ok(id) = get_head(repo_path) {
         vec![repo.find_commit(id.into())?]
     } else {
         Vec::new()
     };
 
+    let merging = repo.state() == RepositoryState::Merge;
+    if merging {
+        if let Ok(merge_head) = repo.find_reference("MERGE_HEAD") {
+            parents.push(merge_head.peel_to_commit()?);
+        }
+    }
+
     let parents = parents.iter().collect::<Vec<_>>();
 
-    Ok(repo
-        .commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            msg,
-            &tree,
-            parents.as_slice(),
-        )?
-        .into())
+    let id = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        msg,
+        &tree,
+        parents.as_slice(),
+    )?;
+
+    if merging {
+        repo.cleanup_state()?;
+    }
+
+    Ok(id.into())
 }
 
-/// Tag a commit.
+/// Tag a commit (lightweight tag).
 ///
 /// This function will return an `Err(…)` variant if the tag’s name is refused
 /// by git or if the tag already exists.
@@ -93,12 +148,85 @@ pub fn tag(
 
     let repo = repo(repo_path)?;
 
+    let object_id = commit_id.get_oid();
+    let target =
+        repo.find_object(object_id, Some(ObjectType::Commit))?;
+
+    Ok(repo.tag_lightweight(tag, &target, false)?.into())
+}
+
+/// Tag a commit with an annotated tag carrying `message`, the tag is
+/// signed with gpg if `tag.gpgSign` is set (like `git tag -a`).
+///
+/// Errors like `tag` does, returns the id of the tag object.
+pub fn tag_annotated(
+    repo_path: &str,
+    commit_id: &CommitId,
+    tag: &str,
+    message: &str,
+) -> Result<CommitId> {
+    scope_time!("tag_annotated");
+
+    let repo = repo(repo_path)?;
+
     let signature = signature_allow_undefined_name(&repo)?;
     let object_id = commit_id.get_oid();
     let target =
         repo.find_object(object_id, Some(ObjectType::Commit))?;
 
-    Ok(repo.tag(tag, &target, &signature, "", false)?.into())
+    let message = format!("{}\n", message.trim_end());
+
+    if !gpg_sign_tags(&repo)? {
+        return Ok(repo
+            .tag(tag, &target, &signature, &message, false)?
+            .into());
+    }
+
+    let reference = format!("refs/tags/{}", tag);
+    if !Reference::is_valid_name(&reference) {
+        return Err(Error::Generic(format!(
+            "invalid tag name: {}",
+            tag
+        )));
+    }
+
+    let mut buffer = tag_buffer(object_id, tag, &signature, &message);
+    buffer.push_str(&gpg_sign(&repo, &signature, &buffer)?);
+
+    let id = repo.odb()?.write(ObjectType::Tag, buffer.as_bytes())?;
+    repo.reference(&reference, id, false, "tag: signed")?;
+
+    Ok(id.into())
+}
+
+/// whether `tag_annotated` signs the tag with gpg (`tag.gpgSign`),
+/// gpg might ask for a passphrase on the terminal then
+pub fn tag_gpg_sign(repo_path: &str) -> Result<bool> {
+    scope_time!("tag_gpg_sign");
+
+    let repo = repo(repo_path)?;
+
+    gpg_sign_tags(&repo)
+}
+
+fn gpg_sign_tags(repo: &Repository) -> Result<bool> {
+    Ok(repo.config()?.get_bool("tag.gpgSign").unwrap_or(false))
+}
+
+/// raw content of a tag object (what gets signed)
+fn tag_buffer(
+    target: Oid,
+    tag: &str,
+    tagger: &Signature,
+    message: &str,
+) -> String {
+    format!(
+        "object {}\ntype commit\ntag {}\ntagger {}\n\n{}",
+        target,
+        tag,
+        signature_header(tagger),
+        message
+    )
 }
 
 #[cfg(test)]
@@ -112,9 +240,16 @@ mod tests {
         utils::get_head,
         LogWalker,
     };
-    use commit::{amend, tag};
-    use git2::Repository;
-    use std::{fs::File, io::Write, path::Path};
+    use commit::{
+        amend, commit_template, read_commit_template, tag,
+        tag_annotated, tag_buffer, tag_gpg_sign,
+    };
+    use git2::{Config, Repository};
+    use std::{
+        fs::{self, File},
+        io::Write,
+        path::Path,
+    };
 
     fn count_commits(repo: &Repository, max: usize) -> usize {
         let mut items = Vec::new();
@@ -197,7 +332,7 @@ mod tests {
         let details = get_commit_details(repo_path, new_id)?;
         assert_eq!(details.message.unwrap().subject, "amended");
 
-        let files = get_commit_files(repo_path, new_id)?;
+        let files = get_commit_files(repo_path, new_id, None)?;
 
         assert_eq!(files.len(), 2);
 
@@ -245,4 +380,136 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_tag_annotated() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        // no matter what the user configured globally
+        repo.config()?.set_bool("tag.gpgSign", false)?;
+        assert!(!tag_gpg_sign(repo_path)?);
+
+        let head = get_head(repo_path)?;
+
+        let id =
+            tag_annotated(repo_path, &head, "v1.0", "release\n\n")?;
+
+        let tag = repo.find_tag(id.into())?;
+        assert_eq!(tag.name(), Some("v1.0"));
+        assert_eq!(tag.message(), Some("release\n"));
+        assert_eq!(tag.target_id(), head.into());
+
+        assert_eq!(get_tags(repo_path)?[&head], vec!["v1.0"]);
+
+        assert!(tag_annotated(repo_path, &head, "v1.0", "x").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_tag_annotated_signed() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        // stands in for gpg, "signs" with the key it was given
+        let gpg = repo.path().join("fake-gpg");
+        File::create(&gpg)?.write_all(
+            b"#!/bin/sh\n\
+            cat > /dev/null\n\
+            echo '[GNUPG:] SIG_CREATED D' >&2\n\
+            printf -- '-----BEGIN PGP SIGNATURE-----\\n%s\\n\
+            -----END PGP SIGNATURE-----\\n' \"$3\"\n",
+        )?;
+        fs::set_permissions(&gpg, fs::Permissions::from_mode(0o755))?;
+
+        let mut config = repo.config()?;
+        config.set_bool("tag.gpgSign", true)?;
+        config.set_str("gpg.program", "false")?;
+        config.set_str("user.signingKey", "ABCD1234")?;
+        assert!(tag_gpg_sign(repo_path)?);
+
+        let head = get_head(repo_path)?;
+
+        // no unsigned tag if gpg fails
+        assert!(tag_annotated(repo_path, &head, "v1.0", "x").is_err());
+        assert!(get_tags(repo_path)?.is_empty());
+
+        config.set_str("gpg.program", gpg.to_str().unwrap())?;
+
+        let id = tag_annotated(repo_path, &head, "v1.0", "release")?;
+
+        let tag = repo.find_tag(id.into())?;
+        assert_eq!(tag.name(), Some("v1.0"));
+        assert_eq!(tag.target_id(), head.into());
+        assert_eq!(
+            tag.message(),
+            Some(
+                "release\n-----BEGIN PGP SIGNATURE-----\nABCD1234\n\
+                -----END PGP SIGNATURE-----\n"
+            )
+        );
+
+        assert_eq!(get_tags(repo_path)?[&head], vec!["v1.0"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_buffer() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let head = get_head(repo_path)?;
+        let sig = repo.signature()?;
+
+        // the buffer that gets signed has to be a valid tag object
+        let buffer = tag_buffer(head.into(), "v2", &sig, "msg\n");
+        let id = repo
+            .odb()?
+            .write(git2::ObjectType::Tag, buffer.as_bytes())?;
+
+        let tag = repo.find_tag(id)?;
+        assert_eq!(tag.name(), Some("v2"));
+        assert_eq!(tag.message(), Some("msg\n"));
+        assert_eq!(tag.target_id(), head.into());
+
+        let tagger = tag.tagger().unwrap();
+        assert_eq!(tagger.name(), sig.name());
+        assert_eq!(tagger.when(), sig.when());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_template() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        // only the config of the repo, not the one of the user
+        let mut config = Config::open(&repo.path().join("config"))?;
+
+        assert_eq!(read_commit_template(&repo, &config)?, None);
+
+        config.set_str("commit.template", "template.txt")?;
+        assert!(read_commit_template(&repo, &config).is_err());
+        assert!(commit_template(repo_path).is_err());
+
+        File::create(&root.join("template.txt"))?
+            .write_all(b"subject\n\n# body")?;
+
+        assert_eq!(
+            read_commit_template(&repo, &config)?,
+            Some(String::from("subject\n\n# body"))
+        );
+
+        Ok(())
+    }
 }
@@ -1,19 +1,63 @@
-use super::{stash::is_stash_commit, utils::repo, CommitId};
+use super::{
+    diff::DiffOptions, stash::is_stash_commit, utils::repo, CommitId,
+};
 use crate::{error::Result, StatusItem, StatusItemType};
-use git2::{Diff, DiffDelta, DiffOptions, Repository};
+use git2::{
+    Delta, Diff, DiffDelta, DiffFindOptions,
+    DiffOptions as RawDiffOptions, Repository, Tree,
+};
 use scopetime::scope_time;
+use std::path::Path;
 
-/// get all files that are part of a commit
+/// get all files that are part of a commit (renamed files are
+/// listed once under their new path if `options` ask to find renames)
 pub fn get_commit_files(
     repo_path: &str,
     id: CommitId,
+    options: Option<DiffOptions>,
 ) -> Result<Vec<StatusItem>> {
     scope_time!("get_commit_files");
 
     let repo = repo(repo_path)?;
 
-    let diff = get_commit_diff(&repo, id, None)?;
+    let diff = get_commit_diff(&repo, id, None, options)?;
 
+    diff_status_items(&diff)
+}
+
+/// get all files that changed between two commits (from `ids.0` to
+/// `ids.1`)
+pub fn get_compare_commits_files(
+    repo_path: &str,
+    ids: (CommitId, CommitId),
+    options: Option<DiffOptions>,
+) -> Result<Vec<StatusItem>> {
+    scope_time!("get_compare_commits_files");
+
+    let repo = repo(repo_path)?;
+
+    let diff = get_compare_commits_diff(&repo, ids, None, options)?;
+
+    diff_status_items(&diff)
+}
+
+/// get all tracked files that changed between a commit and the
+/// working dir (like `git diff <commit>`)
+pub fn get_compare_workdir_files(
+    repo_path: &str,
+    id: CommitId,
+    options: Option<DiffOptions>,
+) -> Result<Vec<StatusItem>> {
+    scope_time!("get_compare_workdir_files");
+
+    let repo = repo(repo_path)?;
+
+    let diff = get_compare_workdir_diff(&repo, id, None, options)?;
+
+    diff_status_items(&diff)
+}
+
+fn diff_status_items(diff: &Diff) -> Result<Vec<StatusItem>> {
     let mut res = Vec::new();
 
     diff.foreach(
@@ -41,6 +85,7 @@ pub(crate) fn get_commit_diff(
     repo: &Repository,
     id: CommitId,
     pathspec: Option<String>,
+    options: Option<DiffOptions>,
 ) -> Result<Diff<'_>> {
     // scope_time!("get_commit_diff");
 
@@ -52,19 +97,35 @@ pub(crate) fn get_commit_diff(
         None
     };
 
-    let mut opt = pathspec.as_ref().map(|p| {
-        let mut opts = DiffOptions::new();
-        opts.pathspec(p);
-        opts.show_binary(true);
-        opts
-    });
+    let find_renames = options.map_or(false, |o| o.find_renames);
+
+    // the rename can only be found if the old path is diffed as well
+    let renamed_from = match &pathspec {
+        Some(p) if find_renames => find_rename_source(
+            repo,
+            parent.as_ref(),
+            &commit_tree,
+            p,
+        )?,
+        _ => None,
+    };
+
+    let mut opts = raw_options(
+        pathspec.as_ref(),
+        renamed_from.as_ref(),
+        options,
+    );
 
     let mut diff = repo.diff_tree_to_tree(
         parent.as_ref(),
         Some(&commit_tree),
-        opt.as_mut(),
+        Some(&mut opts),
     )?;
 
+    if find_renames {
+        diff.find_similar(Some(&mut rename_options()))?;
+    }
+
     if is_stash_commit(
         repo.path().to_str().expect("repo path utf8 err"),
         &id,
@@ -74,6 +135,7 @@ pub(crate) fn get_commit_diff(
                 repo,
                 CommitId::new(untracked_commit),
                 pathspec,
+                options,
             )?;
 
             diff.merge(&untracked_diff)?;
@@ -83,13 +145,129 @@ pub(crate) fn get_commit_diff(
     Ok(diff)
 }
 
+///
+pub(crate) fn get_compare_commits_diff(
+    repo: &Repository,
+    ids: (CommitId, CommitId),
+    pathspec: Option<String>,
+    options: Option<DiffOptions>,
+) -> Result<Diff<'_>> {
+    // scope_time!("get_compare_commits_diff");
+
+    let old_tree = repo.find_commit(ids.0.into())?.tree()?;
+    let new_tree = repo.find_commit(ids.1.into())?.tree()?;
+
+    let find_renames = options.map_or(false, |o| o.find_renames);
+
+    let renamed_from = match &pathspec {
+        Some(p) if find_renames => {
+            find_rename_source(repo, Some(&old_tree), &new_tree, p)?
+        }
+        _ => None,
+    };
+
+    let mut opts = raw_options(
+        pathspec.as_ref(),
+        renamed_from.as_ref(),
+        options,
+    );
+
+    let mut diff = repo.diff_tree_to_tree(
+        Some(&old_tree),
+        Some(&new_tree),
+        Some(&mut opts),
+    )?;
+
+    if find_renames {
+        diff.find_similar(Some(&mut rename_options()))?;
+    }
+
+    Ok(diff)
+}
+
+/// untracked files are ignored, renames are not detected
+pub(crate) fn get_compare_workdir_diff(
+    repo: &Repository,
+    id: CommitId,
+    pathspec: Option<String>,
+    options: Option<DiffOptions>,
+) -> Result<Diff<'_>> {
+    // scope_time!("get_compare_workdir_diff");
+
+    let tree = repo.find_commit(id.into())?.tree()?;
+
+    let mut opts = raw_options(pathspec.as_ref(), None, options);
+
+    Ok(repo.diff_tree_to_workdir_with_index(
+        Some(&tree),
+        Some(&mut opts),
+    )?)
+}
+
+fn raw_options(
+    pathspec: Option<&String>,
+    renamed_from: Option<&String>,
+    options: Option<DiffOptions>,
+) -> RawDiffOptions {
+    let mut opts = RawDiffOptions::new();
+    if let Some(options) = options {
+        options.apply(&mut opts);
+    }
+    if let Some(p) = pathspec {
+        opts.pathspec(p);
+        if let Some(old_path) = renamed_from {
+            opts.pathspec(old_path);
+        }
+        opts.show_binary(true);
+    }
+    opts
+}
+
+fn rename_options() -> DiffFindOptions {
+    let mut opts = DiffFindOptions::new();
+    opts.renames(true).copies(true);
+    opts
+}
+
+/// old path of `path` if it was renamed or copied between the trees
+fn find_rename_source(
+    repo: &Repository,
+    old_tree: Option<&Tree>,
+    new_tree: &Tree,
+    path: &str,
+) -> Result<Option<String>> {
+    let mut diff =
+        repo.diff_tree_to_tree(old_tree, Some(new_tree), None)?;
+    diff.find_similar(Some(&mut rename_options()))?;
+
+    Ok(diff
+        .deltas()
+        .find(|delta| {
+            (delta.status() == Delta::Renamed
+                || delta.status() == Delta::Copied)
+                && delta.new_file().path() == Some(Path::new(path))
+        })
+        .and_then(|delta| {
+            delta
+                .old_file()
+                .path()
+                .and_then(Path::to_str)
+                .map(String::from)
+        }))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::get_commit_files;
+    use super::{
+        get_commit_diff, get_commit_files, get_compare_commits_files,
+        get_compare_workdir_files,
+    };
     use crate::{
         error::Result,
         sync::{
-            commit, stage_add_file, stash_save,
+            commit,
+            diff::DiffOptions,
+            stage_add_file, stage_addremoved, stash_save,
             tests::{get_statuses, repo_init},
         },
         StatusItemType,
@@ -110,7 +288,7 @@ mod tests {
 
         let id = commit(repo_path, "commit msg")?;
 
-        let diff = get_commit_files(repo_path, id)?;
+        let diff = get_commit_files(repo_path, id, None)?;
 
         assert_eq!(diff.len(), 1);
         assert_eq!(diff[0].status, StatusItemType::New);
@@ -130,7 +308,7 @@ mod tests {
 
         let id = stash_save(repo_path, None, true, false)?;
 
-        let diff = get_commit_files(repo_path, id)?;
+        let diff = get_commit_files(repo_path, id, None)?;
 
         assert_eq!(diff.len(), 1);
         assert_eq!(diff[0].status, StatusItemType::New);
@@ -158,7 +336,7 @@ mod tests {
 
         let id = stash_save(repo_path, None, true, false)?;
 
-        let diff = get_commit_files(repo_path, id)?;
+        let diff = get_commit_files(repo_path, id, None)?;
 
         assert_eq!(diff.len(), 2);
         assert_eq!(diff[0].status, StatusItemType::Modified);
@@ -166,4 +344,120 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_commit_diff_renames() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("foo.txt"))?
+            .write_all(b"some\nlonger\ncontent\n")?;
+        stage_add_file(repo_path, Path::new("foo.txt"))?;
+        commit(repo_path, "add foo")?;
+
+        std::fs::rename(root.join("foo.txt"), root.join("bar.txt"))?;
+        stage_addremoved(repo_path, Path::new("foo.txt"))?;
+        stage_add_file(repo_path, Path::new("bar.txt"))?;
+        let id = commit(repo_path, "rename foo")?;
+
+        let diff = get_commit_diff(
+            &repo,
+            id,
+            Some(String::from("bar.txt")),
+            None,
+        )?;
+        assert_eq!(diff.deltas().len(), 1);
+        assert_eq!(
+            diff.get_delta(0).unwrap().status(),
+            git2::Delta::Added
+        );
+
+        let options = DiffOptions {
+            find_renames: true,
+            ..DiffOptions::default()
+        };
+        let diff = get_commit_diff(
+            &repo,
+            id,
+            Some(String::from("bar.txt")),
+            Some(options),
+        )?;
+        assert_eq!(diff.deltas().len(), 1);
+        assert_eq!(
+            diff.get_delta(0).unwrap().status(),
+            git2::Delta::Renamed
+        );
+
+        assert_eq!(get_commit_files(repo_path, id, None)?.len(), 2);
+
+        let files = get_commit_files(repo_path, id, Some(options))?;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "bar.txt");
+        assert_eq!(files[0].status, StatusItemType::Renamed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_commits() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("file1.txt"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("file1.txt"))?;
+        let first = commit(repo_path, "commit 1")?;
+
+        File::create(&root.join("file1.txt"))?.write_all(b"b")?;
+        stage_add_file(repo_path, Path::new("file1.txt"))?;
+        commit(repo_path, "commit 2")?;
+
+        File::create(&root.join("file2.txt"))?.write_all(b"c")?;
+        stage_add_file(repo_path, Path::new("file2.txt"))?;
+        let third = commit(repo_path, "commit 3")?;
+
+        let files = get_compare_commits_files(
+            repo_path,
+            (first, third),
+            None,
+        )?;
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "file1.txt");
+        assert_eq!(files[0].status, StatusItemType::Modified);
+        assert_eq!(files[1].path, "file2.txt");
+        assert_eq!(files[1].status, StatusItemType::New);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_workdir() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("file1.txt"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("file1.txt"))?;
+        let first = commit(repo_path, "commit 1")?;
+
+        File::create(&root.join("file2.txt"))?.write_all(b"b")?;
+        stage_add_file(repo_path, Path::new("file2.txt"))?;
+        commit(repo_path, "commit 2")?;
+
+        File::create(&root.join("file1.txt"))?.write_all(b"c")?;
+        File::create(&root.join("untracked.txt"))?.write_all(b"d")?;
+
+        let files =
+            get_compare_workdir_files(repo_path, first, None)?;
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "file1.txt");
+        assert_eq!(files[0].status, StatusItemType::Modified);
+        assert_eq!(files[1].path, "file2.txt");
+        assert_eq!(files[1].status, StatusItemType::New);
+
+        Ok(())
+    }
 }
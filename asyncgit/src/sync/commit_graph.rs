@@ -0,0 +1,337 @@
+//! lanes of the commit graph, computed row by row in log order
+
+use super::CommitId;
+
+/// what a lane shows in the row of a commit
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GraphCell {
+    /// no lane here
+    Empty,
+    /// lane passing by
+    Line,
+    /// the commit of this row
+    Commit,
+    /// new lane starting at the commit for one of its parents
+    Fork,
+    /// lane ending in the commit (it was waiting for it too)
+    Join,
+    /// lane passing by that also continues with a parent of the
+    /// commit
+    Junction,
+    /// horizontal connection through an empty lane
+    Horizontal,
+    /// horizontal connection crossing a passing lane
+    Cross,
+}
+
+impl GraphCell {
+    /// `true` if the cell is part of the horizontal connection to the
+    /// commit of the row
+    pub fn is_connected(self) -> bool {
+        !matches!(self, Self::Empty | Self::Line)
+    }
+}
+
+/// lanes of a single row of the graph
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphRow {
+    cells: Vec<GraphCell>,
+}
+
+impl GraphRow {
+    /// cells left to right, trailing empty lanes are omitted
+    pub fn cells(&self) -> &[GraphCell] {
+        self.cells.as_slice()
+    }
+
+    /// index of the lane of the commit
+    pub fn commit_lane(&self) -> usize {
+        self.cells
+            .iter()
+            .position(|c| *c == GraphCell::Commit)
+            .unwrap_or_default()
+    }
+}
+
+/// lanes of the commit graph, built incrementally by pushing the
+/// commits (with their parents) in the order of the log
+#[derive(Default)]
+pub struct CommitGraph {
+    /// commit each lane is waiting for
+    lanes: Vec<Option<CommitId>>,
+    rows: Vec<GraphRow>,
+}
+
+impl CommitGraph {
+    /// number of rows computed so far
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// `true` if no commit was pushed yet
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// starts over with an empty graph
+    pub fn clear(&mut self) {
+        self.lanes.clear();
+        self.rows.clear();
+    }
+
+    /// row of the commit at position `idx` in the log
+    pub fn get(&self, idx: usize) -> Option<&GraphRow> {
+        self.rows.get(idx)
+    }
+
+    /// adds the row of the next commit in the log
+    pub fn push(&mut self, id: CommitId, parents: &[CommitId]) {
+        let waiting: Vec<usize> = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, lane)| {
+                if *lane == Some(id) {
+                    Some(idx)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let mut cells: Vec<GraphCell> = self
+            .lanes
+            .iter()
+            .map(|lane| {
+                if lane.is_some() {
+                    GraphCell::Line
+                } else {
+                    GraphCell::Empty
+                }
+            })
+            .collect();
+
+        let commit_lane = waiting
+            .first()
+            .copied()
+            .unwrap_or_else(|| self.free_lane(&mut cells));
+
+        cells[commit_lane] = GraphCell::Commit;
+        self.lanes[commit_lane] = parents.first().copied();
+
+        for lane in waiting.iter().skip(1) {
+            cells[*lane] = GraphCell::Join;
+            self.lanes[*lane] = None;
+        }
+
+        for parent in parents.iter().skip(1) {
+            if let Some(lane) =
+                self.lanes.iter().position(|l| *l == Some(*parent))
+            {
+                if cells[lane] == GraphCell::Line {
+                    cells[lane] = GraphCell::Junction;
+                }
+            } else {
+                let lane = self.free_lane(&mut cells);
+                cells[lane] = GraphCell::Fork;
+                self.lanes[lane] = Some(*parent);
+            }
+        }
+
+        Self::connect(&mut cells);
+
+        while self.lanes.last() == Some(&None) {
+            self.lanes.pop();
+        }
+
+        while cells.last() == Some(&GraphCell::Empty) {
+            cells.pop();
+        }
+
+        self.rows.push(GraphRow { cells });
+    }
+
+    /// first lane that was already empty before this row
+    /// (appends a new one if there is none)
+    fn free_lane(&mut self, cells: &mut Vec<GraphCell>) -> usize {
+        if let Some(idx) =
+            self.lanes.iter().enumerate().position(|(idx, lane)| {
+                lane.is_none() && cells[idx] == GraphCell::Empty
+            })
+        {
+            idx
+        } else {
+            self.lanes.push(None);
+            cells.push(GraphCell::Empty);
+            self.lanes.len() - 1
+        }
+    }
+
+    /// draws the horizontal connection between the commit and the
+    /// lanes forking off or joining it
+    fn connect(cells: &mut [GraphCell]) {
+        let first = cells.iter().position(|c| c.is_connected());
+        let last = cells.iter().rposition(|c| c.is_connected());
+
+        if let (Some(first), Some(last)) = (first, last) {
+            for cell in &mut cells[first..=last] {
+                *cell = match *cell {
+                    GraphCell::Empty => GraphCell::Horizontal,
+                    GraphCell::Line => GraphCell::Cross,
+                    other => other,
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommitGraph, GraphCell};
+    use crate::sync::{
+        get_commits_info, tests::repo_init, CommitId, LogWalker,
+    };
+    use GraphCell::{
+        Commit, Cross, Empty, Fork, Horizontal, Join, Junction, Line,
+    };
+
+    fn id(n: u8) -> CommitId {
+        CommitId::new(git2::Oid::from_bytes(&[n; 20]).unwrap())
+    }
+
+    fn cells(graph: &CommitGraph, idx: usize) -> Vec<GraphCell> {
+        graph.get(idx).unwrap().cells().to_vec()
+    }
+
+    #[test]
+    fn test_linear() {
+        let mut graph = CommitGraph::default();
+
+        graph.push(id(3), &[id(2)]);
+        graph.push(id(2), &[id(1)]);
+        graph.push(id(1), &[]);
+
+        assert_eq!(graph.len(), 3);
+        for idx in 0..3 {
+            assert_eq!(cells(&graph, idx), vec![Commit]);
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut graph = CommitGraph::default();
+
+        // 4 merges 3 (branch) into 2, both based on 1
+        graph.push(id(4), &[id(2), id(3)]);
+        graph.push(id(3), &[id(1)]);
+        graph.push(id(2), &[id(1)]);
+        graph.push(id(1), &[]);
+
+        assert_eq!(cells(&graph, 0), vec![Commit, Fork]);
+        assert_eq!(cells(&graph, 1), vec![Line, Commit]);
+        assert_eq!(cells(&graph, 2), vec![Commit, Line]);
+        assert_eq!(cells(&graph, 3), vec![Commit, Join]);
+    }
+
+    #[test]
+    fn test_crossing() {
+        let mut graph = CommitGraph::default();
+
+        // branch 8 forks off a second branch 6 which ends up in 1,
+        // crossing the lane of 7 when joining the first lane
+        graph.push(id(9), &[id(1), id(8)]);
+        graph.push(id(8), &[id(7), id(6)]);
+        graph.push(id(6), &[id(1)]);
+        graph.push(id(1), &[]);
+        graph.push(id(7), &[]);
+
+        assert_eq!(cells(&graph, 0), vec![Commit, Fork]);
+        assert_eq!(cells(&graph, 1), vec![Line, Commit, Fork]);
+        assert_eq!(cells(&graph, 2), vec![Line, Line, Commit]);
+        assert_eq!(cells(&graph, 3), vec![Commit, Cross, Join]);
+        assert_eq!(cells(&graph, 4), vec![Empty, Commit]);
+        assert_eq!(graph.get(4).unwrap().commit_lane(), 1);
+    }
+
+    #[test]
+    fn test_junction() {
+        let mut graph = CommitGraph::default();
+
+        // 8 merges 1 which the first lane is already waiting for
+        graph.push(id(9), &[id(1), id(8)]);
+        graph.push(id(8), &[id(7), id(1)]);
+
+        assert_eq!(cells(&graph, 1), vec![Junction, Commit]);
+    }
+
+    #[test]
+    fn test_unrelated_heads() {
+        let mut graph = CommitGraph::default();
+
+        graph.push(id(2), &[]);
+        graph.push(id(1), &[]);
+        graph.push(id(5), &[id(4), id(3)]);
+
+        assert_eq!(cells(&graph, 0), vec![Commit]);
+        assert_eq!(cells(&graph, 1), vec![Commit]);
+        assert_eq!(cells(&graph, 2), vec![Commit, Fork]);
+
+        graph.clear();
+        assert!(graph.is_empty());
+
+        graph.push(id(7), &[id(1), id(2), id(3)]);
+        graph.push(id(2), &[]);
+        graph.push(id(3), &[id(1)]);
+        graph.push(id(1), &[]);
+
+        assert_eq!(cells(&graph, 0), vec![Commit, Fork, Fork]);
+        assert_eq!(cells(&graph, 1), vec![Line, Commit, Line]);
+        assert_eq!(cells(&graph, 2), vec![Line, Empty, Commit]);
+        assert_eq!(cells(&graph, 3), vec![Commit, Horizontal, Join]);
+    }
+
+    #[test]
+    fn test_walked_log() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let sig = repo.signature().unwrap();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = base.tree().unwrap();
+
+        let side = repo
+            .commit(None, &sig, &sig, "side", &tree, &[&base])
+            .unwrap();
+        let side = repo.find_commit(side).unwrap();
+        let main = repo
+            .commit(Some("HEAD"), &sig, &sig, "main", &tree, &[&base])
+            .unwrap();
+        let main = repo.find_commit(main).unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            "merge",
+            &tree,
+            &[&main, &side],
+        )
+        .unwrap();
+
+        let mut ids = Vec::new();
+        LogWalker::new(&repo).read(&mut ids, 100).unwrap();
+
+        let infos = get_commits_info(repo_path, &ids, 50).unwrap();
+        assert_eq!(infos.len(), 4);
+        assert_eq!(infos[0].parents.len(), 2);
+
+        let mut graph = CommitGraph::default();
+        for info in &infos {
+            graph.push(info.id, &info.parents);
+        }
+
+        assert_eq!(cells(&graph, 0), vec![Commit, Fork]);
+        assert_eq!(cells(&graph, 3), vec![Commit, Join]);
+        assert_eq!(infos[3].id, base.id().into());
+    }
+}
@@ -17,6 +17,11 @@ impl CommitId {
     pub(crate) fn get_oid(self) -> Oid {
         self.0
     }
+
+    /// 7 chars short hash
+    pub fn get_short_string(&self) -> String {
+        self.to_string().chars().take(7).collect()
+    }
 }
 
 impl ToString for CommitId {
@@ -48,6 +53,8 @@ pub struct CommitInfo {
     pub author: String,
     ///
     pub id: CommitId,
+    /// first parent first, empty for root commits
+    pub parents: Vec<CommitId>,
 }
 
 ///
@@ -79,6 +86,7 @@ pub fn get_commits_info(
                 author,
                 time: c.time().seconds(),
                 id: CommitId(c.id()),
+                parents: c.parent_ids().map(CommitId).collect(),
             }
         })
         .collect::<Vec<_>>();
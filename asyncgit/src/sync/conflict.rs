@@ -0,0 +1,362 @@
+//! resolving conflicted index entries (after merge, pull or stash apply)
+
+use super::{merge::conflicted_paths, utils};
+use crate::error::{Error, Result};
+use git2::{build::CheckoutBuilder, IndexConflict, Repository};
+use scopetime::scope_time;
+use std::{fs, path::Path};
+
+/// which version of a conflicted file to take
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictSide {
+    /// version of the checked out branch
+    Ours,
+    /// version of the branch/stash that was merged in
+    Theirs,
+}
+
+/// paths of all conflicted files in the index
+pub fn get_conflicts(repo_path: &str) -> Result<Vec<String>> {
+    scope_time!("get_conflicts");
+
+    let repo = utils::repo(repo_path)?;
+    let index = repo.index()?;
+
+    conflicted_paths(&index)
+}
+
+/// replaces the workdir file `path` with the `side` version of the
+/// conflict (removes the file if it was deleted on that side).
+/// the conflict stays in the index until `resolve_conflict` is called.
+pub fn checkout_conflict_side(
+    repo_path: &str,
+    path: &str,
+    side: ConflictSide,
+) -> Result<()> {
+    scope_time!("checkout_conflict_side");
+
+    let repo = utils::repo(repo_path)?;
+
+    let conflict = find_conflict(&repo, path)?;
+    let entry = match side {
+        ConflictSide::Ours => conflict.our,
+        ConflictSide::Theirs => conflict.their,
+    };
+
+    let file_path = utils::work_dir(&repo).join(path);
+
+    match entry {
+        Some(_) => {
+            // checking out the stage keeps its mode (exec bit, symlink)
+            let mut checkout = CheckoutBuilder::new();
+            checkout.force().update_index(false).path(path);
+            match side {
+                ConflictSide::Ours => checkout.use_ours(true),
+                ConflictSide::Theirs => checkout.use_theirs(true),
+            };
+
+            repo.checkout_index(None, Some(&mut checkout))?;
+        }
+        None => {
+            if file_path.exists() {
+                fs::remove_file(&file_path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// marks `path` as resolved by staging its current workdir version
+/// (or its removal if the file does not exist anymore)
+pub fn resolve_conflict(repo_path: &str, path: &str) -> Result<()> {
+    scope_time!("resolve_conflict");
+
+    let repo = utils::repo(repo_path)?;
+
+    // make sure it actually is a conflict
+    find_conflict(&repo, path)?;
+
+    let mut index = repo.index()?;
+
+    if utils::work_dir(&repo).join(path).exists() {
+        index.add_path(Path::new(path))?;
+    } else {
+        index.remove_path(Path::new(path))?;
+    }
+
+    index.write()?;
+
+    Ok(())
+}
+
+fn find_conflict(
+    repo: &Repository,
+    path: &str,
+) -> Result<IndexConflict> {
+    let index = repo.index()?;
+
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+
+        let matches =
+            [&conflict.our, &conflict.their, &conflict.ancestor]
+                .iter()
+                .filter_map(|e| e.as_ref())
+                .any(|e| e.path == path.as_bytes());
+
+        if matches {
+            return Ok(conflict);
+        }
+    }
+
+    Err(Error::Generic(format!("no conflict found for: {}", path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit,
+        diff::get_diff,
+        stash_apply, stash_save,
+        status::{get_status, StatusItemType, StatusType},
+        tests::repo_init,
+    };
+    use git2::{Commit, Oid};
+    use std::{fs::File, io::Write};
+
+    fn commit_file(repo: &Repository, content: &str) -> Oid {
+        let root = repo.path().parent().unwrap();
+        File::create(&root.join("foo.txt"))
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("foo.txt")).unwrap();
+        index.write().unwrap();
+        let tree =
+            repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+        let sig = repo.signature().unwrap();
+        let head: Commit =
+            repo.head().unwrap().peel_to_commit().unwrap();
+
+        repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            content,
+            &tree,
+            &[&head],
+        )
+        .unwrap()
+    }
+
+    /// merges a commit changing `foo.txt` to `theirs` into a HEAD
+    /// that changed it to `ours`
+    fn merge_conflict(repo: &Repository) {
+        merge_conflict_with_mode(repo, 0o100_644);
+    }
+
+    /// like `merge_conflict`, `their_mode` is the mode of `foo.txt`
+    /// in the merged commit
+    fn merge_conflict_with_mode(repo: &Repository, their_mode: i32) {
+        let base =
+            repo.find_commit(commit_file(repo, "base")).unwrap();
+
+        let blob = repo.blob(b"theirs").unwrap();
+        let mut builder =
+            repo.treebuilder(Some(&base.tree().unwrap())).unwrap();
+        builder.insert("foo.txt", blob, their_mode).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let sig = repo.signature().unwrap();
+        let theirs = repo
+            .commit(None, &sig, &sig, "theirs", &tree, &[&base])
+            .unwrap();
+
+        commit_file(repo, "ours");
+
+        let annotated = repo.find_annotated_commit(theirs).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+    }
+
+    #[test]
+    fn test_conflict_status() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        merge_conflict(&repo);
+
+        assert_eq!(
+            get_conflicts(repo_path).unwrap(),
+            vec!["foo.txt"]
+        );
+
+        let status =
+            get_status(repo_path, StatusType::WorkingDir, true)
+                .unwrap();
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].status, StatusItemType::Conflicted);
+    }
+
+    #[test]
+    fn test_conflict_diff() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        merge_conflict(&repo);
+
+        let diff = get_diff(repo_path, "foo.txt".into(), false, None)
+            .unwrap();
+
+        assert_eq!(diff.hunks.len(), 1);
+        assert!(diff.hunks[0]
+            .lines
+            .iter()
+            .any(|l| l.content.starts_with("<<<<<<<")));
+    }
+
+    #[test]
+    fn test_take_theirs() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        merge_conflict(&repo);
+
+        checkout_conflict_side(
+            repo_path,
+            "foo.txt",
+            ConflictSide::Theirs,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("foo.txt")).unwrap(),
+            "theirs"
+        );
+
+        checkout_conflict_side(
+            repo_path,
+            "foo.txt",
+            ConflictSide::Ours,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("foo.txt")).unwrap(),
+            "ours"
+        );
+
+        // still conflicted until resolved
+        assert_eq!(get_conflicts(repo_path).unwrap().len(), 1);
+
+        resolve_conflict(repo_path, "foo.txt").unwrap();
+
+        assert!(get_conflicts(repo_path).unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_take_theirs_keeps_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        merge_conflict_with_mode(&repo, 0o100_755);
+
+        let mode = || {
+            fs::metadata(root.join("foo.txt"))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o111
+        };
+
+        checkout_conflict_side(
+            repo_path,
+            "foo.txt",
+            ConflictSide::Theirs,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("foo.txt")).unwrap(),
+            "theirs"
+        );
+        assert_ne!(mode(), 0);
+
+        checkout_conflict_side(
+            repo_path,
+            "foo.txt",
+            ConflictSide::Ours,
+        )
+        .unwrap();
+        assert_eq!(mode(), 0);
+        assert_eq!(get_conflicts(repo_path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_and_commit_merge() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        merge_conflict(&repo);
+
+        // not possible while conflicts exist
+        assert!(commit(repo_path, "merge").is_err());
+
+        File::create(&root.join("foo.txt"))
+            .unwrap()
+            .write_all(b"edited")
+            .unwrap();
+
+        resolve_conflict(repo_path, "foo.txt").unwrap();
+
+        let id = commit(repo_path, "merge").unwrap();
+
+        let commit = repo.find_commit(id.into()).unwrap();
+        assert_eq!(commit.parent_count(), 2);
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_stash_apply_conflict() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        commit_file(&repo, "base");
+
+        File::create(&root.join("foo.txt"))
+            .unwrap()
+            .write_all(b"stashed")
+            .unwrap();
+        let stash = stash_save(repo_path, None, true, false).unwrap();
+
+        commit_file(&repo, "ours");
+
+        assert!(matches!(
+            stash_apply(repo_path, stash),
+            Err(Error::Conflicts(_))
+        ));
+
+        assert_eq!(
+            get_conflicts(repo_path).unwrap(),
+            vec!["foo.txt"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_unknown_path() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert!(resolve_conflict(repo_path, "foo.txt").is_err());
+    }
+}
@@ -0,0 +1,444 @@
+//! credentials used when talking to remotes
+
+use crate::{
+    error::{Error, Result},
+    sync::utils,
+};
+use git2::{Config, Cred, CredentialHelper, CredentialType};
+use scopetime::scope_time;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// username used for ssh if neither the url nor the user provides one
+const DEFAULT_SSH_USERNAME: &str = "git";
+
+/// username/password pair for http(s) remotes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BasicAuthCredential {
+    ///
+    pub username: Option<String>,
+    ///
+    pub password: Option<String>,
+}
+
+impl BasicAuthCredential {
+    ///
+    pub const fn new(
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Self {
+        Self { username, password }
+    }
+
+    /// true if both username and password are set
+    pub const fn is_complete(&self) -> bool {
+        self.username.is_some() && self.password.is_some()
+    }
+}
+
+/// true if the url of `remote` uses a transport that authenticates
+/// via username/password (http/https)
+pub fn need_username_password(
+    repo_path: &str,
+    remote: &str,
+) -> Result<bool> {
+    scope_time!("need_username_password");
+
+    let url = remote_url(repo_path, remote)?;
+
+    Ok(url.starts_with("http://") || url.starts_with("https://"))
+}
+
+/// asks the configured git credential helpers for username/password
+/// of `remote`, fields stay `None` if no helper knows them
+pub fn extract_username_password(
+    repo_path: &str,
+    remote: &str,
+) -> Result<BasicAuthCredential> {
+    scope_time!("extract_username_password");
+
+    let repo = utils::repo(repo_path)?;
+    let url = remote_url(repo_path, remote)?;
+
+    Ok(helper_credential(&url, &repo.config()?))
+}
+
+/// runs the credential helpers configured in `config` for `url`
+fn helper_credential(
+    url: &str,
+    config: &Config,
+) -> BasicAuthCredential {
+    let mut helper = CredentialHelper::new(url);
+    helper.config(config);
+
+    match helper.execute() {
+        Some((username, password)) => {
+            BasicAuthCredential::new(Some(username), Some(password))
+        }
+        None => BasicAuthCredential::default(),
+    }
+}
+
+/// credential to authenticate at `remote` with: `basic_credential` if
+/// given, otherwise what the credential helpers know for http(s)
+/// remotes. this spawns the helpers, so it belongs in an async job.
+///
+/// returns `Error::MissingCredential` if the user has to complete it
+pub fn resolve_credential(
+    repo_path: &str,
+    remote: &str,
+    basic_credential: Option<BasicAuthCredential>,
+) -> Result<Option<BasicAuthCredential>> {
+    scope_time!("resolve_credential");
+
+    let config = utils::repo(repo_path)?.config()?;
+
+    resolve_credential_from(
+        repo_path,
+        remote,
+        basic_credential,
+        &config,
+    )
+}
+
+fn resolve_credential_from(
+    repo_path: &str,
+    remote: &str,
+    basic_credential: Option<BasicAuthCredential>,
+    config: &Config,
+) -> Result<Option<BasicAuthCredential>> {
+    if basic_credential.is_some()
+        || !need_username_password(repo_path, remote)?
+    {
+        return Ok(basic_credential);
+    }
+
+    let cred =
+        helper_credential(&remote_url(repo_path, remote)?, config);
+
+    if cred.is_complete() {
+        Ok(Some(cred))
+    } else {
+        Err(Error::MissingCredential(cred))
+    }
+}
+
+fn remote_url(repo_path: &str, remote: &str) -> Result<String> {
+    let repo = utils::repo(repo_path)?;
+    let remote = repo.find_remote(remote)?;
+
+    remote.url().map(String::from).ok_or_else(|| {
+        Error::Generic(String::from("remote url is not valid utf8"))
+    })
+}
+
+/// state behind the `credentials` remote callback.
+///
+/// libgit2 keeps calling the callback as long as authentication
+/// fails, so every source is handed out only once and only if its
+/// type is in the `allowed_types` of the request. once everything
+/// was tried we return an error instead of looping forever.
+pub(crate) struct CredentialProvider {
+    config: Option<Config>,
+    basic: Option<BasicAuthCredential>,
+    ssh_keys: Option<Vec<PathBuf>>,
+    tried_username: bool,
+    tried_agent: bool,
+    tried_basic: bool,
+    tried_helper: bool,
+    tried_default: bool,
+}
+
+impl CredentialProvider {
+    ///
+    pub(crate) const fn new(
+        config: Option<Config>,
+        basic: Option<BasicAuthCredential>,
+    ) -> Self {
+        Self {
+            config,
+            basic,
+            ssh_keys: None,
+            tried_username: false,
+            tried_agent: false,
+            tried_basic: false,
+            tried_helper: false,
+            tried_default: false,
+        }
+    }
+
+    /// next credential to try for `url`
+    pub(crate) fn next(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed_types: CredentialType,
+    ) -> std::result::Result<Cred, git2::Error> {
+        let username = username_from_url
+            .map(String::from)
+            .or_else(|| {
+                self.basic.as_ref().and_then(|b| b.username.clone())
+            })
+            .unwrap_or_else(|| DEFAULT_SSH_USERNAME.to_string());
+
+        if allowed_types.contains(CredentialType::USERNAME)
+            && !self.tried_username
+        {
+            self.tried_username = true;
+            return Cred::username(username.as_str());
+        }
+
+        if allowed_types.contains(CredentialType::SSH_KEY) {
+            if !self.tried_agent {
+                self.tried_agent = true;
+                return Cred::ssh_key_from_agent(username.as_str());
+            }
+
+            let keys = self.ssh_keys.get_or_insert_with(|| {
+                home_dir()
+                    .map(|home| ssh_key_files(&home.join(".ssh")))
+                    .unwrap_or_default()
+            });
+
+            while let Some(key) = keys.pop() {
+                let public = key.with_extension("pub");
+                let public =
+                    if public.exists() { Some(public) } else { None };
+
+                match Cred::ssh_key(
+                    username.as_str(),
+                    public.as_deref(),
+                    &key,
+                    None,
+                ) {
+                    Ok(cred) => return Ok(cred),
+                    Err(e) => log::debug!(
+                        "ssh key {:?} unusable: {}",
+                        key,
+                        e
+                    ),
+                }
+            }
+        }
+
+        if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT)
+        {
+            if !self.tried_basic {
+                self.tried_basic = true;
+
+                if let Some(BasicAuthCredential {
+                    username: Some(username),
+                    password: Some(password),
+                }) = &self.basic
+                {
+                    return Cred::userpass_plaintext(
+                        username.as_str(),
+                        password.as_str(),
+                    );
+                }
+            }
+
+            if !self.tried_helper {
+                self.tried_helper = true;
+
+                if let Some(config) = &self.config {
+                    if let Ok(cred) = Cred::credential_helper(
+                        config,
+                        url,
+                        username_from_url,
+                    ) {
+                        return Ok(cred);
+                    }
+                }
+            }
+        }
+
+        if allowed_types.contains(CredentialType::DEFAULT)
+            && !self.tried_default
+        {
+            self.tried_default = true;
+            return Cred::default();
+        }
+
+        Err(git2::Error::from_str(
+            "authentication failed: no (more) credentials to try",
+        ))
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// private keys following the `id_*` naming scheme in `dir`,
+/// in reverse order of how they are tried (we pop from the back)
+fn ssh_key_files(dir: &Path) -> Vec<PathBuf> {
+    let mut keys: Vec<PathBuf> = dir
+        .read_dir()
+        .map(|entries| {
+            entries
+                .filter_map(std::result::Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .map_or(true, |e| e != "pub")
+                        && path
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .map_or(false, |n| n.starts_with("id_"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    keys.sort();
+    keys.reverse();
+
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::repo_init;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_need_username_password() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        repo.remote("http", "https://github.com/user/repo.git")
+            .unwrap();
+        repo.remote("ssh", "git@github.com:user/repo.git").unwrap();
+        repo.remote("file", "file:///tmp/repo").unwrap();
+
+        assert!(need_username_password(repo_path, "http").unwrap());
+        assert!(!need_username_password(repo_path, "ssh").unwrap());
+        assert!(!need_username_password(repo_path, "file").unwrap());
+        assert!(need_username_password(repo_path, "missing").is_err());
+    }
+
+    #[test]
+    fn test_resolve_credential() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        repo.remote("http", "https://localhost/user/repo.git")
+            .unwrap();
+        repo.remote("ssh", "git@localhost:user/repo.git").unwrap();
+
+        // only the repo config, helpers of the global or system
+        // config (e.g. osxkeychain) must not be asked
+        let config =
+            Config::open(&repo.path().join("config")).unwrap();
+        let resolve = |remote, cred| {
+            resolve_credential_from(repo_path, remote, cred, &config)
+        };
+
+        let cred = BasicAuthCredential::new(
+            Some("u".into()),
+            Some("p".into()),
+        );
+
+        assert_eq!(
+            resolve("http", Some(cred.clone())).unwrap(),
+            Some(cred)
+        );
+        assert_eq!(resolve("ssh", None).unwrap(), None);
+        assert!(matches!(
+            resolve("http", None),
+            Err(Error::MissingCredential(_))
+        ));
+    }
+
+    #[test]
+    fn test_basic_credential_complete() {
+        assert!(!BasicAuthCredential::default().is_complete());
+        assert!(!BasicAuthCredential::new(Some("u".into()), None)
+            .is_complete());
+        assert!(BasicAuthCredential::new(
+            Some("u".into()),
+            Some("p".into())
+        )
+        .is_complete());
+    }
+
+    #[test]
+    fn test_provider_respects_allowed_types() {
+        let mut provider = CredentialProvider::new(
+            None,
+            Some(BasicAuthCredential::new(
+                Some("user".into()),
+                Some("pass".into()),
+            )),
+        );
+
+        // ssh is not allowed, so only the user/password is handed out
+        let cred = provider
+            .next(
+                "https://host/repo",
+                None,
+                CredentialType::USER_PASS_PLAINTEXT,
+            )
+            .unwrap();
+        assert!(cred.has_username());
+        assert!(provider.tried_basic);
+        assert!(!provider.tried_agent);
+
+        // no config means no helper, nothing left to try
+        assert!(provider
+            .next(
+                "https://host/repo",
+                None,
+                CredentialType::USER_PASS_PLAINTEXT,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_provider_does_not_loop() {
+        let mut provider = CredentialProvider::new(None, None);
+
+        let mut tries = 0;
+        while provider
+            .next("https://host/repo", None, CredentialType::DEFAULT)
+            .is_ok()
+        {
+            tries += 1;
+            assert!(tries < 10);
+        }
+
+        assert_eq!(tries, 1);
+    }
+
+    #[test]
+    fn test_ssh_key_files() {
+        let td = TempDir::new().unwrap();
+
+        for name in &["id_rsa", "id_rsa.pub", "id_ed25519", "config"]
+        {
+            File::create(td.path().join(name)).unwrap();
+        }
+        fs::create_dir(td.path().join("id_dir")).unwrap();
+
+        let keys = ssh_key_files(td.path());
+
+        assert_eq!(
+            keys,
+            vec![
+                td.path().join("id_rsa"),
+                td.path().join("id_ed25519")
+            ]
+        );
+    }
+}
@@ -1,17 +1,21 @@
 //! sync git api for fetching a diff
 
 use super::{
-    commit_files::get_commit_diff,
+    commit_files::{
+        get_commit_diff, get_compare_commits_diff,
+        get_compare_workdir_diff,
+    },
     utils::{self, get_head_repo, work_dir},
+    word_diff::emphasize_changes,
     CommitId,
 };
 use crate::{error::Error, error::Result, hash};
 use git2::{
-    Delta, Diff, DiffDelta, DiffFormat, DiffHunk, DiffOptions, Patch,
-    Repository,
+    Delta, Diff, DiffDelta, DiffFile, DiffFormat, DiffHunk,
+    DiffOptions as RawDiffOptions, FileMode, Patch, Repository,
 };
 use scopetime::scope_time;
-use std::{cell::RefCell, fs, path::Path, rc::Rc};
+use std::{cell::RefCell, fs, ops::Range, path::Path, rc::Rc};
 
 /// type of diff of a single line
 #[derive(Copy, Clone, PartialEq, Hash, Debug)]
@@ -39,18 +43,30 @@ pub struct DiffLine {
     pub content: String,
     ///
     pub line_type: DiffLineType,
+    /// line number in the old file (`None` for added lines)
+    pub old_lineno: Option<u32>,
+    /// line number in the new file (`None` for deleted lines)
+    pub new_lineno: Option<u32>,
+    /// byte ranges of `content` that changed compared to the
+    /// deleted/added line this one replaces
+    pub emphasis: Vec<Range<usize>>,
 }
 
+/// line ranges a hunk covers in the old and new file
 #[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
-pub(crate) struct HunkHeader {
-    old_start: u32,
-    old_lines: u32,
-    new_start: u32,
-    new_lines: u32,
+pub struct HunkHeader {
+    /// first line in the old file
+    pub old_start: u32,
+    /// amount of lines in the old file
+    pub old_lines: u32,
+    /// first line in the new file
+    pub new_start: u32,
+    /// amount of lines in the new file
+    pub new_lines: u32,
 }
 
-impl From<DiffHunk<'_>> for HunkHeader {
-    fn from(h: DiffHunk) -> Self {
+impl From<&DiffHunk<'_>> for HunkHeader {
+    fn from(h: &DiffHunk) -> Self {
         Self {
             old_start: h.old_start(),
             old_lines: h.old_lines(),
@@ -65,13 +81,75 @@ impl From<DiffHunk<'_>> for HunkHeader {
 pub struct Hunk {
     /// hash of the hunk header
     pub header_hash: u64,
+    /// parsed line ranges of the hunk
+    pub header: HunkHeader,
+    /// text git shows after the range (usually the enclosing
+    /// function), empty if there is none
+    pub function_context: String,
     /// list of `DiffLine`s
     pub lines: Vec<DiffLine>,
 }
 
+/// user configurable options of a diff
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub struct DiffOptions {
+    /// ignore changes in the amount of whitespace (like `git diff -b`)
+    pub ignore_whitespace: bool,
+    /// amount of unchanged lines shown around changes
+    pub context: u32,
+    /// detect renamed and copied files in commit diffs
+    pub find_renames: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            ignore_whitespace: false,
+            context: 3,
+            find_renames: false,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// applies the line related options to `opt`
+    pub(crate) fn apply(self, opt: &mut RawDiffOptions) {
+        opt.ignore_whitespace_change(self.ignore_whitespace);
+        opt.context_lines(self.context);
+    }
+}
+
+/// files bigger than this (in bytes) are not diffed line by line
+pub const MAX_DIFF_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// amount of bytes checked for NUL bytes to consider content binary
+/// (same heuristic as git)
+const BINARY_CHECK_SIZE: usize = 8000;
+
+/// what a `FileDiff` describes, only `Text` diffs contain hunks
+#[derive(Copy, Clone, PartialEq, Hash, Debug)]
+pub enum DiffKind {
+    /// regular line based diff
+    Text,
+    /// old or new content is binary
+    Binary,
+    /// old or new content exceeds `MAX_DIFF_FILE_SIZE`
+    TooLarge,
+    /// submodule (or nested repository) changes
+    Submodule,
+}
+
+impl Default for DiffKind {
+    fn default() -> Self {
+        DiffKind::Text
+    }
+}
+
 /// collection of hunks, sum of all diff lines
 #[derive(Default, Clone, Hash, Debug)]
 pub struct FileDiff {
+    /// kind of content compared
+    pub kind: DiffKind,
     /// list of hunks
     pub hunks: Vec<Hunk>,
     /// lines total summed up over hunks
@@ -89,10 +167,14 @@ pub(crate) fn get_diff_raw<'a>(
     p: &str,
     stage: bool,
     reverse: bool,
+    options: Option<DiffOptions>,
 ) -> Result<Diff<'a>> {
     // scope_time!("get_diff_raw");
 
-    let mut opt = DiffOptions::new();
+    let mut opt = RawDiffOptions::new();
+    if let Some(options) = options {
+        options.apply(&mut opt);
+    }
     opt.pathspec(p);
     opt.reverse(reverse);
 
@@ -128,14 +210,14 @@ pub fn get_diff(
     repo_path: &str,
     p: String,
     stage: bool,
+    options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
     scope_time!("get_diff");
 
     let repo = utils::repo(repo_path)?;
-    let work_dir = work_dir(&repo);
-    let diff = get_diff_raw(&repo, &p, stage, false)?;
+    let diff = get_diff_raw(&repo, &p, stage, false, options)?;
 
-    raw_diff_to_file_diff(&diff, work_dir)
+    raw_diff_to_file_diff(&repo, &diff)
 }
 
 /// returns diff of a specific file inside a commit
@@ -144,35 +226,87 @@ pub fn get_diff_commit(
     repo_path: &str,
     id: CommitId,
     p: String,
+    options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
     scope_time!("get_diff_commit");
 
     let repo = utils::repo(repo_path)?;
-    let work_dir = work_dir(&repo);
-    let diff = get_commit_diff(&repo, id, Some(p))?;
+    let diff = get_commit_diff(&repo, id, Some(p), options)?;
+
+    raw_diff_to_file_diff(&repo, &diff)
+}
+
+/// returns diff of a specific file between two commits (from `ids.0`
+/// to `ids.1`)
+pub fn get_diff_commits(
+    repo_path: &str,
+    ids: (CommitId, CommitId),
+    p: String,
+    options: Option<DiffOptions>,
+) -> Result<FileDiff> {
+    scope_time!("get_diff_commits");
+
+    let repo = utils::repo(repo_path)?;
+    let diff =
+        get_compare_commits_diff(&repo, ids, Some(p), options)?;
+
+    raw_diff_to_file_diff(&repo, &diff)
+}
+
+/// returns diff of a specific file between a commit and the working
+/// dir
+pub fn get_diff_commit_workdir(
+    repo_path: &str,
+    id: CommitId,
+    p: String,
+    options: Option<DiffOptions>,
+) -> Result<FileDiff> {
+    scope_time!("get_diff_commit_workdir");
+
+    let repo = utils::repo(repo_path)?;
+    let diff = get_compare_workdir_diff(&repo, id, Some(p), options)?;
 
-    raw_diff_to_file_diff(&diff, work_dir)
+    raw_diff_to_file_diff(&repo, &diff)
 }
 
 ///
-fn raw_diff_to_file_diff<'a>(
+pub(crate) fn raw_diff_to_file_diff<'a>(
+    repo: &Repository,
     diff: &'a Diff,
-    work_dir: &Path,
 ) -> Result<FileDiff> {
+    let work_dir = work_dir(repo);
+
+    if diff.deltas().len() == 1 {
+        if let Some(delta) = diff.deltas().next() {
+            if let Some(summary) =
+                summarize_special_delta(repo, work_dir, &delta)
+            {
+                return Ok(summary);
+            }
+        }
+    }
+
     let res = Rc::new(RefCell::new(FileDiff::default()));
     {
         let mut current_lines = Vec::new();
         let mut current_hunk: Option<HunkHeader> = None;
+        let mut current_context = String::new();
 
         let res_cell = Rc::clone(&res);
         let adder = move |header: &HunkHeader,
+                          context: &str,
                           lines: &Vec<DiffLine>| {
+            let mut lines = lines.clone();
+            emphasize_changes(&mut lines);
+
             let mut res = res_cell.borrow_mut();
+            res.lines += lines.len();
             res.hunks.push(Hunk {
                 header_hash: hash(header),
-                lines: lines.clone(),
+                header: *header,
+                function_context: context.to_string(),
+                lines,
             });
-            res.lines += lines.len();
         };
 
         let res_cell = Rc::clone(&res);
@@ -187,16 +321,25 @@ fn raw_diff_to_file_diff<'a>(
                 );
                 res.size_delta = (res.sizes.1 as i64)
                     .saturating_sub(res.sizes.0 as i64);
+                if delta.flags().is_binary() {
+                    res.kind = DiffKind::Binary;
+                }
             }
             if let Some(hunk) = hunk {
-                let hunk_header = HunkHeader::from(hunk);
+                let hunk_header = HunkHeader::from(&hunk);
 
                 match current_hunk {
-                    None => current_hunk = Some(hunk_header),
+                    None => {
+                        current_hunk = Some(hunk_header);
+                        current_context =
+                            function_context(hunk.header());
+                    }
                     Some(h) if h != hunk_header => {
-                        adder(&h, &current_lines);
+                        adder(&h, &current_context, &current_lines);
                         current_lines.clear();
-                        current_hunk = Some(hunk_header)
+                        current_hunk = Some(hunk_header);
+                        current_context =
+                            function_context(hunk.header());
                     }
                     _ => (),
                 }
@@ -212,6 +355,9 @@ fn raw_diff_to_file_diff<'a>(
                     content: String::from_utf8_lossy(line.content())
                         .to_string(),
                     line_type,
+                    old_lineno: line.old_lineno(),
+                    new_lineno: line.new_lineno(),
+                    emphasis: Vec::new(),
                 };
 
                 current_lines.push(diff_line);
@@ -224,7 +370,11 @@ fn raw_diff_to_file_diff<'a>(
                 .next()
                 .expect("it's safe to unwrap here because we check first that diff.deltas has a single element");
 
-            if delta.status() == Delta::Untracked {
+            // conflicted files have no diff of their own,
+            // show the workdir version (with conflict markers) instead
+            if delta.status() == Delta::Untracked
+                || delta.status() == Delta::Conflicted
+            {
                 let relative_path =
                     delta.new_file().path().ok_or_else(|| {
                         Error::Generic(
@@ -238,6 +388,18 @@ fn raw_diff_to_file_diff<'a>(
                 if let Some(newfile_content) =
                     new_file_content(&newfile_path)
                 {
+                    if is_binary(&newfile_content) {
+                        let size = newfile_content.len() as u64;
+                        return Ok(FileDiff {
+                            kind: DiffKind::Binary,
+                            untracked: delta.status()
+                                == Delta::Untracked,
+                            sizes: (0, size),
+                            size_delta: size as i64,
+                            ..FileDiff::default()
+                        });
+                    }
+
                     let mut patch = Patch::from_buffers(
                         &[],
                         None,
@@ -276,6 +438,7 @@ fn raw_diff_to_file_diff<'a>(
         if !current_lines.is_empty() {
             adder(
                 &current_hunk.expect("invalid hunk"),
+                &current_context,
                 &current_lines,
             );
         }
@@ -288,6 +451,96 @@ fn raw_diff_to_file_diff<'a>(
     Ok(res.into_inner())
 }
 
+/// summary without hunks for submodules and files too large to diff,
+/// `None` if the content needs to be looked at
+fn summarize_special_delta(
+    repo: &Repository,
+    work_dir: &Path,
+    delta: &DiffDelta,
+) -> Option<FileDiff> {
+    let old = delta.old_file();
+    let new = delta.new_file();
+
+    let sizes = (
+        if delta.status() == Delta::Added
+            || delta.status() == Delta::Untracked
+        {
+            0
+        } else {
+            file_size(repo, work_dir, &old)
+        },
+        if delta.status() == Delta::Deleted {
+            0
+        } else {
+            file_size(repo, work_dir, &new)
+        },
+    );
+
+    let nested_repo = new.path().map_or(false, |path| {
+        work_dir.join(path).join(".git").exists()
+    });
+
+    let kind = if old.mode() == FileMode::Commit
+        || new.mode() == FileMode::Commit
+        || nested_repo
+    {
+        DiffKind::Submodule
+    } else if sizes.0 > MAX_DIFF_FILE_SIZE
+        || sizes.1 > MAX_DIFF_FILE_SIZE
+    {
+        DiffKind::TooLarge
+    } else {
+        return None;
+    };
+
+    Some(FileDiff {
+        kind,
+        untracked: delta.status() == Delta::Untracked,
+        sizes,
+        size_delta: (sizes.1 as i64).saturating_sub(sizes.0 as i64),
+        ..FileDiff::default()
+    })
+}
+
+/// size of one side of a delta without loading its content: from the
+/// object header if it is in the odb, from the workdir otherwise
+fn file_size(
+    repo: &Repository,
+    work_dir: &Path,
+    file: &DiffFile,
+) -> u64 {
+    if !file.id().is_zero() {
+        if let Ok((size, _)) =
+            repo.odb().and_then(|odb| odb.read_header(file.id()))
+        {
+            return size as u64;
+        }
+    }
+
+    file.path()
+        .and_then(|path| {
+            fs::symlink_metadata(work_dir.join(path)).ok()
+        })
+        .filter(|meta| meta.is_file())
+        .map_or(0, |meta| meta.len())
+}
+
+fn is_binary(content: &[u8]) -> bool {
+    content.iter().take(BINARY_CHECK_SIZE).any(|b| *b == 0)
+}
+
+/// extracts the text following the line ranges of a hunk header
+/// like `@@ -1,2 +1,3 @@ fn foo()`
+fn function_context(header: &[u8]) -> String {
+    let header = String::from_utf8_lossy(header);
+
+    header
+        .splitn(3, "@@")
+        .nth(2)
+        .map(|ctx| ctx.trim().to_string())
+        .unwrap_or_default()
+}
+
 fn new_file_content(path: &Path) -> Option<Vec<u8>> {
     if let Ok(meta) = fs::symlink_metadata(path) {
         if meta.file_type().is_symlink() {
@@ -308,13 +561,18 @@ fn new_file_content(path: &Path) -> Option<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{get_diff, get_diff_commit};
+    use super::{
+        get_diff, get_diff_commit, get_diff_commit_workdir,
+        get_diff_commits, DiffKind, DiffLineType, DiffOptions,
+        MAX_DIFF_FILE_SIZE,
+    };
     use crate::error::Result;
     use crate::sync::{
         commit, stage_add_file,
         status::{get_status, StatusType},
         tests::{get_statuses, repo_init, repo_init_empty},
     };
+    use git2::Repository;
     use std::{
         fs::{self, File},
         io::Write,
@@ -337,9 +595,13 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (1, 0));
 
-        let diff =
-            get_diff(repo_path, "foo/bar.txt".to_string(), false)
-                .unwrap();
+        let diff = get_diff(
+            repo_path,
+            "foo/bar.txt".to_string(),
+            false,
+            None,
+        )
+        .unwrap();
 
         assert_eq!(diff.hunks.len(), 1);
         assert_eq!(diff.hunks[0].lines[1].content, "test\n");
@@ -369,6 +631,7 @@ mod tests {
             repo_path,
             String::from(file_path.to_str().unwrap()),
             true,
+            None,
         )
         .unwrap();
 
@@ -436,12 +699,58 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (1, 1));
 
-        let res = get_diff(repo_path, "bar.txt".to_string(), false)
-            .unwrap();
+        let res =
+            get_diff(repo_path, "bar.txt".to_string(), false, None)
+                .unwrap();
 
         assert_eq!(res.hunks.len(), 2)
     }
 
+    #[test]
+    fn test_hunk_header_and_line_numbers() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let file_path = root.join("bar.txt");
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"fn foo() {\n1\n2\n3\n4\n5\n6\n7\n8\n}\n")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("bar.txt")).unwrap();
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"fn foo() {\n1\n2\n3\n4\n5\n6\nx\n8\n}\n")
+            .unwrap();
+
+        let diff =
+            get_diff(repo_path, "bar.txt".to_string(), false, None)
+                .unwrap();
+
+        assert_eq!(diff.hunks.len(), 1);
+
+        let hunk = &diff.hunks[0];
+        assert_eq!(hunk.header.old_start, 5);
+        assert_eq!(hunk.header.old_lines, 6);
+        assert_eq!(hunk.header.new_start, 5);
+        assert_eq!(hunk.header.new_lines, 6);
+        assert_eq!(hunk.function_context, "fn foo() {");
+
+        let changed = hunk
+            .lines
+            .iter()
+            .filter(|l| l.line_type != DiffLineType::None)
+            .filter(|l| l.line_type != DiffLineType::Header)
+            .map(|l| (l.old_lineno, l.new_lineno))
+            .collect::<Vec<_>>();
+
+        assert_eq!(changed, vec![(Some(8), None), (None, Some(8))]);
+        assert_eq!(hunk.lines[1].old_lineno, Some(5));
+        assert_eq!(hunk.lines[1].new_lineno, Some(5));
+    }
+
     #[test]
     fn test_diff_newfile_in_sub_dir_current_dir() {
         let file_path = Path::new("foo/foo.txt");
@@ -460,6 +769,7 @@ mod tests {
             sub_path.to_str().unwrap(),
             String::from(file_path.to_str().unwrap()),
             false,
+            None,
         )
         .unwrap();
 
@@ -486,6 +796,7 @@ mod tests {
             repo_path,
             String::from(file_path.to_str().unwrap()),
             false,
+            None,
         )
         .unwrap();
 
@@ -510,6 +821,7 @@ mod tests {
             repo_path,
             String::from(file_path.to_str().unwrap()),
             false,
+            None,
         )
         .unwrap();
 
@@ -541,7 +853,8 @@ mod tests {
         let id = commit(repo_path, "").unwrap();
 
         let diff =
-            get_diff_commit(repo_path, id, String::new()).unwrap();
+            get_diff_commit(repo_path, id, String::new(), None)
+                .unwrap();
 
         dbg!(&diff);
         assert_eq!(diff.sizes, (1, 2));
@@ -549,4 +862,208 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_diff_kind_binary() -> Result<()> {
+        let file_path = Path::new("bar");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(file_path))?.write_all(b"a\x00b")?;
+
+        let diff =
+            get_diff(repo_path, "bar".to_string(), false, None)
+                .unwrap();
+
+        assert_eq!(diff.kind, DiffKind::Binary);
+        assert!(diff.hunks.is_empty());
+        assert_eq!(diff.sizes, (0, 3));
+
+        stage_add_file(repo_path, file_path).unwrap();
+        commit(repo_path, "commit").unwrap();
+
+        File::create(&root.join(file_path))?
+            .write_all(b"a\x00c\n")?;
+
+        let diff =
+            get_diff(repo_path, "bar".to_string(), false, None)
+                .unwrap();
+
+        assert_eq!(diff.kind, DiffKind::Binary);
+        assert!(diff.hunks.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_kind_too_large() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let size = MAX_DIFF_FILE_SIZE + 1;
+        let content = "a\n".repeat((size / 2 + 1) as usize);
+        File::create(&root.join("big.txt"))?
+            .write_all(content.as_bytes())?;
+
+        let diff =
+            get_diff(repo_path, "big.txt".to_string(), false, None)
+                .unwrap();
+
+        assert_eq!(diff.kind, DiffKind::TooLarge);
+        assert!(diff.untracked);
+        assert_eq!(diff.sizes, (0, content.len() as u64));
+
+        stage_add_file(repo_path, Path::new("big.txt")).unwrap();
+
+        let diff =
+            get_diff(repo_path, "big.txt".to_string(), true, None)
+                .unwrap();
+
+        assert_eq!(diff.kind, DiffKind::TooLarge);
+        assert_eq!(diff.sizes, (0, content.len() as u64));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_kind_nested_repo() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let sub = Repository::init(root.join("sub"))?;
+        File::create(&root.join("sub/foo"))?.write_all(b"test")?;
+        drop(sub);
+
+        let diff =
+            get_diff(repo_path, "sub/".to_string(), false, None)
+                .unwrap();
+
+        assert_eq!(diff.kind, DiffKind::Submodule);
+        assert!(diff.hunks.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_options_whitespace() -> Result<()> {
+        let file_path = Path::new("foo.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(file_path))?
+            .write_all(b"a\nb c\n")?;
+        stage_add_file(repo_path, file_path)?;
+        commit(repo_path, "commit msg")?;
+
+        File::create(&root.join(file_path))?
+            .write_all(b"a  \nb \t c\n")?;
+
+        let diff =
+            get_diff(repo_path, "foo.txt".to_string(), false, None)?;
+        assert_eq!(diff.hunks.len(), 1);
+
+        let options = DiffOptions {
+            ignore_whitespace: true,
+            ..DiffOptions::default()
+        };
+        let diff = get_diff(
+            repo_path,
+            "foo.txt".to_string(),
+            false,
+            Some(options),
+        )?;
+        assert!(diff.hunks.is_empty());
+
+        // whitespace where there was none is still a change
+        File::create(&root.join(file_path))?.write_all(b"a\nbc\n")?;
+
+        let diff = get_diff(
+            repo_path,
+            "foo.txt".to_string(),
+            false,
+            Some(options),
+        )?;
+        assert_eq!(diff.hunks.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_options_context() -> Result<()> {
+        let file_path = Path::new("foo.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let content: String =
+            (1..=10).map(|i| format!("{}\n", i)).collect();
+        File::create(&root.join(file_path))?
+            .write_all(content.as_bytes())?;
+        stage_add_file(repo_path, file_path)?;
+        commit(repo_path, "commit msg")?;
+
+        File::create(&root.join(file_path))?
+            .write_all(content.replace("5\n", "five\n").as_bytes())?;
+
+        let diff =
+            get_diff(repo_path, "foo.txt".to_string(), false, None)?;
+        // header, 3 context lines on each side, delete and add
+        assert_eq!(diff.hunks[0].lines.len(), 9);
+
+        let options = DiffOptions {
+            context: 0,
+            ..DiffOptions::default()
+        };
+        let diff = get_diff(
+            repo_path,
+            "foo.txt".to_string(),
+            false,
+            Some(options),
+        )?;
+        assert_eq!(diff.hunks[0].lines.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_compare() -> Result<()> {
+        let file_path = Path::new("foo.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(file_path))?.write_all(b"a\n")?;
+        stage_add_file(repo_path, file_path)?;
+        let first = commit(repo_path, "commit 1")?;
+
+        File::create(&root.join(file_path))?.write_all(b"b\n")?;
+        stage_add_file(repo_path, file_path)?;
+        let second = commit(repo_path, "commit 2")?;
+
+        File::create(&root.join(file_path))?.write_all(b"c\n")?;
+
+        let diff = get_diff_commits(
+            repo_path,
+            (first, second),
+            "foo.txt".to_string(),
+            None,
+        )?;
+        assert_eq!(diff.hunks[0].lines[1].content, "a\n");
+        assert_eq!(diff.hunks[0].lines[2].content, "b\n");
+
+        let diff = get_diff_commit_workdir(
+            repo_path,
+            first,
+            "foo.txt".to_string(),
+            None,
+        )?;
+        assert_eq!(diff.hunks[0].lines[1].content, "a\n");
+        assert_eq!(diff.hunks[0].lines[2].content, "c\n");
+
+        Ok(())
+    }
 }
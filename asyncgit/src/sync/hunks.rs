@@ -1,12 +1,12 @@
 use super::{
-    diff::{get_diff_raw, HunkHeader},
+    diff::{get_diff_raw, raw_diff_to_file_diff, HunkHeader},
     utils::repo,
 };
 use crate::{
     error::{Error, Result},
     hash,
 };
-use git2::{ApplyLocation, ApplyOptions, Diff};
+use git2::{ApplyLocation, ApplyOptions, Diff, Patch, Repository};
 use scopetime::scope_time;
 
 ///
@@ -19,12 +19,12 @@ pub fn stage_hunk(
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, &file_path, false, false)?;
+    let diff = get_diff_raw(&repo, &file_path, false, false, None)?;
 
     let mut opt = ApplyOptions::new();
     opt.hunk_callback(|hunk| {
         let header =
-            HunkHeader::from(hunk.expect("hunk unavailable"));
+            HunkHeader::from(&hunk.expect("hunk unavailable"));
         hash(&header) == hunk_hash
     });
 
@@ -43,7 +43,7 @@ pub fn reset_hunk(
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, &file_path, false, false)?;
+    let diff = get_diff_raw(&repo, &file_path, false, false, None)?;
 
     let hunk_index = find_hunk_index(&diff, hunk_hash);
     if let Some(hunk_index) = hunk_index {
@@ -55,7 +55,8 @@ pub fn reset_hunk(
             res
         });
 
-        let diff = get_diff_raw(&repo, &file_path, false, true)?;
+        let diff =
+            get_diff_raw(&repo, &file_path, false, true, None)?;
 
         repo.apply(&diff, ApplyLocation::WorkDir, Some(&mut opt))?;
 
@@ -74,7 +75,7 @@ fn find_hunk_index(diff: &Diff, hunk_hash: u64) -> Option<usize> {
         &mut |_, _| true,
         None,
         Some(&mut |_, hunk| {
-            let header = HunkHeader::from(hunk);
+            let header = HunkHeader::from(&hunk);
             if hash(&header) == hunk_hash {
                 result = Some(hunk_count);
             }
@@ -101,7 +102,7 @@ pub fn unstage_hunk(
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, &file_path, true, false)?;
+    let diff = get_diff_raw(&repo, &file_path, true, false, None)?;
     let diff_count_positive = diff.deltas().len();
 
     let hunk_index = find_hunk_index(&diff, hunk_hash);
@@ -109,7 +110,7 @@ pub fn unstage_hunk(
         return Err(Error::Generic("hunk not found".to_string()));
     }
 
-    let diff = get_diff_raw(&repo, &file_path, true, true)?;
+    let diff = get_diff_raw(&repo, &file_path, true, true, None)?;
 
     if diff.deltas().len() != diff_count_positive {
         return Err(Error::Generic(format!(
@@ -143,12 +144,255 @@ pub fn unstage_hunk(
     Ok(count == 1)
 }
 
+/// stages only the selected `lines` of the workdir diff of
+/// `file_path`. `lines` are indices into the lines of the `FileDiff`
+/// (see `get_diff`) whose hash is `diff_hash`.
+pub fn stage_lines(
+    repo_path: &str,
+    file_path: &str,
+    diff_hash: u64,
+    lines: &[usize],
+) -> Result<()> {
+    scope_time!("stage_lines");
+
+    let repo = repo(repo_path)?;
+
+    let diff = get_diff_raw(&repo, file_path, false, false, None)?;
+    check_diff_hash(&repo, &diff, diff_hash)?;
+
+    if let Some(patch) =
+        partial_patch(&diff, file_path, lines, false)?
+    {
+        repo.apply(&patch, ApplyLocation::Index, None)?;
+    }
+
+    Ok(())
+}
+
+/// unstages only the selected `lines` of the staged diff of
+/// `file_path`, see `stage_lines`
+pub fn unstage_lines(
+    repo_path: &str,
+    file_path: &str,
+    diff_hash: u64,
+    lines: &[usize],
+) -> Result<()> {
+    scope_time!("unstage_lines");
+
+    let repo = repo(repo_path)?;
+
+    let diff = get_diff_raw(&repo, file_path, true, false, None)?;
+    check_diff_hash(&repo, &diff, diff_hash)?;
+
+    if let Some(patch) = partial_patch(&diff, file_path, lines, true)?
+    {
+        repo.apply(&patch, ApplyLocation::Index, None)?;
+    }
+
+    Ok(())
+}
+
+/// discards only the selected `lines` of the workdir diff of
+/// `file_path`, see `stage_lines`
+pub fn reset_lines(
+    repo_path: &str,
+    file_path: &str,
+    diff_hash: u64,
+    lines: &[usize],
+) -> Result<()> {
+    scope_time!("reset_lines");
+
+    let repo = repo(repo_path)?;
+
+    let diff = get_diff_raw(&repo, file_path, false, false, None)?;
+    check_diff_hash(&repo, &diff, diff_hash)?;
+
+    if let Some(patch) = partial_patch(&diff, file_path, lines, true)?
+    {
+        repo.apply(&patch, ApplyLocation::WorkDir, None)?;
+    }
+
+    Ok(())
+}
+
+/// makes sure the line indices refer to the diff as it is now
+fn check_diff_hash(
+    repo: &Repository,
+    diff: &Diff,
+    diff_hash: u64,
+) -> Result<()> {
+    let file_diff = raw_diff_to_file_diff(repo, diff)?;
+
+    if file_diff.untracked {
+        return Err(Error::Generic(
+            "lines of untracked files cannot be staged".to_string(),
+        ));
+    }
+
+    if hash(&file_diff) != diff_hash {
+        return Err(Error::Generic(
+            "diff changed, selected lines are out of date"
+                .to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Body,
+    Kept,
+}
+
+/// builds a patch out of `diff` that only contains the changes of
+/// the selected `lines` (indices like in `FileDiff`, counting hunk
+/// headers). with `reverse` the patch undoes these changes instead.
+/// returns `None` if no change was selected.
+fn partial_patch(
+    diff: &Diff,
+    file_path: &str,
+    lines: &[usize],
+    reverse: bool,
+) -> Result<Option<Diff<'static>>> {
+    let patch = Patch::from_diff(diff, 0)?
+        .ok_or_else(|| Error::Generic("no diff found".to_string()))?;
+
+    let mut out = format!(
+        "diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n",
+        file_path
+    )
+    .into_bytes();
+
+    let mut line_index = 0;
+    let mut offset = 0_i64;
+    let mut any_change = false;
+
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, hunk_lines) = patch.hunk(hunk_idx)?;
+
+        // the header line
+        line_index += 1;
+
+        let mut body = Vec::new();
+        // removals that are kept as context, emitted at the end of
+        // their block of changes so that selected additions take
+        // the place of selected removals
+        let mut kept = Vec::new();
+        let mut last = None;
+        let mut old_lines = 0_u32;
+        let mut new_lines = 0_u32;
+        let mut changed = false;
+
+        for line_idx in 0..hunk_lines {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            let selected = lines.contains(&line_index);
+            line_index += 1;
+
+            let origin = match (line.origin(), reverse) {
+                ('+', false) | ('-', true) => b'+',
+                ('-', false) | ('+', true) => b'-',
+                ('=', _) | ('>', _) | ('<', _) => {
+                    // "no newline at end of file" of the line before
+                    let target = match last {
+                        Some(Target::Body) => &mut body,
+                        Some(Target::Kept) => &mut kept,
+                        None => continue,
+                    };
+                    target.extend_from_slice(
+                        b"\\ No newline at end of file\n",
+                    );
+                    continue;
+                }
+                _ => b' ',
+            };
+
+            let (origin, target) = match origin {
+                b'+' if selected => (b'+', Target::Body),
+                b'+' => {
+                    // not part of the image the patch applies to
+                    last = None;
+                    continue;
+                }
+                b'-' if selected => {
+                    body.append(&mut kept);
+                    (b'-', Target::Body)
+                }
+                b'-' => (b' ', Target::Kept),
+                _ => {
+                    body.append(&mut kept);
+                    (b' ', Target::Body)
+                }
+            };
+
+            if origin != b'+' {
+                old_lines += 1;
+            }
+            if origin != b'-' {
+                new_lines += 1;
+            }
+            changed |= origin != b' ';
+            last = Some(target);
+
+            let target = match target {
+                Target::Body => &mut body,
+                Target::Kept => &mut kept,
+            };
+            target.push(origin);
+            target.extend_from_slice(line.content());
+            if !line.content().ends_with(b"\n") {
+                target.push(b'\n');
+            }
+        }
+
+        body.append(&mut kept);
+
+        if !changed {
+            continue;
+        }
+        any_change = true;
+
+        let old_start = if reverse {
+            hunk.new_start()
+        } else {
+            hunk.old_start()
+        };
+
+        // libgit2 locates the hunk by its start in the new image,
+        // pure insertions start after `old_start`
+        let new_start = i64::from(old_start)
+            + offset
+            + if old_lines == 0 { 1 } else { 0 };
+        offset += i64::from(new_lines) - i64::from(old_lines);
+
+        out.extend_from_slice(
+            format!(
+                "@@ -{},{} +{},{} @@\n",
+                old_start, old_lines, new_start, new_lines
+            )
+            .as_bytes(),
+        );
+        out.append(&mut body);
+    }
+
+    if any_change {
+        Ok(Some(Diff::from_buffer(&out)?))
+    } else {
+        Ok(None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         error::Result,
-        sync::{diff::get_diff, tests::repo_init_empty},
+        sync::{
+            commit,
+            diff::{get_diff, DiffLineType, FileDiff},
+            stage_add_file,
+            tests::{repo_init, repo_init_empty},
+        },
     };
     use std::{
         fs::{self, File},
@@ -172,6 +416,7 @@ mod tests {
             sub_path.to_str().unwrap(),
             String::from(file_path.to_str().unwrap()),
             false,
+            None,
         )?;
 
         assert!(reset_hunk(
@@ -183,4 +428,217 @@ mod tests {
 
         Ok(())
     }
+
+    const FILE: &str = "foo.txt";
+
+    /// commits `committed`, then writes `changed` to the workdir
+    fn setup(
+        repo_path: &str,
+        committed: &str,
+        changed: &str,
+    ) -> Result<()> {
+        let root = Path::new(repo_path);
+
+        File::create(root.join(FILE))?
+            .write_all(committed.as_bytes())?;
+        stage_add_file(repo_path, Path::new(FILE))?;
+        commit(repo_path, "commit")?;
+
+        File::create(root.join(FILE))?
+            .write_all(changed.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// indices of the changed lines with this content
+    fn lines_of(diff: &FileDiff, content: &[&str]) -> Vec<usize> {
+        diff.hunks
+            .iter()
+            .flat_map(|hunk| hunk.lines.iter())
+            .enumerate()
+            .filter(|(_, line)| {
+                line.line_type != DiffLineType::None
+                    && line.line_type != DiffLineType::Header
+                    && content.contains(&line.content.trim_end())
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn index_content(repo_path: &str) -> String {
+        let repo = repo(repo_path).unwrap();
+        let index = repo.index().unwrap();
+        let entry = index.get_path(Path::new(FILE), 0).unwrap();
+        let blob = repo.find_blob(entry.id).unwrap();
+
+        String::from_utf8(blob.content().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_stage_lines() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        setup(
+            repo_path,
+            "a\nb\nc\nd\ne\nf\ng\nh\ni\n",
+            "a\nB\nc\nd\ne\nf\ng\nH\ni\nj\n",
+        )?;
+
+        let diff = get_diff(repo_path, FILE.into(), false, None)?;
+        let lines = lines_of(&diff, &["b", "B", "j"]);
+        assert_eq!(lines.len(), 3);
+
+        stage_lines(repo_path, FILE, hash(&diff), &lines)?;
+
+        assert_eq!(
+            index_content(repo_path),
+            "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join(FILE))?,
+            "a\nB\nc\nd\ne\nf\ng\nH\ni\nj\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stage_single_deletion() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        setup(repo_path, "a\nb\nc\n", "a\nB\nc\n")?;
+
+        let diff = get_diff(repo_path, FILE.into(), false, None)?;
+        let lines = lines_of(&diff, &["b"]);
+
+        stage_lines(repo_path, FILE, hash(&diff), &lines)?;
+
+        assert_eq!(index_content(repo_path), "a\nc\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stage_lines_keeps_order() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        setup(repo_path, "a\nb\nc\n", "a\nX\n")?;
+
+        let diff = get_diff(repo_path, FILE.into(), false, None)?;
+        let lines = lines_of(&diff, &["c", "X"]);
+
+        stage_lines(repo_path, FILE, hash(&diff), &lines)?;
+
+        assert_eq!(index_content(repo_path), "a\nb\nX\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unstage_lines() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        setup(repo_path, "a\nb\nc\n", "a\nB\nc\nd\n")?;
+        stage_add_file(repo_path, Path::new(FILE))?;
+
+        let diff = get_diff(repo_path, FILE.into(), true, None)?;
+        let lines = lines_of(&diff, &["d"]);
+
+        unstage_lines(repo_path, FILE, hash(&diff), &lines)?;
+
+        assert_eq!(index_content(repo_path), "a\nB\nc\n");
+        assert_eq!(
+            fs::read_to_string(root.join(FILE))?,
+            "a\nB\nc\nd\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_lines() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        setup(repo_path, "a\nb\nc\n", "a\nB\nc\nd\n")?;
+
+        let diff = get_diff(repo_path, FILE.into(), false, None)?;
+        let lines = lines_of(&diff, &["b", "B"]);
+
+        reset_lines(repo_path, FILE, hash(&diff), &lines)?;
+
+        assert_eq!(
+            fs::read_to_string(root.join(FILE))?,
+            "a\nb\nc\nd\n"
+        );
+        assert_eq!(index_content(repo_path), "a\nb\nc\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lines_missing_newline_at_eof() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        setup(repo_path, "a\nb\nc", "a\nB\nc\nd")?;
+
+        let diff = get_diff(repo_path, FILE.into(), false, None)?;
+        let lines = lines_of(&diff, &["b", "B"]);
+
+        stage_lines(repo_path, FILE, hash(&diff), &lines)?;
+
+        assert_eq!(index_content(repo_path), "a\nB\nc");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lines_outdated_diff() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        setup(repo_path, "a\nb\n", "a\nB\n")?;
+
+        let diff = get_diff(repo_path, FILE.into(), false, None)?;
+        let lines = lines_of(&diff, &["b", "B"]);
+
+        File::create(root.join(FILE))?.write_all(b"A\nB\n")?;
+
+        assert!(stage_lines(repo_path, FILE, hash(&diff), &lines)
+            .is_err());
+        assert!(reset_lines(repo_path, FILE, hash(&diff), &lines)
+            .is_err());
+        assert_eq!(index_content(repo_path), "a\nb\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lines_untracked() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join(FILE))?.write_all(b"a\n")?;
+
+        let diff = get_diff(repo_path, FILE.into(), false, None)?;
+
+        assert!(
+            stage_lines(repo_path, FILE, hash(&diff), &[1]).is_err()
+        );
+
+        Ok(())
+    }
 }
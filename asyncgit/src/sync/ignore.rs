@@ -38,6 +38,25 @@ pub fn add_to_ignore(
     Ok(())
 }
 
+/// returns true if any of `paths` (relative to the workdir) is not
+/// ignored by the `.gitignore` files or other exclude rules
+pub fn any_path_not_ignored(
+    repo_path: &str,
+    paths: &[PathBuf],
+) -> Result<bool> {
+    scope_time!("any_path_not_ignored");
+
+    let repo = repo(repo_path)?;
+
+    for path in paths {
+        if !repo.is_path_ignored(path)? {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
 fn file_ends_with_newline(file: &PathBuf) -> Result<bool> {
     let mut file = File::open(file)?;
     let size = file.metadata()?.len();
@@ -56,7 +75,7 @@ mod tests {
     use super::*;
     use crate::sync::tests::repo_init;
     use io::BufRead;
-    use std::{fs::File, io, path::Path};
+    use std::{fs, fs::File, io, path::Path};
 
     #[test]
     fn test_empty() -> Result<()> {
@@ -126,4 +145,29 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_any_path_not_ignored() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(".gitignore"))?
+            .write_all(b"target/\n*.log\n")?;
+        fs::create_dir(&root.join("target"))?;
+
+        let ignored = vec![
+            PathBuf::from("target/debug/foo"),
+            PathBuf::from("foo.log"),
+        ];
+        assert_eq!(any_path_not_ignored(repo_path, &ignored)?, false);
+
+        let mut paths = ignored;
+        paths.push(PathBuf::from("src/main.rs"));
+        assert_eq!(any_path_not_ignored(repo_path, &paths)?, true);
+
+        assert_eq!(any_path_not_ignored(repo_path, &[])?, false);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,72 @@
+//! matching commits against a log search query
+
+use git2::Commit;
+
+/// case insensitive search matching commits whose message or author
+/// contains the query or whose id starts with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogSearch {
+    query: String,
+}
+
+impl LogSearch {
+    ///
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query.trim().to_lowercase(),
+        }
+    }
+
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.query.is_empty()
+    }
+
+    ///
+    pub fn matches(&self, commit: &Commit) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        commit.id().to_string().starts_with(&self.query)
+            || commit.author().name().map_or(false, |name| {
+                name.to_lowercase().contains(&self.query)
+            })
+            || String::from_utf8_lossy(commit.message_bytes())
+                .to_lowercase()
+                .contains(&self.query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{commit, stage_add_file, tests::repo_init};
+    use std::{fs::File, io::Write, path::Path};
+
+    #[test]
+    fn test_matches() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join("foo"))
+            .unwrap()
+            .write_all(b"a")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let id =
+            commit(repo_path, "Fix the Parser\n\nbody text").unwrap();
+
+        let c = repo.find_commit(id.into()).unwrap();
+        let sha = id.to_string();
+
+        assert!(LogSearch::new("parser").matches(&c));
+        assert!(LogSearch::new("BODY").matches(&c));
+        assert!(LogSearch::new("nam").matches(&c));
+        assert!(LogSearch::new(&sha[..6]).matches(&c));
+        assert!(!LogSearch::new(&sha[1..7]).matches(&c));
+        assert!(!LogSearch::new("lexer").matches(&c));
+        assert!(!LogSearch::new("  ").matches(&c));
+    }
+}
@@ -1,6 +1,9 @@
 use super::CommitId;
 use crate::error::Result;
-use git2::{Repository, Revwalk};
+use git2::{
+    Commit, Delta, DiffFindOptions, Oid, Repository, Revwalk, Sort,
+};
+use std::path::Path;
 
 ///
 pub struct LogWalker<'a> {
@@ -48,14 +51,155 @@ impl<'a> LogWalker<'a> {
     }
 }
 
+/// commit that changed a followed file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileHistoryEntry {
+    ///
+    pub commit_id: CommitId,
+    /// path of the file in this commit
+    pub path: String,
+}
+
+/// walks the history from HEAD and only yields the commits changing
+/// one file (compared to their first parent), following renames
+pub struct FileLogWalker<'a> {
+    repo: &'a Repository,
+    revwalk: Option<Revwalk<'a>>,
+    path: String,
+}
+
+impl<'a> FileLogWalker<'a> {
+    ///
+    pub fn new(repo: &'a Repository, path: &str) -> Self {
+        Self {
+            repo,
+            revwalk: None,
+            path: path.to_string(),
+        }
+    }
+
+    /// walks up to `limit` commits and pushes those changing the
+    /// file to `out`. returns the number of commits walked, so
+    /// anything less than `limit` means the walk is done
+    pub fn read(
+        &mut self,
+        out: &mut Vec<FileHistoryEntry>,
+        limit: usize,
+    ) -> Result<usize> {
+        let mut count = 0_usize;
+
+        if self.revwalk.is_none() {
+            let mut walk = self.repo.revwalk()?;
+            // children before their parents, so renames are followed
+            // in the right order
+            walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+            walk.push_head()?;
+            self.revwalk = Some(walk);
+        }
+
+        let mut walked = Vec::with_capacity(limit);
+        if let Some(ref mut walk) = self.revwalk {
+            for id in walk {
+                if let Ok(id) = id {
+                    walked.push(id);
+                    count += 1;
+
+                    if count == limit {
+                        break;
+                    }
+                }
+            }
+        }
+
+        for id in walked {
+            let commit = self.repo.find_commit(id)?;
+
+            if self.changes_file(&commit)? {
+                out.push(FileHistoryEntry {
+                    commit_id: id.into(),
+                    path: self.path.clone(),
+                });
+
+                if let Some(old_path) = self.renamed_from(&commit)? {
+                    self.path = old_path;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// id and mode of the file in the tree of `commit`
+    fn entry(&self, commit: &Commit) -> Result<Option<(Oid, i32)>> {
+        Ok(commit
+            .tree()?
+            .get_path(Path::new(&self.path))
+            .ok()
+            .map(|entry| (entry.id(), entry.filemode())))
+    }
+
+    /// true if `commit` added, modified (content or mode) or removed
+    /// the file
+    fn changes_file(&self, commit: &Commit) -> Result<bool> {
+        let entry = self.entry(commit)?;
+
+        let parent_entry = match commit.parents().next() {
+            Some(parent) => self.entry(&parent)?,
+            None => None,
+        };
+
+        Ok(entry != parent_entry)
+    }
+
+    /// if `commit` added the file under a new name this returns the
+    /// path it had in the parent
+    fn renamed_from(
+        &self,
+        commit: &Commit,
+    ) -> Result<Option<String>> {
+        let parent = match commit.parents().next() {
+            Some(parent) => parent,
+            None => return Ok(None),
+        };
+
+        if self.entry(&parent)?.is_some() {
+            return Ok(None);
+        }
+
+        let mut diff = self.repo.diff_tree_to_tree(
+            Some(&parent.tree()?),
+            Some(&commit.tree()?),
+            None,
+        )?;
+        diff.find_similar(Some(
+            DiffFindOptions::new().renames(true),
+        ))?;
+
+        Ok(diff
+            .deltas()
+            .find(|delta| {
+                delta.status() == Delta::Renamed
+                    && delta.new_file().path()
+                        == Some(Path::new(&self.path))
+            })
+            .and_then(|delta| {
+                delta
+                    .old_file()
+                    .path()
+                    .and_then(Path::to_str)
+                    .map(String::from)
+            }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::sync::{
-        commit, get_commits_info, stage_add_file,
+        commit, get_commits_info, stage_add_file, stage_addremoved,
         tests::repo_init_empty,
     };
-    use std::{fs::File, io::Write, path::Path};
+    use std::{fs, fs::File, io::Write, path::Path};
 
     #[test]
     fn test_limit() -> Result<()> {
@@ -112,4 +256,97 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_file_history() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("foo"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let oid1 = commit(repo_path, "commit1").unwrap();
+        File::create(&root.join("bar"))?.write_all(b"b")?;
+        stage_add_file(repo_path, Path::new("bar")).unwrap();
+        commit(repo_path, "commit2").unwrap();
+        File::create(&root.join("foo"))?.write_all(b"c")?;
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let oid3 = commit(repo_path, "commit3").unwrap();
+
+        let mut items = Vec::new();
+        let mut walk = FileLogWalker::new(&repo, "foo");
+        assert_eq!(walk.read(&mut items, 100).unwrap(), 3);
+
+        assert_eq!(
+            items.iter().map(|e| e.commit_id).collect::<Vec<_>>(),
+            vec![oid3, oid1]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_history_rename() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let content = b"some content\nthat is long enough\n";
+
+        File::create(&root.join("foo"))?.write_all(content)?;
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let oid1 = commit(repo_path, "commit1").unwrap();
+        fs::rename(root.join("foo"), root.join("bar"))?;
+        stage_addremoved(repo_path, Path::new("foo")).unwrap();
+        stage_add_file(repo_path, Path::new("bar")).unwrap();
+        let oid2 = commit(repo_path, "commit2").unwrap();
+
+        let mut items = Vec::new();
+        let mut walk = FileLogWalker::new(&repo, "bar");
+        walk.read(&mut items, 100).unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                FileHistoryEntry {
+                    commit_id: oid2,
+                    path: String::from("bar")
+                },
+                FileHistoryEntry {
+                    commit_id: oid1,
+                    path: String::from("foo")
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_history_removed() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("foo"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let oid1 = commit(repo_path, "commit1").unwrap();
+        fs::remove_file(root.join("foo"))?;
+        stage_addremoved(repo_path, Path::new("foo")).unwrap();
+        let oid2 = commit(repo_path, "commit2").unwrap();
+        File::create(&root.join("bar"))?.write_all(b"b")?;
+        stage_add_file(repo_path, Path::new("bar")).unwrap();
+        commit(repo_path, "commit3").unwrap();
+
+        let mut items = Vec::new();
+        let mut walk = FileLogWalker::new(&repo, "foo");
+        walk.read(&mut items, 100).unwrap();
+
+        assert_eq!(
+            items.iter().map(|e| e.commit_id).collect::<Vec<_>>(),
+            vec![oid2, oid1]
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,274 @@
+//! merging upstream changes into local branches
+
+use super::{
+    commit::signature_allow_undefined_name, utils, CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{build::CheckoutBuilder, BranchType, Index, Repository};
+use scopetime::scope_time;
+
+/// outcome of `merge_upstream`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeResult {
+    /// nothing to merge
+    UpToDate,
+    /// branch was fast-forwarded to its upstream
+    FastForward,
+    /// merge commit that was created
+    MergeCommit(CommitId),
+}
+
+/// merges the upstream of local branch `branch_name` into it.
+/// fast-forwards if possible, creates a merge commit otherwise.
+///
+/// in case of conflicts the merge is left in progress (like git does)
+/// and `Error::Conflicts` is returned.
+pub fn merge_upstream(
+    repo_path: &str,
+    branch_name: &str,
+) -> Result<MergeResult> {
+    scope_time!("merge_upstream");
+
+    let repo = utils::repo(repo_path)?;
+
+    let branch = repo.find_branch(branch_name, BranchType::Local)?;
+    let upstream = branch.upstream()?;
+    let upstream_name =
+        String::from_utf8(upstream.name_bytes()?.to_vec())?;
+    let upstream_id = upstream.get().target().ok_or_else(|| {
+        Error::Generic(format!("invalid upstream: {}", upstream_name))
+    })?;
+
+    if !branch.is_head() {
+        return Err(Error::Generic(
+            "can only merge into the checked out branch".into(),
+        ));
+    }
+
+    let annotated = repo.find_annotated_commit(upstream_id)?;
+    let (analysis, _) = repo.merge_analysis(&[&annotated])?;
+
+    if analysis.is_up_to_date() {
+        return Ok(MergeResult::UpToDate);
+    }
+
+    if analysis.is_fast_forward() {
+        let upstream_commit = repo.find_commit(upstream_id)?;
+
+        repo.checkout_tree(
+            upstream_commit.as_object(),
+            Some(CheckoutBuilder::new().safe()),
+        )?;
+
+        branch.into_reference().set_target(
+            upstream_id,
+            format!("merge {}: Fast-forward", upstream_name).as_str(),
+        )?;
+
+        return Ok(MergeResult::FastForward);
+    }
+
+    if analysis.is_normal() {
+        repo.merge(&[&annotated], None, None)?;
+
+        let mut index = repo.index()?;
+
+        if index.has_conflicts() {
+            return Err(Error::Conflicts(conflicted_paths(&index)?));
+        }
+
+        let id = commit_merge(
+            &repo,
+            &mut index,
+            upstream_id,
+            format!(
+                "Merge remote-tracking branch '{}'",
+                upstream_name
+            )
+            .as_str(),
+        )?;
+
+        repo.cleanup_state()?;
+
+        return Ok(MergeResult::MergeCommit(id));
+    }
+
+    Err(Error::Generic(format!(
+        "cannot merge {}: {:?}",
+        upstream_name, analysis
+    )))
+}
+
+/// paths of all conflicted entries in `index`
+pub(crate) fn conflicted_paths(index: &Index) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+
+        if let Some(entry) =
+            conflict.our.or(conflict.their).or(conflict.ancestor)
+        {
+            paths.push(String::from_utf8(entry.path)?);
+        }
+    }
+
+    Ok(paths)
+}
+
+fn commit_merge(
+    repo: &Repository,
+    index: &mut Index,
+    other: git2::Oid,
+    msg: &str,
+) -> Result<CommitId> {
+    let signature = signature_allow_undefined_name(repo)?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+
+    let head = repo.head()?.peel_to_commit()?;
+    let other = repo.find_commit(other)?;
+
+    Ok(repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            msg,
+            &tree,
+            &[&head, &other],
+        )?
+        .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{tests::repo_init, CommitId};
+    use git2::Commit;
+    use std::{fs::File, io::Write, path::Path};
+    use tempfile::TempDir;
+
+    fn commit_file(
+        repo: &Repository,
+        file: &str,
+        content: &str,
+        msg: &str,
+    ) -> CommitId {
+        let root = repo.path().parent().unwrap();
+        File::create(&root.join(file))
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree =
+            repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+        let sig = repo.signature().unwrap();
+        let head: Commit =
+            repo.head().unwrap().peel_to_commit().unwrap();
+
+        repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &[&head])
+            .unwrap()
+            .into()
+    }
+
+    /// returns (upstream, clone) where `clone` tracks `upstream/master`
+    fn clone_repo() -> (TempDir, Repository, TempDir, Repository) {
+        let (upstream_dir, upstream) = repo_init().unwrap();
+        let td = TempDir::new().unwrap();
+
+        let url = format!(
+            "file://{}",
+            upstream_dir.path().as_os_str().to_str().unwrap()
+        );
+        let clone =
+            Repository::clone(url.as_str(), td.path()).unwrap();
+        {
+            let mut config = clone.config().unwrap();
+            config.set_str("user.name", "name").unwrap();
+            config.set_str("user.email", "email").unwrap();
+        }
+
+        (upstream_dir, upstream, td, clone)
+    }
+
+    fn fetch_clone(path: &str) {
+        crate::sync::fetch(path, "origin", "master", None).unwrap();
+    }
+
+    #[test]
+    fn test_up_to_date() {
+        let (_ud, _upstream, td, _clone) = clone_repo();
+        let repo_path = td.path().as_os_str().to_str().unwrap();
+
+        assert_eq!(
+            merge_upstream(repo_path, "master").unwrap(),
+            MergeResult::UpToDate
+        );
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let (_ud, upstream, td, clone) = clone_repo();
+        let repo_path = td.path().as_os_str().to_str().unwrap();
+
+        let id =
+            commit_file(&upstream, "foo.txt", "test", "upstream");
+
+        fetch_clone(repo_path);
+
+        assert_eq!(
+            merge_upstream(repo_path, "master").unwrap(),
+            MergeResult::FastForward
+        );
+
+        assert_eq!(
+            clone.head().unwrap().target().unwrap(),
+            id.into()
+        );
+        assert!(td.path().join("foo.txt").exists());
+    }
+
+    #[test]
+    fn test_merge_commit() {
+        let (_ud, upstream, td, clone) = clone_repo();
+        let repo_path = td.path().as_os_str().to_str().unwrap();
+
+        commit_file(&upstream, "foo.txt", "test", "upstream");
+        commit_file(&clone, "bar.txt", "test", "local");
+
+        fetch_clone(repo_path);
+
+        let res = merge_upstream(repo_path, "master").unwrap();
+
+        assert!(matches!(res, MergeResult::MergeCommit(_)));
+        if let MergeResult::MergeCommit(id) = res {
+            let commit = clone.find_commit(id.into()).unwrap();
+            assert_eq!(commit.parent_count(), 2);
+        }
+        assert!(td.path().join("foo.txt").exists());
+        assert_eq!(clone.state(), git2::RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_conflict() {
+        let (_ud, upstream, td, clone) = clone_repo();
+        let repo_path = td.path().as_os_str().to_str().unwrap();
+
+        commit_file(&upstream, "foo.txt", "upstream", "upstream");
+        commit_file(&clone, "foo.txt", "local", "local");
+
+        fetch_clone(repo_path);
+
+        let res = merge_upstream(repo_path, "master");
+
+        assert!(matches!(
+            res,
+            Err(Error::Conflicts(paths)) if paths == vec![String::from("foo.txt")]
+        ));
+        assert_eq!(clone.state(), git2::RepositoryState::Merge);
+    }
+}
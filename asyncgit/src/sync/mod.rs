@@ -1,39 +1,93 @@
 //! sync git api
 
+mod blame;
 mod branch;
+mod cherry_pick;
 mod commit;
 mod commit_details;
 mod commit_files;
+mod commit_graph;
 mod commits_info;
+mod conflict;
+mod cred;
 pub mod diff;
 mod hooks;
 mod hunks;
 mod ignore;
+mod log_search;
 mod logwalker;
+mod merge;
+mod rebase;
 mod remotes;
 mod reset;
+mod sign;
 mod stash;
+mod state;
 pub mod status;
 mod tags;
 pub mod utils;
+mod word_diff;
 
-pub use branch::create_branch;
+pub use blame::{blame_file, BlameLine, FileBlame};
 pub(crate) use branch::get_branch_name;
-pub use commit::{amend, commit, tag};
+pub use branch::{
+    checkout_branch, checkout_commit, create_branch,
+    create_branch_at, delete_branch, get_branches_info,
+    rename_branch, BranchCompare, BranchInfo,
+};
+pub use cherry_pick::{cherry_pick, revert_commit};
+pub use commit::{
+    amend, commit, commit_template, tag, tag_annotated, tag_gpg_sign,
+};
 pub use commit_details::{
     get_commit_details, CommitDetails, CommitMessage,
 };
-pub use commit_files::get_commit_files;
+pub use commit_files::{
+    get_commit_files, get_compare_commits_files,
+    get_compare_workdir_files,
+};
+pub use commit_graph::{CommitGraph, GraphCell, GraphRow};
 pub use commits_info::{get_commits_info, CommitId, CommitInfo};
-pub use diff::get_diff_commit;
+pub use conflict::{
+    checkout_conflict_side, get_conflicts, resolve_conflict,
+    ConflictSide,
+};
+pub use cred::{
+    extract_username_password, need_username_password,
+    resolve_credential, BasicAuthCredential,
+};
+pub use diff::{
+    get_diff_commit, get_diff_commit_workdir, get_diff_commits,
+};
 pub use hooks::{hooks_commit_msg, hooks_post_commit, HookResult};
-pub use hunks::{reset_hunk, stage_hunk, unstage_hunk};
-pub use ignore::add_to_ignore;
-pub use logwalker::LogWalker;
-pub use remotes::{fetch_origin, get_remotes, push_origin};
-pub use reset::{reset_stage, reset_workdir};
-pub use stash::{get_stashes, stash_apply, stash_drop, stash_save};
-pub use tags::{get_tags, CommitTags, Tags};
+pub use hunks::{
+    reset_hunk, reset_lines, stage_hunk, stage_lines, unstage_hunk,
+    unstage_lines,
+};
+pub use ignore::{add_to_ignore, any_path_not_ignored};
+pub use log_search::LogSearch;
+pub use logwalker::{FileHistoryEntry, FileLogWalker, LogWalker};
+pub use merge::{merge_upstream, MergeResult};
+pub use rebase::{
+    get_rebase_plan, InteractiveRebase, RebaseAction, RebaseStatus,
+    RebaseStep,
+};
+pub use remotes::{
+    fetch, get_branch_remote, get_default_remote, get_remotes,
+    push_origin, push_tag, ProgressNotification, DEFAULT_REMOTE_NAME,
+};
+pub use reset::{
+    reset_branch, reset_stage, reset_workdir, ResetType,
+};
+pub use stash::{
+    get_stashes, get_stashes_info, stash_apply, stash_drop,
+    stash_pop, stash_save, stash_save_paths, stash_to_branch,
+    StashInfo,
+};
+pub use state::{repo_state, RepoState};
+pub use tags::{
+    delete_tag, get_tags, get_tags_info, CommitTags, TagInfo, Tags,
+};
 pub use utils::{
     get_head, get_head_tuple, is_bare_repo, is_repo, stage_add_all,
     stage_add_file, stage_addremoved, Head,
@@ -0,0 +1,1141 @@
+//! interactive rebase: rewrite a range of commits following a plan
+
+use super::{
+    commit::signature_allow_undefined_name,
+    commits_info::get_message, merge::conflicted_paths, utils,
+    CommitId,
+};
+use crate::error::{Error, Result};
+use git2::{
+    build::CheckoutBuilder, ErrorCode, Rebase, Repository,
+    RepositoryState, ResetType, StatusOptions,
+};
+use scopetime::scope_time;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// file in the git dir keeping the plan of a paused rebase, so that
+/// it can be continued after a restart
+const PLAN_FILE: &str = "gitui/rebase-plan";
+
+/// what to do with a commit of the plan
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RebaseAction {
+    /// use commit
+    Pick,
+    /// use commit, but change its message
+    Reword,
+    /// meld into previous commit, combining both messages
+    Squash,
+    /// meld into previous commit, keeping only its message
+    Fixup,
+    /// remove commit
+    Drop,
+}
+
+/// one entry of the rebase plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebaseStep {
+    ///
+    pub id: CommitId,
+    ///
+    pub action: RebaseAction,
+    /// full commit message (the new one in case of `Reword`)
+    pub message: String,
+}
+
+impl RebaseStep {
+    /// first line of `message`
+    pub fn summary(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// outcome of `InteractiveRebase::run`
+#[derive(Debug, Clone, PartialEq)]
+pub enum RebaseStatus {
+    /// all steps were applied and the branch points to the result
+    Finished,
+    /// the step at this position caused conflicts in these paths,
+    /// resolve them and `run` again to continue
+    Conflicts(usize, Vec<String>),
+}
+
+/// returns the default plan (pick everything) to rewrite all commits
+/// from `base` (inclusive) up to HEAD, oldest first
+pub fn get_rebase_plan(
+    repo_path: &str,
+    base: CommitId,
+) -> Result<Vec<RebaseStep>> {
+    scope_time!("get_rebase_plan");
+
+    let repo = utils::repo(repo_path)?;
+
+    let base = repo.find_commit(base.into())?;
+    let head = utils::get_head_repo(&repo)?;
+
+    let mut walk = repo.revwalk()?;
+    walk.push(head.into())?;
+    for parent in base.parent_ids() {
+        walk.hide(parent)?;
+    }
+
+    let mut steps = Vec::new();
+    for id in walk {
+        let commit = repo.find_commit(id?)?;
+
+        steps.push(RebaseStep {
+            id: commit.id().into(),
+            action: RebaseAction::Pick,
+            message: get_message(&commit, None),
+        });
+    }
+
+    if !steps.iter().any(|step| step.id == base.id().into()) {
+        return Err(Error::Generic(
+            "commit is not an ancestor of HEAD".to_string(),
+        ));
+    }
+
+    steps.reverse();
+
+    Ok(steps)
+}
+
+/// a rebase plan that is (being) applied step by step using
+/// libgit2's rebase machinery, one commit at a time so that the
+/// plan can reorder commits freely
+pub struct InteractiveRebase {
+    repo_path: String,
+    steps: Vec<RebaseStep>,
+    position: usize,
+    paused: bool,
+    /// the step paused on is applied to the index (it stopped on
+    /// conflicts or failed to commit), otherwise it runs again
+    step_applied: bool,
+    started: bool,
+    /// planned in gitui, not started by another tool
+    own: bool,
+    orig_head_ref: Option<String>,
+    orig_head: CommitId,
+    onto: CommitId,
+}
+
+impl InteractiveRebase {
+    /// validates `steps` (oldest first, see `get_rebase_plan`)
+    pub fn new(
+        repo_path: &str,
+        steps: Vec<RebaseStep>,
+    ) -> Result<Self> {
+        scope_time!("InteractiveRebase::new");
+
+        let repo = utils::repo(repo_path)?;
+
+        if repo.state() != RepositoryState::Clean {
+            return Err(Error::Generic(
+                "another operation (merge/rebase/..) is in progress"
+                    .to_string(),
+            ));
+        }
+
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(false).include_ignored(false);
+        if !repo.statuses(Some(&mut opts))?.is_empty() {
+            return Err(Error::Generic(
+                "cannot rebase: there are uncommitted changes"
+                    .to_string(),
+            ));
+        }
+
+        let first_kept =
+            steps.iter().find(|s| s.action != RebaseAction::Drop);
+        if let Some(step) = first_kept {
+            if step.action == RebaseAction::Squash
+                || step.action == RebaseAction::Fixup
+            {
+                return Err(Error::Generic(
+                    "cannot squash/fixup without a previous commit"
+                        .to_string(),
+                ));
+            }
+        }
+
+        if steps.is_empty() {
+            return Err(Error::Generic(
+                "nothing to rebase".to_string(),
+            ));
+        }
+
+        // the plan may be reordered, the commit we rebase onto is the
+        // parent that is not part of the plan itself
+        let mut onto = None;
+        for step in &steps {
+            let commit = repo.find_commit(step.id.into())?;
+            match commit.parent_count() {
+                0 => {
+                    return Err(Error::Generic(
+                        "cannot rebase the root commit".to_string(),
+                    ))
+                }
+                1 => (),
+                _ => {
+                    return Err(Error::Generic(
+                        "cannot rebase merge commits".to_string(),
+                    ))
+                }
+            }
+
+            let parent: CommitId = commit.parent_id(0)?.into();
+            if !steps.iter().any(|s| s.id == parent) {
+                onto = Some(parent);
+            }
+        }
+        let onto = onto.ok_or_else(|| {
+            Error::Generic("invalid rebase plan".to_string())
+        })?;
+
+        let head = repo.head()?;
+        let orig_head_ref = if repo.head_detached()? {
+            None
+        } else {
+            Some(String::from_utf8(head.name_bytes().to_vec())?)
+        };
+        let orig_head = utils::get_head_repo(&repo)?;
+
+        Ok(Self {
+            repo_path: repo_path.to_string(),
+            steps,
+            position: 0,
+            paused: false,
+            step_applied: false,
+            started: false,
+            own: true,
+            orig_head_ref,
+            orig_head,
+            onto,
+        })
+    }
+
+    /// reopens a rebase left paused by a previous session, or one
+    /// started by another tool using the merge backend
+    /// (e.g. `git rebase -m`), `None` if there is no rebase in
+    /// progress that gitui can continue
+    pub fn resume(repo_path: &str) -> Result<Option<Self>> {
+        scope_time!("InteractiveRebase::resume");
+
+        let repo = utils::repo(repo_path)?;
+        let plan_file = plan_file(&repo);
+
+        let rebasing = matches!(
+            repo.state(),
+            RepositoryState::Rebase
+                | RepositoryState::RebaseInteractive
+                | RepositoryState::RebaseMerge
+        );
+
+        if plan_file.exists() {
+            let (rebase, head) =
+                Self::read_plan(repo_path, &plan_file)?;
+
+            // stopped in between two steps the repo is not in a rebase
+            // state, only HEAD tells whether it was left alone since
+            let paused = match head {
+                Some(head) => {
+                    repo.head_detached()?
+                        && utils::get_head_repo(&repo)? == head
+                }
+                None => rebasing,
+            };
+            if paused {
+                return Ok(Some(rebase));
+            }
+
+            // finished or aborted outside of gitui
+            fs::remove_file(plan_file)?;
+            return Ok(None);
+        }
+
+        if !rebasing {
+            return Ok(None);
+        }
+
+        let mut rebase = match repo.open_rebase(None) {
+            Ok(rebase) => rebase,
+            // not one of the merge backend
+            Err(_) => return Ok(None),
+        };
+        let mut steps = Vec::with_capacity(rebase.len());
+        for idx in 0..rebase.len() {
+            if let Some(operation) = rebase.nth(idx) {
+                let commit = repo.find_commit(operation.id())?;
+                steps.push(RebaseStep {
+                    id: commit.id().into(),
+                    action: RebaseAction::Pick,
+                    message: get_message(&commit, None),
+                });
+            }
+        }
+
+        let orig_head = rebase.orig_head_id().ok_or_else(|| {
+            Error::Generic("rebase without original head".to_string())
+        })?;
+
+        Ok(Some(Self {
+            repo_path: repo_path.to_string(),
+            steps,
+            position: rebase.operation_current().unwrap_or_default(),
+            paused: true,
+            step_applied: true,
+            started: true,
+            own: false,
+            orig_head_ref: rebase.orig_head_name().map(String::from),
+            orig_head: orig_head.into(),
+            // only needed to start, which happened already
+            onto: orig_head.into(),
+        }))
+    }
+
+    ///
+    pub fn steps(&self) -> &[RebaseStep] {
+        &self.steps
+    }
+
+    /// index of the step that is applied next (or paused on)
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// true if a step stopped because of conflicts or an error
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// false for a rebase started by another tool
+    pub const fn is_own(&self) -> bool {
+        self.own
+    }
+
+    /// applies the steps, starting or continuing where it stopped.
+    /// when paused the conflicts need to be resolved (and staged)
+    /// first. an error pauses the rebase as well, keeping the steps
+    /// applied so far to continue (or abort) once the cause is fixed
+    pub fn run(&mut self) -> Result<RebaseStatus> {
+        scope_time!("InteractiveRebase::run");
+
+        let repo = utils::repo(self.repo_path.as_str())?;
+
+        let res = self.run_steps(&repo);
+
+        if res.is_err() && self.started {
+            self.paused = true;
+            if let Err(e) = self.write_plan(&repo) {
+                log::error!("rebase plan not saved: {}", e);
+            }
+        }
+
+        res
+    }
+
+    fn run_steps(
+        &mut self,
+        repo: &Repository,
+    ) -> Result<RebaseStatus> {
+        if self.paused {
+            if self.step_applied {
+                let index = repo.index()?;
+                if index.has_conflicts() {
+                    return Ok(RebaseStatus::Conflicts(
+                        self.position,
+                        conflicted_paths(&index)?,
+                    ));
+                }
+
+                let mut rebase = repo.open_rebase(None)?;
+                self.commit_step(repo, &mut rebase)?;
+                rebase.finish(None)?;
+
+                self.step_applied = false;
+                self.position += 1;
+            }
+
+            self.paused = false;
+        } else if !self.started {
+            let onto = repo.find_commit(self.onto.into())?;
+            repo.checkout_tree(
+                onto.as_object(),
+                Some(CheckoutBuilder::new().safe()),
+            )?;
+            repo.set_head_detached(onto.id())?;
+            self.started = true;
+        }
+
+        while self.position < self.steps.len() {
+            let step = &self.steps[self.position];
+
+            if step.action == RebaseAction::Drop {
+                self.position += 1;
+                continue;
+            }
+
+            let commit = repo.find_commit(step.id.into())?;
+            let branch = repo.find_annotated_commit(commit.id())?;
+            let upstream =
+                repo.find_annotated_commit(commit.parent_id(0)?)?;
+            let onto = repo.find_annotated_commit(
+                utils::get_head_repo(repo)?.into(),
+            )?;
+
+            let mut rebase = repo.rebase(
+                Some(&branch),
+                Some(&upstream),
+                Some(&onto),
+                None,
+            )?;
+
+            if let Some(Err(e)) = rebase.next() {
+                // nothing of the step got applied, it starts over
+                repo.cleanup_state()?;
+                return Err(e.into());
+            }
+            self.step_applied = true;
+
+            let index = repo.index()?;
+            if index.has_conflicts() {
+                self.paused = true;
+                self.write_plan(repo)?;
+                return Ok(RebaseStatus::Conflicts(
+                    self.position,
+                    conflicted_paths(&index)?,
+                ));
+            }
+
+            self.commit_step(repo, &mut rebase)?;
+            rebase.finish(None)?;
+
+            self.step_applied = false;
+            self.position += 1;
+        }
+
+        self.finish(repo)?;
+
+        Ok(RebaseStatus::Finished)
+    }
+
+    /// stops the rebase and restores the branch as it was before
+    pub fn abort(&mut self) -> Result<()> {
+        scope_time!("InteractiveRebase::abort");
+
+        let repo = utils::repo(self.repo_path.as_str())?;
+
+        if repo.state() == RepositoryState::RebaseMerge {
+            repo.open_rebase(None)?.abort()?;
+        }
+
+        self.restore_head(&repo, self.orig_head)?;
+
+        let orig = repo.find_commit(self.orig_head.into())?;
+        repo.reset(orig.as_object(), ResetType::Hard, None)?;
+
+        self.paused = false;
+        self.step_applied = false;
+        self.position = self.steps.len();
+
+        remove_plan(&repo)?;
+
+        Ok(())
+    }
+
+    fn commit_step(
+        &self,
+        repo: &Repository,
+        rebase: &mut Rebase,
+    ) -> Result<()> {
+        let step = &self.steps[self.position];
+        let signature = signature_allow_undefined_name(repo)?;
+
+        match step.action {
+            RebaseAction::Pick | RebaseAction::Reword => {
+                let message = if step.action == RebaseAction::Reword {
+                    Some(step.message.as_str())
+                } else {
+                    None
+                };
+
+                match rebase.commit(None, &signature, message) {
+                    // changes are already upstream, nothing to commit
+                    Err(e) if e.code() == ErrorCode::Applied => (),
+                    res => {
+                        res?;
+                    }
+                }
+            }
+            RebaseAction::Squash | RebaseAction::Fixup => {
+                let tree =
+                    repo.find_tree(repo.index()?.write_tree()?)?;
+                let head = repo.head()?.peel_to_commit()?;
+
+                let message = if step.action == RebaseAction::Squash {
+                    format!(
+                        "{}\n\n{}",
+                        get_message(&head, None).trim_end(),
+                        step.message
+                    )
+                } else {
+                    get_message(&head, None)
+                };
+
+                head.amend(
+                    Some("HEAD"),
+                    None,
+                    Some(&signature),
+                    None,
+                    Some(message.as_str()),
+                    Some(&tree),
+                )?;
+            }
+            RebaseAction::Drop => (),
+        }
+
+        Ok(())
+    }
+
+    fn finish(&self, repo: &Repository) -> Result<()> {
+        let new_head = if self.started {
+            utils::get_head_repo(repo)?
+        } else {
+            self.onto
+        };
+
+        self.restore_head(repo, new_head)?;
+
+        remove_plan(repo)?;
+
+        Ok(())
+    }
+
+    /// one `key value` per line, the steps last (messages escaped)
+    fn write_plan(&self, repo: &Repository) -> Result<()> {
+        let mut plan = format!(
+            "onto {}\norig_head {}\nposition {}\n",
+            self.onto.to_string(),
+            self.orig_head.to_string(),
+            self.position
+        );
+        if let Some(name) = &self.orig_head_ref {
+            plan.push_str(&format!("orig_head_ref {}\n", name));
+        }
+        if !self.step_applied {
+            plan.push_str(&format!(
+                "head {}\n",
+                utils::get_head_repo(repo)?.to_string()
+            ));
+        }
+        for step in &self.steps {
+            plan.push_str(&format!(
+                "step {} {} {}\n",
+                action_name(step.action),
+                step.id.to_string(),
+                escape(&step.message)
+            ));
+        }
+
+        let file = plan_file(repo);
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(file, plan)?;
+
+        Ok(())
+    }
+
+    /// also returns the HEAD the plan stopped at if it stopped in
+    /// between two steps
+    fn read_plan(
+        repo_path: &str,
+        file: &Path,
+    ) -> Result<(Self, Option<CommitId>)> {
+        let invalid =
+            || Error::Generic("invalid rebase plan file".to_string());
+        let parse_id = |id: &str| -> Result<CommitId> {
+            Ok(git2::Oid::from_str(id)?.into())
+        };
+
+        let mut onto = None;
+        let mut orig_head = None;
+        let mut orig_head_ref = None;
+        let mut position = None;
+        let mut head = None;
+        let mut steps = Vec::new();
+
+        for line in fs::read_to_string(file)?.lines() {
+            let mut parts = line.splitn(2, ' ');
+            let key = parts.next().unwrap_or_default();
+            let value = parts.next().ok_or_else(invalid)?;
+
+            match key {
+                "onto" => onto = Some(parse_id(value)?),
+                "orig_head" => orig_head = Some(parse_id(value)?),
+                "orig_head_ref" => {
+                    orig_head_ref = Some(value.to_string())
+                }
+                "head" => head = Some(parse_id(value)?),
+                "position" => {
+                    position =
+                        Some(value.parse().map_err(|_| invalid())?)
+                }
+                "step" => {
+                    let mut parts = value.splitn(3, ' ');
+                    let action = parts
+                        .next()
+                        .and_then(parse_action)
+                        .ok_or_else(invalid)?;
+                    let id =
+                        parse_id(parts.next().ok_or_else(invalid)?)?;
+                    let message =
+                        unescape(parts.next().unwrap_or_default());
+
+                    steps.push(RebaseStep {
+                        id,
+                        action,
+                        message,
+                    });
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        let rebase = Self {
+            repo_path: repo_path.to_string(),
+            steps,
+            position: position.ok_or_else(invalid)?,
+            paused: true,
+            step_applied: head.is_none(),
+            started: true,
+            own: true,
+            orig_head_ref,
+            orig_head: orig_head.ok_or_else(invalid)?,
+            onto: onto.ok_or_else(invalid)?,
+        };
+
+        Ok((rebase, head))
+    }
+
+    /// points the original branch (or detached HEAD) to `id`
+    fn restore_head(
+        &self,
+        repo: &Repository,
+        id: CommitId,
+    ) -> Result<()> {
+        match &self.orig_head_ref {
+            Some(name) => {
+                repo.find_reference(name.as_str())?
+                    .set_target(id.into(), "rebase (interactive)")?;
+                repo.set_head(name.as_str())?;
+            }
+            None => repo.set_head_detached(id.into())?,
+        }
+
+        Ok(())
+    }
+}
+
+fn plan_file(repo: &Repository) -> PathBuf {
+    repo.path().join(PLAN_FILE)
+}
+
+fn remove_plan(repo: &Repository) -> Result<()> {
+    let file = plan_file(repo);
+    if file.exists() {
+        fs::remove_file(file)?;
+    }
+
+    Ok(())
+}
+
+const fn action_name(action: RebaseAction) -> &'static str {
+    match action {
+        RebaseAction::Pick => "pick",
+        RebaseAction::Reword => "reword",
+        RebaseAction::Squash => "squash",
+        RebaseAction::Fixup => "fixup",
+        RebaseAction::Drop => "drop",
+    }
+}
+
+fn parse_action(name: &str) -> Option<RebaseAction> {
+    match name {
+        "pick" => Some(RebaseAction::Pick),
+        "reword" => Some(RebaseAction::Reword),
+        "squash" => Some(RebaseAction::Squash),
+        "fixup" => Some(RebaseAction::Fixup),
+        "drop" => Some(RebaseAction::Drop),
+        _ => None,
+    }
+}
+
+/// keeps a message on a single line
+fn escape(msg: &str) -> String {
+    msg.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(msg: &str) -> String {
+    let mut res = String::with_capacity(msg.len());
+    let mut chars = msg.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => res.push('\n'),
+                Some(c) => res.push(c),
+                None => (),
+            }
+        } else {
+            res.push(c);
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        get_commits_info, resolve_conflict, tests::repo_init,
+        LogWalker,
+    };
+    use std::{fs::File, io::Write, path::Path};
+
+    fn commit_file(
+        repo: &Repository,
+        file: &str,
+        content: &str,
+        msg: &str,
+    ) -> CommitId {
+        let root = repo.path().parent().unwrap();
+        File::create(&root.join(file))
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree =
+            repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+        let sig = repo.signature().unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+
+        repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &[&head])
+            .unwrap()
+            .into()
+    }
+
+    fn messages(repo_path: &str) -> Vec<String> {
+        let repo = utils::repo(repo_path).unwrap();
+        let mut ids = Vec::new();
+        LogWalker::new(&repo).read(&mut ids, 100).unwrap();
+
+        get_commits_info(repo_path, &ids, 100)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_plan() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let c1 = commit_file(&repo, "a.txt", "a", "c1");
+        let c2 = commit_file(&repo, "b.txt", "b", "c2");
+
+        let steps = get_rebase_plan(repo_path, c1).unwrap();
+
+        assert_eq!(
+            steps.iter().map(|s| s.id).collect::<Vec<_>>(),
+            vec![c1, c2]
+        );
+        assert!(steps.iter().all(|s| s.action == RebaseAction::Pick));
+        assert_eq!(steps[0].summary(), "c1");
+    }
+
+    #[test]
+    fn test_reorder_reword_drop() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let c1 = commit_file(&repo, "a.txt", "a", "c1");
+        commit_file(&repo, "b.txt", "b", "c2");
+        commit_file(&repo, "c.txt", "c", "c3");
+
+        let mut steps = get_rebase_plan(repo_path, c1).unwrap();
+        steps.swap(0, 2);
+        steps[1].action = RebaseAction::Drop;
+        steps[2].action = RebaseAction::Reword;
+        steps[2].message = String::from("reworded");
+
+        let mut rebase =
+            InteractiveRebase::new(repo_path, steps).unwrap();
+
+        assert_eq!(rebase.run().unwrap(), RebaseStatus::Finished);
+
+        assert_eq!(
+            messages(repo_path),
+            vec!["reworded", "c3", "initial"]
+        );
+        assert!(!root.join("b.txt").exists());
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert!(!repo.head_detached().unwrap());
+    }
+
+    #[test]
+    fn test_squash_fixup() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let c1 = commit_file(&repo, "a.txt", "a", "c1");
+        commit_file(&repo, "b.txt", "b", "c2");
+        commit_file(&repo, "c.txt", "c", "c3");
+
+        let mut steps = get_rebase_plan(repo_path, c1).unwrap();
+        steps[1].action = RebaseAction::Squash;
+        steps[2].action = RebaseAction::Fixup;
+
+        let mut rebase =
+            InteractiveRebase::new(repo_path, steps).unwrap();
+
+        assert_eq!(rebase.run().unwrap(), RebaseStatus::Finished);
+
+        assert_eq!(messages(repo_path), vec!["c1", "initial"]);
+        assert_eq!(
+            repo.head().unwrap().peel_to_commit().unwrap().message(),
+            Some("c1\n\nc2")
+        );
+        assert!(root.join("b.txt").exists());
+        assert!(root.join("c.txt").exists());
+    }
+
+    #[test]
+    fn test_invalid_plans() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let c1 = commit_file(&repo, "a.txt", "a", "c1");
+
+        let mut steps = get_rebase_plan(repo_path, c1).unwrap();
+        steps[0].action = RebaseAction::Squash;
+        assert!(InteractiveRebase::new(repo_path, steps).is_err());
+
+        assert!(
+            InteractiveRebase::new(repo_path, Vec::new()).is_err()
+        );
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"dirty")
+            .unwrap();
+        let steps = get_rebase_plan(repo_path, c1).unwrap();
+        assert!(InteractiveRebase::new(repo_path, steps).is_err());
+    }
+
+    #[test]
+    fn test_conflict_continue() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let c1 = commit_file(&repo, "a.txt", "1", "c1");
+        commit_file(&repo, "a.txt", "2", "c2");
+
+        // swapping both makes c2 conflict with the base
+        let mut steps = get_rebase_plan(repo_path, c1).unwrap();
+        steps.swap(0, 1);
+
+        let mut rebase =
+            InteractiveRebase::new(repo_path, steps).unwrap();
+
+        let res = rebase.run().unwrap();
+        assert_eq!(
+            res,
+            RebaseStatus::Conflicts(0, vec![String::from("a.txt")])
+        );
+        assert!(rebase.is_paused());
+
+        // still conflicted
+        assert!(matches!(
+            rebase.run().unwrap(),
+            RebaseStatus::Conflicts(..)
+        ));
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"2")
+            .unwrap();
+        resolve_conflict(repo_path, "a.txt").unwrap();
+
+        let res = rebase.run().unwrap();
+        assert!(matches!(res, RebaseStatus::Conflicts(1, _)));
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"1")
+            .unwrap();
+        resolve_conflict(repo_path, "a.txt").unwrap();
+
+        assert_eq!(rebase.run().unwrap(), RebaseStatus::Finished);
+        assert_eq!(messages(repo_path), vec!["c1", "c2", "initial"]);
+        assert_eq!(repo.state(), RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_abort() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let c1 = commit_file(&repo, "a.txt", "1", "c1");
+        let c2 = commit_file(&repo, "a.txt", "2", "c2");
+
+        let mut steps = get_rebase_plan(repo_path, c1).unwrap();
+        steps.swap(0, 1);
+
+        let mut rebase =
+            InteractiveRebase::new(repo_path, steps).unwrap();
+
+        assert!(matches!(
+            rebase.run().unwrap(),
+            RebaseStatus::Conflicts(..)
+        ));
+
+        rebase.abort().unwrap();
+
+        assert_eq!(utils::get_head(repo_path).unwrap(), c2);
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert!(!repo.head_detached().unwrap());
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.txt")).unwrap(),
+            "2"
+        );
+    }
+
+    #[test]
+    fn test_resume_paused() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert!(InteractiveRebase::resume(repo_path)
+            .unwrap()
+            .is_none());
+
+        let c1 = commit_file(&repo, "a.txt", "1", "c1");
+        commit_file(&repo, "a.txt", "2", "c2");
+
+        let mut steps = get_rebase_plan(repo_path, c1).unwrap();
+        steps.swap(0, 1);
+        steps[1].action = RebaseAction::Reword;
+        steps[1].message = String::from("c1\\\n\nbody");
+
+        let mut rebase =
+            InteractiveRebase::new(repo_path, steps.clone()).unwrap();
+        assert!(matches!(
+            rebase.run().unwrap(),
+            RebaseStatus::Conflicts(0, _)
+        ));
+
+        // like a restart of the app while paused
+        drop(rebase);
+        let mut rebase =
+            InteractiveRebase::resume(repo_path).unwrap().unwrap();
+
+        assert!(rebase.is_paused());
+        assert!(rebase.is_own());
+        assert_eq!(rebase.position(), 0);
+        assert_eq!(rebase.steps(), steps.as_slice());
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"2")
+            .unwrap();
+        resolve_conflict(repo_path, "a.txt").unwrap();
+        assert!(matches!(
+            rebase.run().unwrap(),
+            RebaseStatus::Conflicts(1, _)
+        ));
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"1")
+            .unwrap();
+        resolve_conflict(repo_path, "a.txt").unwrap();
+
+        assert_eq!(rebase.run().unwrap(), RebaseStatus::Finished);
+        assert_eq!(
+            messages(repo_path),
+            vec!["c1\\", "c2", "initial"]
+        );
+        assert!(!repo.path().join(PLAN_FILE).exists());
+        assert!(InteractiveRebase::resume(repo_path)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_error_keeps_paused() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let c1 = commit_file(&repo, "a.txt", "1", "c1");
+        commit_file(&repo, "a.txt", "2", "c2");
+        commit_file(&repo, "b.txt", "b", "c3");
+
+        let mut steps = get_rebase_plan(repo_path, c1).unwrap();
+        steps.swap(0, 1);
+
+        let mut rebase =
+            InteractiveRebase::new(repo_path, steps).unwrap();
+
+        assert!(matches!(
+            rebase.run().unwrap(),
+            RebaseStatus::Conflicts(0, _)
+        ));
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"2")
+            .unwrap();
+        resolve_conflict(repo_path, "a.txt").unwrap();
+
+        assert!(matches!(
+            rebase.run().unwrap(),
+            RebaseStatus::Conflicts(1, _)
+        ));
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"1")
+            .unwrap();
+        resolve_conflict(repo_path, "a.txt").unwrap();
+
+        // the last step cannot check out b.txt over this one
+        File::create(&root.join("b.txt"))
+            .unwrap()
+            .write_all(b"untracked")
+            .unwrap();
+
+        assert!(rebase.run().is_err());
+        assert!(rebase.is_paused());
+        assert_eq!(rebase.position(), 2);
+        assert_eq!(messages(repo_path), vec!["c1", "c2", "initial"]);
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.txt")).unwrap(),
+            "1"
+        );
+
+        // like a restart of the app while paused
+        drop(rebase);
+        let mut rebase =
+            InteractiveRebase::resume(repo_path).unwrap().unwrap();
+        assert!(rebase.is_paused());
+        assert_eq!(rebase.position(), 2);
+
+        std::fs::remove_file(root.join("b.txt")).unwrap();
+
+        assert_eq!(rebase.run().unwrap(), RebaseStatus::Finished);
+        assert_eq!(
+            messages(repo_path),
+            vec!["c3", "c1", "c2", "initial"]
+        );
+        assert!(InteractiveRebase::resume(repo_path)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_resume_foreign() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let c1 = commit_file(&repo, "a.txt", "1", "c1");
+        let c2 = commit_file(&repo, "a.txt", "2", "c2");
+        commit_file(&repo, "b.txt", "b", "c3");
+
+        // `other` changes a.txt differently on top of c1
+        let sig = repo.signature().unwrap();
+        let mut index = git2::Index::new().unwrap();
+        index
+            .read_tree(
+                &repo.find_commit(c1.into()).unwrap().tree().unwrap(),
+            )
+            .unwrap();
+        let blob = repo.blob(b"x").unwrap();
+        let mut entry =
+            index.get_path(Path::new("a.txt"), 0).unwrap();
+        entry.id = blob;
+        entry.file_size = 1;
+        index.add(&entry).unwrap();
+        let tree = repo
+            .find_tree(index.write_tree_to(&repo).unwrap())
+            .unwrap();
+        let other = repo
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "other",
+                &tree,
+                &[&repo.find_commit(c1.into()).unwrap()],
+            )
+            .unwrap();
+
+        let mut rebase = repo
+            .rebase(
+                Some(
+                    &repo
+                        .reference_to_annotated_commit(
+                            &repo.head().unwrap(),
+                        )
+                        .unwrap(),
+                ),
+                Some(&repo.find_annotated_commit(c1.into()).unwrap()),
+                Some(&repo.find_annotated_commit(other).unwrap()),
+                None,
+            )
+            .unwrap();
+        rebase.next().unwrap().unwrap();
+        drop(rebase);
+        assert!(repo.index().unwrap().has_conflicts());
+
+        let mut rebase =
+            InteractiveRebase::resume(repo_path).unwrap().unwrap();
+
+        assert!(rebase.is_paused());
+        assert!(!rebase.is_own());
+        assert_eq!(rebase.position(), 0);
+        assert_eq!(rebase.steps()[0].id, c2);
+        assert_eq!(rebase.steps().len(), 2);
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"2")
+            .unwrap();
+        resolve_conflict(repo_path, "a.txt").unwrap();
+
+        assert_eq!(rebase.run().unwrap(), RebaseStatus::Finished);
+        assert_eq!(
+            messages(repo_path),
+            vec!["c3", "c2", "other", "c1", "initial"]
+        );
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert!(!repo.head_detached().unwrap());
+    }
+}
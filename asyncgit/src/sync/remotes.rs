@@ -1,9 +1,50 @@
 //!
 
-use crate::{error::Result, sync::utils};
-use git2::{Cred, FetchOptions, PushOptions, RemoteCallbacks};
+use crate::{
+    error::{Error, Result},
+    sync::{
+        cred::{BasicAuthCredential, CredentialProvider},
+        utils,
+    },
+};
+use crossbeam_channel::Sender;
+use git2::{
+    FetchOptions, PackBuilderStage, PushOptions, RemoteCallbacks,
+    Repository,
+};
 use scopetime::scope_time;
 
+/// progress reported by the remote callbacks while pushing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressNotification {
+    ///
+    PackingAddingObject {
+        ///
+        current: usize,
+        ///
+        total: usize,
+    },
+    ///
+    PackingDeltafication {
+        ///
+        current: usize,
+        ///
+        total: usize,
+    },
+    ///
+    PushTransfer {
+        ///
+        current: usize,
+        ///
+        total: usize,
+        ///
+        bytes: usize,
+    },
+}
+
+/// name of the remote we default to if nothing else is configured
+pub const DEFAULT_REMOTE_NAME: &str = "origin";
+
 ///
 pub fn get_remotes(repo_path: &str) -> Result<Vec<String>> {
     scope_time!("get_remotes");
@@ -16,74 +57,235 @@ pub fn get_remotes(repo_path: &str) -> Result<Vec<String>> {
     Ok(remotes)
 }
 
-///
-pub fn fetch_origin(repo_path: &str, branch: &str) -> Result<usize> {
-    scope_time!("remote_fetch_master");
+/// returns the remote `branch` is tracking (`branch.<name>.remote`),
+/// falls back to `origin` or the only remote there is
+pub fn get_branch_remote(
+    repo_path: &str,
+    branch: &str,
+) -> Result<String> {
+    scope_time!("get_branch_remote");
+
+    let repo = utils::repo(repo_path)?;
+
+    if let Ok(remote) = repo
+        .config()?
+        .get_string(format!("branch.{}.remote", branch).as_str())
+    {
+        return Ok(remote);
+    }
+
+    default_remote(&repo).map_err(|_| {
+        Error::Generic(format!(
+            "no remote found for branch: {}",
+            branch
+        ))
+    })
+}
+
+/// returns `origin` or the only remote there is
+pub fn get_default_remote(repo_path: &str) -> Result<String> {
+    scope_time!("get_default_remote");
+
+    let repo = utils::repo(repo_path)?;
+
+    default_remote(&repo)
+}
+
+fn default_remote(repo: &Repository) -> Result<String> {
+    let remotes = repo.remotes()?;
+    let remotes: Vec<&str> =
+        remotes.iter().filter_map(|s| s).collect();
+
+    if remotes.contains(&DEFAULT_REMOTE_NAME) {
+        Ok(DEFAULT_REMOTE_NAME.to_string())
+    } else if remotes.len() == 1 {
+        Ok(remotes[0].to_string())
+    } else {
+        Err(Error::Generic("no default remote found".to_string()))
+    }
+}
+
+/// fetches `branch` from `remote`, returns the amount of bytes received
+pub fn fetch(
+    repo_path: &str,
+    remote: &str,
+    branch: &str,
+    basic_credential: Option<BasicAuthCredential>,
+) -> Result<usize> {
+    scope_time!("fetch");
 
     let repo = utils::repo(repo_path)?;
-    let mut remote = repo.find_remote("origin")?;
+    let mut remote = repo.find_remote(remote)?;
 
     let mut options = FetchOptions::new();
-    options.remote_callbacks(remote_callbacks());
+    options.remote_callbacks(remote_callbacks(
+        &repo,
+        None,
+        basic_credential,
+    ));
+
+    // explicit refspec so the remote tracking branch gets updated as well
+    let refspec = format!(
+        "+refs/heads/{}:refs/remotes/{}/{}",
+        branch,
+        remote.name().unwrap_or(DEFAULT_REMOTE_NAME),
+        branch
+    );
 
-    remote.fetch(&[branch], Some(&mut options), None)?;
+    remote.fetch(&[refspec.as_str()], Some(&mut options), None)?;
 
     Ok(remote.stats().received_bytes())
 }
 
-///
+/// pushes `branch` to `remote`,
+/// progress is reported via `progress_sender` if provided
 pub fn push_origin(
     repo_path: &str,
     remote: &str,
     branch: &str,
+    basic_credential: Option<BasicAuthCredential>,
+    progress_sender: Option<Sender<ProgressNotification>>,
 ) -> Result<()> {
     scope_time!("push_origin");
 
     let repo = utils::repo(repo_path)?;
+
+    push_refspecs(
+        &repo,
+        remote,
+        &[branch.to_string()],
+        basic_credential,
+        progress_sender,
+    )
+}
+
+/// pushes the tag `tag` to `remote` (like `git push <remote> <tag>`),
+/// progress is reported like in `push_origin`
+pub fn push_tag(
+    repo_path: &str,
+    remote: &str,
+    tag: &str,
+    basic_credential: Option<BasicAuthCredential>,
+    progress_sender: Option<Sender<ProgressNotification>>,
+) -> Result<()> {
+    scope_time!("push_tag");
+
+    let repo = utils::repo(repo_path)?;
+
+    let refspec = format!("refs/tags/{}", tag);
+    if repo.find_reference(refspec.as_str()).is_err() {
+        return Err(Error::Generic(format!(
+            "tag not found: {}",
+            tag
+        )));
+    }
+
+    push_refspecs(
+        &repo,
+        remote,
+        &[refspec],
+        basic_credential,
+        progress_sender,
+    )
+}
+
+fn push_refspecs(
+    repo: &Repository,
+    remote: &str,
+    refspecs: &[String],
+    basic_credential: Option<BasicAuthCredential>,
+    progress_sender: Option<Sender<ProgressNotification>>,
+) -> Result<()> {
     let mut remote = repo.find_remote(remote)?;
 
     let mut options = PushOptions::new();
-    options.remote_callbacks(remote_callbacks());
+    options.remote_callbacks(remote_callbacks(
+        repo,
+        progress_sender,
+        basic_credential,
+    ));
     options.packbuilder_parallelism(0);
 
-    remote.push(&[branch], Some(&mut options))?;
+    remote.push(refspecs, Some(&mut options))?;
 
     Ok(())
 }
 
-fn remote_callbacks<'a>() -> RemoteCallbacks<'a> {
+fn remote_callbacks<'a>(
+    repo: &Repository,
+    sender: Option<Sender<ProgressNotification>>,
+    basic_credential: Option<BasicAuthCredential>,
+) -> RemoteCallbacks<'a> {
     let mut callbacks = RemoteCallbacks::new();
-    callbacks.push_transfer_progress(|progress, total, bytes| {
-        log::debug!(
-            "progress: {}/{} ({} B)",
-            progress,
-            total,
-            bytes,
+    let sender_clone = sender.clone();
+    callbacks.push_transfer_progress(move |current, total, bytes| {
+        log::debug!("progress: {}/{} ({} B)", current, total, bytes,);
+
+        send_progress(
+            &sender_clone,
+            ProgressNotification::PushTransfer {
+                current,
+                total,
+                bytes,
+            },
         );
     });
-    callbacks.pack_progress(|stage, current, total| {
+    callbacks.pack_progress(move |stage, current, total| {
         log::debug!("packing: {:?} - {}/{}", stage, current, total);
-    });
-    callbacks.credentials(|url, username_from_url, allowed_types| {
-        log::debug!(
-            "creds: '{}' {:?} ({:?})",
-            url,
-            username_from_url,
-            allowed_types
-        );
 
-        Cred::ssh_key_from_agent(
-            username_from_url.expect("username not found"),
-        )
+        send_progress(
+            &sender,
+            match stage {
+                PackBuilderStage::AddingObjects => {
+                    ProgressNotification::PackingAddingObject {
+                        current,
+                        total,
+                    }
+                }
+                PackBuilderStage::Deltafication => {
+                    ProgressNotification::PackingDeltafication {
+                        current,
+                        total,
+                    }
+                }
+            },
+        );
     });
+    let mut provider =
+        CredentialProvider::new(repo.config().ok(), basic_credential);
+    callbacks.credentials(
+        move |url, username_from_url, allowed_types| {
+            log::debug!(
+                "creds: '{}' {:?} ({:?})",
+                url,
+                username_from_url,
+                allowed_types
+            );
+
+            provider.next(url, username_from_url, allowed_types)
+        },
+    );
 
     callbacks
 }
 
+fn send_progress(
+    sender: &Option<Sender<ProgressNotification>>,
+    progress: ProgressNotification,
+) {
+    if let Some(sender) = sender {
+        // the receiving end might be gone already, progress is best effort
+        if let Err(e) = sender.send(progress) {
+            log::error!("progress send error: {}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sync::tests::debug_cmd_print;
+    use crate::sync::tests::{debug_cmd_print, repo_init};
+    use git2::Repository;
     use tempfile::TempDir;
 
     #[test]
@@ -102,6 +304,122 @@ mod tests {
 
         assert_eq!(remotes, vec![String::from("origin")]);
 
-        fetch_origin(repo_path, "master").unwrap();
+        fetch(repo_path, "origin", "master", None).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_local() {
+        let (upstream_dir, upstream) = repo_init().unwrap();
+        let td = TempDir::new().unwrap();
+
+        let url = format!(
+            "file://{}",
+            upstream_dir.path().as_os_str().to_str().unwrap()
+        );
+        let clone =
+            Repository::clone(url.as_str(), td.path()).unwrap();
+        let repo_path = td.path().as_os_str().to_str().unwrap();
+
+        assert_eq!(
+            get_branch_remote(repo_path, "master").unwrap().as_str(),
+            "origin"
+        );
+
+        let head = upstream.head().unwrap().peel_to_commit().unwrap();
+        let sig = upstream.signature().unwrap();
+        let new_id = upstream
+            .commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "upstream",
+                &head.tree().unwrap(),
+                &[&head],
+            )
+            .unwrap();
+
+        fetch(repo_path, "origin", "master", None).unwrap();
+
+        assert_eq!(
+            clone
+                .find_reference("refs/remotes/origin/master")
+                .unwrap()
+                .target()
+                .unwrap(),
+            new_id
+        );
+    }
+
+    #[test]
+    fn test_push_progress() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let upstream_dir = TempDir::new().unwrap();
+        Repository::init_bare(upstream_dir.path()).unwrap();
+        let url = format!(
+            "file://{}",
+            upstream_dir.path().as_os_str().to_str().unwrap()
+        );
+        repo.remote("origin", url.as_str()).unwrap();
+
+        let (sender, receiver) = crossbeam_channel::unbounded();
+
+        push_origin(
+            repo_path,
+            "origin",
+            "refs/heads/master",
+            None,
+            Some(sender),
+        )
+        .unwrap();
+
+        let progress: Vec<_> = receiver.try_iter().collect();
+
+        assert!(progress.iter().any(|p| matches!(
+            p,
+            ProgressNotification::PushTransfer { .. }
+        )));
+    }
+
+    #[test]
+    fn test_push_tag_local() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let upstream_dir = TempDir::new().unwrap();
+        let upstream =
+            Repository::init_bare(upstream_dir.path()).unwrap();
+
+        let url = format!(
+            "file://{}",
+            upstream_dir.path().as_os_str().to_str().unwrap()
+        );
+        repo.remote("origin", url.as_str()).unwrap();
+
+        assert_eq!(get_default_remote(repo_path).unwrap(), "origin");
+        assert!(
+            push_tag(repo_path, "origin", "v1", None, None).is_err()
+        );
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1", head.as_object(), false).unwrap();
+        repo.tag_lightweight("v2", head.as_object(), false).unwrap();
+
+        push_tag(repo_path, "origin", "v1", None, None).unwrap();
+
+        // only the selected tag
+        assert!(upstream.find_reference("refs/tags/v2").is_err());
+
+        assert_eq!(
+            upstream
+                .find_reference("refs/tags/v1")
+                .unwrap()
+                .target()
+                .unwrap(),
+            head.id()
+        );
     }
 }
@@ -1,8 +1,33 @@
-use super::utils::{get_head_repo, repo};
+use super::{
+    utils::{get_head_repo, repo},
+    CommitId,
+};
 use crate::error::Result;
 use git2::{build::CheckoutBuilder, ObjectType};
 use scopetime::scope_time;
 
+/// how far `reset_branch` resets (see `git reset --soft/--mixed/--hard`)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ResetType {
+    /// only moves the branch, index and working dir are kept
+    Soft,
+    /// moves the branch and resets the index, working dir is kept
+    Mixed,
+    /// moves the branch and resets index and working dir,
+    /// uncommitted changes are lost
+    Hard,
+}
+
+impl From<ResetType> for git2::ResetType {
+    fn from(t: ResetType) -> Self {
+        match t {
+            ResetType::Soft => Self::Soft,
+            ResetType::Mixed => Self::Mixed,
+            ResetType::Hard => Self::Hard,
+        }
+    }
+}
+
 ///
 pub fn reset_stage(repo_path: &str, path: &str) -> Result<()> {
     scope_time!("reset_stage");
@@ -38,9 +63,29 @@ pub fn reset_workdir(repo_path: &str, path: &str) -> Result<()> {
     Ok(())
 }
 
+/// moves the current branch (or detached HEAD) to commit `id`
+pub fn reset_branch(
+    repo_path: &str,
+    id: CommitId,
+    reset_type: ResetType,
+) -> Result<()> {
+    scope_time!("reset_branch");
+
+    let repo = repo(repo_path)?;
+
+    let obj =
+        repo.find_object(id.into(), Some(ObjectType::Commit))?;
+
+    repo.reset(&obj, reset_type.into(), None)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{reset_stage, reset_workdir};
+    use super::{
+        reset_branch, reset_stage, reset_workdir, ResetType,
+    };
     use crate::error::Result;
     use crate::sync::{
         commit,
@@ -48,7 +93,7 @@ mod tests {
         tests::{
             debug_cmd_print, get_statuses, repo_init, repo_init_empty,
         },
-        utils::{stage_add_all, stage_add_file},
+        utils::{get_head_repo, stage_add_all, stage_add_file},
     };
     use std::{
         fs::{self, File},
@@ -311,4 +356,79 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (0, 0));
     }
+
+    fn setup_reset_branch(
+        root: &Path,
+        repo_path: &str,
+    ) -> crate::sync::CommitId {
+        File::create(&root.join("foo.txt"))
+            .unwrap()
+            .write_all(b"a")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+        let first = commit(repo_path, "first").unwrap();
+
+        File::create(&root.join("foo.txt"))
+            .unwrap()
+            .write_all(b"b")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+        commit(repo_path, "second").unwrap();
+
+        first
+    }
+
+    #[test]
+    fn test_reset_branch_soft() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = setup_reset_branch(root, repo_path);
+
+        reset_branch(repo_path, first, ResetType::Soft).unwrap();
+
+        assert_eq!(get_head_repo(&repo).unwrap(), first);
+        assert_eq!(get_statuses(repo_path), (0, 1));
+        assert_eq!(
+            fs::read_to_string(root.join("foo.txt")).unwrap(),
+            "b"
+        );
+    }
+
+    #[test]
+    fn test_reset_branch_mixed() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = setup_reset_branch(root, repo_path);
+
+        reset_branch(repo_path, first, ResetType::Mixed).unwrap();
+
+        assert_eq!(get_head_repo(&repo).unwrap(), first);
+        assert_eq!(get_statuses(repo_path), (1, 0));
+        assert_eq!(
+            fs::read_to_string(root.join("foo.txt")).unwrap(),
+            "b"
+        );
+    }
+
+    #[test]
+    fn test_reset_branch_hard() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let first = setup_reset_branch(root, repo_path);
+
+        reset_branch(repo_path, first, ResetType::Hard).unwrap();
+
+        assert_eq!(get_head_repo(&repo).unwrap(), first);
+        assert_eq!(get_statuses(repo_path), (0, 0));
+        assert_eq!(
+            fs::read_to_string(root.join("foo.txt")).unwrap(),
+            "a"
+        );
+    }
 }
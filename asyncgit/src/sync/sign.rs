@@ -0,0 +1,102 @@
+//! signing objects with gpg (like `git tag -s` does)
+
+use crate::error::{Error, Result};
+use git2::{Repository, Signature};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// signs `buffer` using `gpg.program` (`gpg` by default) with the key
+/// configured in `user.signingKey` (falls back to the identity of
+/// `signer`) and returns the armored detached signature
+pub(crate) fn gpg_sign(
+    repo: &Repository,
+    signer: &Signature,
+    buffer: &str,
+) -> Result<String> {
+    let config = repo.config()?;
+
+    let program = config
+        .get_string("gpg.program")
+        .unwrap_or_else(|_| String::from("gpg"));
+    let key =
+        config.get_string("user.signingKey").unwrap_or_else(|_| {
+            format!(
+                "{} <{}>",
+                String::from_utf8_lossy(signer.name_bytes()),
+                String::from_utf8_lossy(signer.email_bytes())
+            )
+        });
+
+    let mut child = Command::new(&program)
+        .args(&["--status-fd=2", "-bsau", key.as_str()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            Error::Generic(format!(
+                "could not run {}: {}",
+                program, e
+            ))
+        })?;
+
+    // stdin has to be closed before waiting for gpg to finish
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| Error::Generic("gpg stdin".to_string()))?
+        .write_all(buffer.as_bytes())?;
+
+    let output = child.wait_with_output()?;
+    let status = String::from_utf8_lossy(&output.stderr);
+
+    if !output.status.success()
+        || !status.contains("[GNUPG:] SIG_CREATED ")
+    {
+        return Err(Error::Generic(format!(
+            "gpg failed to sign the data:\n{}",
+            status
+        )));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// `signature` formatted like in the headers of git objects
+pub(crate) fn signature_header(signature: &Signature) -> String {
+    let when = signature.when();
+    let offset = when.offset_minutes().abs();
+
+    format!(
+        "{} <{}> {} {}{:02}{:02}",
+        String::from_utf8_lossy(signature.name_bytes()),
+        String::from_utf8_lossy(signature.email_bytes()),
+        when.seconds(),
+        when.sign(),
+        offset / 60,
+        offset % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Time;
+
+    #[test]
+    fn test_signature_header() {
+        let sig = Signature::new(
+            "name",
+            "mail@example.com",
+            &Time::new(1_600_000_000, -150),
+        )
+        .unwrap();
+
+        assert_eq!(
+            signature_header(&sig),
+            "name <mail@example.com> 1600000000 -0230"
+        );
+    }
+}
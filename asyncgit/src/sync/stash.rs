@@ -1,18 +1,49 @@
-use super::{utils::repo, CommitId};
+use super::{
+    branch::create_branch_at, merge::conflicted_paths, utils::repo,
+    CommitId,
+};
 use crate::error::{Error, Result};
-use git2::{Oid, Repository, StashFlags};
+use git2::{
+    build::CheckoutBuilder, CheckoutNotificationType, Commit,
+    ErrorCode, Index, IndexEntry, IndexTime, Oid, Repository,
+    StashApplyOptions, StashFlags, Tree,
+};
 use scopetime::scope_time;
+use std::{fs, path::Path};
+
+/// a single entry of the stash list
+#[derive(Debug, Clone, PartialEq)]
+pub struct StashInfo {
+    /// position in the stash list (`stash@{index}`)
+    pub index: usize,
+    ///
+    pub id: CommitId,
+    /// message as shown by `git stash list`
+    pub message: String,
+}
 
 ///
 pub fn get_stashes(repo_path: &str) -> Result<Vec<CommitId>> {
-    scope_time!("get_stashes");
+    Ok(get_stashes_info(repo_path)?
+        .into_iter()
+        .map(|stash| stash.id)
+        .collect())
+}
+
+/// stashes (newest first) including their messages
+pub fn get_stashes_info(repo_path: &str) -> Result<Vec<StashInfo>> {
+    scope_time!("get_stashes_info");
 
     let mut repo = repo(repo_path)?;
 
     let mut list = Vec::new();
 
-    repo.stash_foreach(|_index, _msg, id| {
-        list.push((*id).into());
+    repo.stash_foreach(|index, msg, id| {
+        list.push(StashInfo {
+            index,
+            id: (*id).into(),
+            message: msg.to_string(),
+        });
         true
     })?;
 
@@ -41,7 +72,10 @@ pub fn stash_drop(repo_path: &str, stash_id: CommitId) -> Result<()> {
     Ok(())
 }
 
+/// applies the stash onto the working dir.
 ///
+/// conflicts are left in the index to be resolved and reported as
+/// `Error::Conflicts`
 pub fn stash_apply(
     repo_path: &str,
     stash_id: CommitId,
@@ -52,7 +86,84 @@ pub fn stash_apply(
 
     let index = get_stash_index(&mut repo, stash_id.get_oid())?;
 
-    repo.stash_apply(index, None)?;
+    apply_stash(&mut repo, index)
+}
+
+/// applies the stash and drops it unless there were conflicts (like
+/// `git stash pop`)
+pub fn stash_pop(repo_path: &str, stash_id: CommitId) -> Result<()> {
+    scope_time!("stash_pop");
+
+    let mut repo = repo(repo_path)?;
+
+    let index = get_stash_index(&mut repo, stash_id.get_oid())?;
+
+    apply_stash(&mut repo, index)?;
+
+    repo.stash_drop(index)?;
+
+    Ok(())
+}
+
+/// creates and checks out branch `name` at the commit the stash was
+/// based on and pops the stash there (like `git stash branch`)
+pub fn stash_to_branch(
+    repo_path: &str,
+    stash_id: CommitId,
+    name: &str,
+) -> Result<()> {
+    scope_time!("stash_to_branch");
+
+    let base = {
+        let repo = repo(repo_path)?;
+        let stash = repo.find_commit(stash_id.into())?;
+        CommitId::new(stash.parent_id(0)?)
+    };
+
+    create_branch_at(repo_path, name, base)?;
+
+    stash_pop(repo_path, stash_id)
+}
+
+fn apply_stash(repo: &mut Repository, index: usize) -> Result<()> {
+    let mut blocking_paths = Vec::new();
+
+    let res = {
+        let mut checkout = CheckoutBuilder::new();
+        checkout
+            .notify_on(CheckoutNotificationType::CONFLICT)
+            .notify(|_, path, _, _, _| {
+                if let Some(path) = path {
+                    blocking_paths
+                        .push(path.to_string_lossy().to_string());
+                }
+                true
+            });
+
+        let mut options = StashApplyOptions::new();
+        options.checkout_options(checkout);
+
+        repo.stash_apply(index, Some(&mut options))
+    };
+
+    match res {
+        Err(e)
+            if e.code() == ErrorCode::Conflict
+                && !blocking_paths.is_empty() =>
+        {
+            return Err(Error::Generic(format!(
+                "local changes would be overwritten:\n{}",
+                blocking_paths.join("\n")
+            )));
+        }
+        res => res?,
+    }
+
+    let repo_index = repo.index()?;
+
+    if repo_index.has_conflicts() {
+        return Err(Error::Conflicts(conflicted_paths(&repo_index)?));
+    }
 
     Ok(())
 }
@@ -104,12 +215,248 @@ pub fn stash_save(
     Ok(CommitId::new(id))
 }
 
+/// like `stash_save` but only stashes the changes of the files in
+/// `paths` (like `git stash push -- <paths>`), the rest of the index
+/// and workdir stays untouched
+pub fn stash_save_paths(
+    repo_path: &str,
+    message: Option<&str>,
+    paths: &[String],
+    include_untracked: bool,
+    keep_index: bool,
+) -> Result<CommitId> {
+    scope_time!("stash_save_paths");
+
+    let repo = repo(repo_path)?;
+    let work_dir = repo.workdir().ok_or_else(|| {
+        Error::Generic("cannot stash in bare repo".to_string())
+    })?;
+
+    let mut tracked = Vec::new();
+    let mut untracked = Vec::new();
+
+    for path in paths {
+        let status = repo.status_file(Path::new(path))?;
+
+        if status.is_wt_new() && !status.is_index_new() {
+            if include_untracked {
+                untracked.push(path.as_str());
+            }
+        } else if !status.is_empty() && !status.is_ignored() {
+            tracked.push(path.as_str());
+        }
+    }
+
+    if tracked.is_empty() && untracked.is_empty() {
+        return Err(Error::Generic(
+            "no local changes to save".to_string(),
+        ));
+    }
+
+    let sig = repo.signature()?;
+    let head = repo.head()?;
+    let head_commit = head.peel_to_commit()?;
+    let head_tree = head_commit.tree()?;
+
+    let description = format!(
+        "{}: {} {}",
+        head.shorthand()
+            .filter(|_| head.is_branch())
+            .unwrap_or("(no branch)"),
+        head_commit
+            .as_object()
+            .short_id()?
+            .as_str()
+            .unwrap_or_default(),
+        head_commit.summary().unwrap_or_default()
+    );
+
+    let repo_index = repo.index()?;
+
+    // index commit: HEAD plus the staged changes of the paths
+    let mut stash_index = Index::new()?;
+    stash_index.read_tree(&head_tree)?;
+    for path in &tracked {
+        match repo_index.get_path(Path::new(path), 0) {
+            Some(entry) => stash_index.add(&entry)?,
+            None => stash_index.remove_path(Path::new(path))?,
+        }
+    }
+    let index_tree =
+        repo.find_tree(stash_index.write_tree_to(&repo)?)?;
+    let index_commit = stash_commit(
+        &repo,
+        &sig,
+        &format!("index on {}\n", description),
+        &index_tree,
+        &[&head_commit],
+    )?;
+
+    // workdir commit: index commit plus the unstaged changes
+    for path in &tracked {
+        if work_dir.join(path).exists() {
+            let index_mode = stash_index
+                .get_path(Path::new(path), 0)
+                .map(|e| e.mode);
+            stash_index.add(&workdir_entry(
+                &repo, work_dir, path, index_mode,
+            )?)?;
+        } else {
+            stash_index.remove_path(Path::new(path))?;
+        }
+    }
+    let work_tree =
+        repo.find_tree(stash_index.write_tree_to(&repo)?)?;
+
+    let mut parents = vec![head_commit.clone(), index_commit];
+
+    // untracked files go into a parentless third commit
+    if !untracked.is_empty() {
+        let mut untracked_index = Index::new()?;
+        for path in &untracked {
+            untracked_index
+                .add(&workdir_entry(&repo, work_dir, path, None)?)?;
+        }
+        let untracked_tree =
+            repo.find_tree(untracked_index.write_tree_to(&repo)?)?;
+
+        parents.push(stash_commit(
+            &repo,
+            &sig,
+            &format!("untracked files on {}\n", description),
+            &untracked_tree,
+            &[],
+        )?);
+    }
+
+    let message = message.map_or_else(
+        || format!("WIP on {}", description),
+        |msg| {
+            format!(
+                "On {}: {}",
+                head.shorthand().unwrap_or("(no branch)"),
+                msg
+            )
+        },
+    );
+
+    let stash = stash_commit(
+        &repo,
+        &sig,
+        &format!("{}\n", message),
+        &work_tree,
+        &parents.iter().collect::<Vec<_>>(),
+    )?;
+
+    // the stash list is read from the reflog of the stash ref
+    repo.reference_ensure_log("refs/stash")?;
+    repo.reference("refs/stash", stash.id(), true, &message)?;
+
+    // remove the stashed changes from index and workdir
+    if !keep_index {
+        repo.reset_default(Some(head_commit.as_object()), &tracked)?;
+    }
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force().update_index(false);
+    for path in &tracked {
+        checkout.path(path);
+    }
+    repo.checkout_index(None, Some(&mut checkout))?;
+
+    let repo_index = repo.index()?;
+    for path in tracked.iter().chain(untracked.iter()) {
+        let file = work_dir.join(path);
+        if repo_index.get_path(Path::new(path), 0).is_none()
+            && file.exists()
+        {
+            fs::remove_file(file)?;
+        }
+    }
+
+    Ok(stash.id().into())
+}
+
+fn stash_commit<'a>(
+    repo: &'a Repository,
+    sig: &git2::Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+) -> Result<Commit<'a>> {
+    let id = repo.commit(None, sig, sig, message, tree, parents)?;
+
+    Ok(repo.find_commit(id)?)
+}
+
+/// index entry with the current content and mode of the file in the
+/// workdir. `index_mode` is the mode the file is tracked with (if any)
+fn workdir_entry(
+    repo: &Repository,
+    work_dir: &Path,
+    path: &str,
+    index_mode: Option<u32>,
+) -> Result<IndexEntry> {
+    let file = work_dir.join(path);
+    let mode =
+        workdir_mode(&fs::symlink_metadata(&file)?, index_mode);
+
+    Ok(IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id: repo.blob_path(&file)?,
+        flags: 0,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    })
+}
+
+const MODE_BLOB: u32 = 0o100_644;
+const MODE_BLOB_EXECUTABLE: u32 = 0o100_755;
+const MODE_LINK: u32 = 0o120_000;
+
+#[cfg(not(windows))]
+fn workdir_mode(
+    meta: &fs::Metadata,
+    _index_mode: Option<u32>,
+) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    if meta.file_type().is_symlink() {
+        MODE_LINK
+    } else if meta.permissions().mode() & 0o111 != 0 {
+        MODE_BLOB_EXECUTABLE
+    } else {
+        MODE_BLOB
+    }
+}
+
+#[cfg(windows)]
+/// windows has no executable bit, so the mode of the index is kept
+/// (like git does with `core.fileMode=false`)
+fn workdir_mode(meta: &fs::Metadata, index_mode: Option<u32>) -> u32 {
+    if meta.file_type().is_symlink() {
+        MODE_LINK
+    } else {
+        index_mode
+            .filter(|mode| *mode != MODE_LINK)
+            .unwrap_or(MODE_BLOB)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::sync::{
         commit, get_commit_files, get_commits_info, stage_add_file,
         tests::{debug_cmd_print, get_statuses, repo_init},
+        utils::get_head_repo,
     };
     use std::{fs::File, io::Write, path::Path};
 
@@ -205,10 +552,323 @@ mod tests {
 
         let stash = get_stashes(repo_path)?[0];
 
-        let diff = get_commit_files(repo_path, stash)?;
+        let diff = get_commit_files(repo_path, stash, None)?;
 
         assert_eq!(diff.len(), 1);
 
         Ok(())
     }
+
+    #[test]
+    fn test_stash_infos() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("foo.txt"))?.write_all(b"a")?;
+        stash_save(repo_path, Some("first"), true, false)?;
+
+        File::create(&root.join("foo.txt"))?.write_all(b"b")?;
+        stash_save(repo_path, Some("second"), true, false)?;
+
+        let stashes = get_stashes_info(repo_path)?;
+
+        assert_eq!(stashes.len(), 2);
+        assert_eq!(stashes[0].index, 0);
+        assert_eq!(stashes[1].index, 1);
+        assert!(stashes[0].message.ends_with("second"));
+        assert!(stashes[1].message.ends_with("first"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_pop() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("foo.txt"))?.write_all(b"test")?;
+
+        let stash = stash_save(repo_path, None, true, false)?;
+
+        assert_eq!(get_statuses(repo_path), (0, 0));
+
+        stash_pop(repo_path, stash)?;
+
+        assert_eq!(get_statuses(repo_path), (1, 0));
+        assert!(get_stashes(repo_path)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_pop_conflict_keeps_stash() -> Result<()> {
+        let file_path = Path::new("foo.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(file_path))?.write_all(b"base")?;
+        stage_add_file(repo_path, file_path)?;
+        commit(repo_path, "base")?;
+
+        File::create(&root.join(file_path))?.write_all(b"stashed")?;
+        let stash = stash_save(repo_path, None, true, false)?;
+
+        File::create(&root.join(file_path))?.write_all(b"ours")?;
+        stage_add_file(repo_path, file_path)?;
+        commit(repo_path, "ours")?;
+
+        let res = stash_pop(repo_path, stash);
+
+        assert!(matches!(
+            res,
+            Err(Error::Conflicts(paths)) if paths == vec!["foo.txt"]
+        ));
+        assert_eq!(get_stashes(repo_path)?, vec![stash]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_pop_blocked_by_local_changes() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("foo.txt"))?.write_all(b"stashed")?;
+        let stash = stash_save(repo_path, None, true, false)?;
+
+        File::create(&root.join("foo.txt"))?.write_all(b"local")?;
+
+        let res = stash_pop(repo_path, stash);
+
+        assert!(matches!(
+            res,
+            Err(Error::Generic(msg)) if msg.contains("foo.txt")
+        ));
+        assert_eq!(get_stashes(repo_path)?, vec![stash]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_to_branch() -> Result<()> {
+        let file_path = Path::new("foo.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(file_path))?.write_all(b"base")?;
+        stage_add_file(repo_path, file_path)?;
+        let base = commit(repo_path, "base")?;
+
+        File::create(&root.join(file_path))?.write_all(b"stashed")?;
+        let stash = stash_save(repo_path, None, true, false)?;
+
+        File::create(&root.join(file_path))?.write_all(b"ours")?;
+        stage_add_file(repo_path, file_path)?;
+        commit(repo_path, "ours")?;
+
+        stash_to_branch(repo_path, stash, "stashed")?;
+
+        assert_eq!(repo.head()?.shorthand(), Some("stashed"));
+        assert_eq!(get_head_repo(&repo)?, base);
+        assert_eq!(
+            std::fs::read_to_string(root.join(file_path))?,
+            "stashed"
+        );
+        assert!(get_stashes(repo_path)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_paths() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        for file in &["a.txt", "b.txt"] {
+            File::create(&root.join(file))?.write_all(b"base")?;
+            stage_add_file(repo_path, Path::new(file))?;
+        }
+        commit(repo_path, "base")?;
+
+        File::create(&root.join("a.txt"))?.write_all(b"a")?;
+        File::create(&root.join("b.txt"))?.write_all(b"b")?;
+
+        let stash = stash_save_paths(
+            repo_path,
+            Some("only a"),
+            &[String::from("a.txt")],
+            true,
+            false,
+        )?;
+
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.txt"))?,
+            "base"
+        );
+        assert_eq!(std::fs::read_to_string(root.join("b.txt"))?, "b");
+        assert_eq!(get_statuses(repo_path), (1, 0));
+
+        let files = get_commit_files(repo_path, stash, None)?;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "a.txt");
+
+        let stashes = get_stashes_info(repo_path)?;
+        assert_eq!(stashes.len(), 1);
+        assert_eq!(stashes[0].message, "On master: only a");
+
+        stash_pop(repo_path, stash)?;
+
+        assert_eq!(std::fs::read_to_string(root.join("a.txt"))?, "a");
+        assert_eq!(get_statuses(repo_path), (2, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_paths_staged_and_untracked() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("staged.txt"))?.write_all(b"s")?;
+        stage_add_file(repo_path, Path::new("staged.txt"))?;
+        File::create(&root.join("new.txt"))?.write_all(b"n")?;
+        File::create(&root.join("other.txt"))?.write_all(b"o")?;
+
+        assert_eq!(get_statuses(repo_path), (2, 1));
+
+        let stash = stash_save_paths(
+            repo_path,
+            None,
+            &[String::from("staged.txt"), String::from("new.txt")],
+            true,
+            false,
+        )?;
+
+        assert!(!root.join("staged.txt").exists());
+        assert!(!root.join("new.txt").exists());
+        assert_eq!(get_statuses(repo_path), (1, 0));
+
+        let stash = repo.find_commit(stash.into())?;
+        assert_eq!(stash.parent_count(), 3);
+        assert!(stash
+            .message()
+            .unwrap_or_default()
+            .starts_with("WIP on master: "));
+
+        stash_apply(repo_path, stash.id().into())?;
+
+        assert_eq!(
+            std::fs::read_to_string(root.join("new.txt"))?,
+            "n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("staged.txt"))?,
+            "s"
+        );
+        assert_eq!(get_statuses(repo_path), (2, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_paths_keep_index() -> Result<()> {
+        let file_path = Path::new("foo.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(file_path))?.write_all(b"base")?;
+        stage_add_file(repo_path, file_path)?;
+        commit(repo_path, "base")?;
+
+        File::create(&root.join(file_path))?.write_all(b"staged")?;
+        stage_add_file(repo_path, file_path)?;
+        File::create(&root.join(file_path))?
+            .write_all(b"unstaged")?;
+
+        stash_save_paths(
+            repo_path,
+            None,
+            &[String::from("foo.txt")],
+            false,
+            true,
+        )?;
+
+        assert_eq!(
+            std::fs::read_to_string(root.join(file_path))?,
+            "staged"
+        );
+        assert_eq!(get_statuses(repo_path), (0, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_paths_nothing() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join("new.txt"))?.write_all(b"n")?;
+
+        // untracked files are skipped unless included
+        assert!(stash_save_paths(
+            repo_path,
+            None,
+            &[String::from("new.txt")],
+            false,
+            false,
+        )
+        .is_err());
+        assert!(get_stashes(repo_path)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_stash_paths_keeps_mode() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let executable = std::fs::Permissions::from_mode(0o755);
+
+        File::create(&root.join("a.sh"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("a.sh"))?;
+        commit(repo_path, "base")?;
+
+        // mode-only change of a tracked file
+        std::fs::set_permissions(
+            root.join("a.sh"),
+            executable.clone(),
+        )?;
+        File::create(&root.join("b.sh"))?.write_all(b"b")?;
+        std::fs::set_permissions(root.join("b.sh"), executable)?;
+
+        let stash = stash_save_paths(
+            repo_path,
+            None,
+            &[String::from("a.sh"), String::from("b.sh")],
+            true,
+            false,
+        )?;
+
+        let stash = repo.find_commit(stash.into())?;
+        let mode = |commit: &Commit, path: &str| -> Result<i32> {
+            Ok(commit.tree()?.get_path(Path::new(path))?.filemode())
+        };
+        assert_eq!(mode(&stash, "a.sh")?, 0o100_755);
+        assert_eq!(mode(&stash.parent(2)?, "b.sh")?, 0o100_755);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,58 @@
+use super::utils::repo;
+use crate::error::Result;
+use git2::RepositoryState;
+use scopetime::scope_time;
+
+/// operation the repository is in the middle of
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepoState {
+    ///
+    Clean,
+    ///
+    Merge,
+    /// by gitui or any other tool
+    Rebase,
+    /// cherry-pick, revert, bisect or applying patches
+    Other,
+}
+
+impl From<RepositoryState> for RepoState {
+    fn from(state: RepositoryState) -> Self {
+        match state {
+            RepositoryState::Clean => Self::Clean,
+            RepositoryState::Merge => Self::Merge,
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => Self::Rebase,
+            _ => Self::Other,
+        }
+    }
+}
+
+///
+pub fn repo_state(repo_path: &str) -> Result<RepoState> {
+    scope_time!("repo_state");
+
+    let repo = repo(repo_path)?;
+
+    Ok(repo.state().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::repo_init;
+    use std::fs;
+
+    #[test]
+    fn test_repo_state() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+        fs::create_dir(repo.path().join("rebase-merge")).unwrap();
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Rebase);
+    }
+}
@@ -18,11 +18,15 @@ pub enum StatusItemType {
     Renamed,
     ///
     Typechange,
+    /// unmerged entry (e.g. after a merge or stash apply)
+    Conflicted,
 }
 
 impl From<Status> for StatusItemType {
     fn from(s: Status) -> Self {
-        if s.is_index_new() || s.is_wt_new() {
+        if s.is_conflicted() {
+            Self::Conflicted
+        } else if s.is_index_new() || s.is_wt_new() {
             Self::New
         } else if s.is_index_deleted() || s.is_wt_deleted() {
             Self::Deleted
@@ -43,6 +47,7 @@ impl From<Delta> for StatusItemType {
             Delta::Deleted => StatusItemType::Deleted,
             Delta::Renamed => StatusItemType::Renamed,
             Delta::Typechange => StatusItemType::Typechange,
+            Delta::Conflicted => StatusItemType::Conflicted,
             _ => StatusItemType::Modified,
         }
     }
@@ -44,10 +44,64 @@ pub fn get_tags(repo_path: &str) -> Result<Tags> {
     Ok(res)
 }
 
+/// a tag and the commit it points to
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagInfo {
+    ///
+    pub name: String,
+    ///
+    pub commit: CommitId,
+    /// message of annotated tags (`None` for lightweight tags)
+    pub annotation: Option<String>,
+}
+
+/// all tags pointing to commits, sorted by name
+pub fn get_tags_info(repo_path: &str) -> Result<Vec<TagInfo>> {
+    scope_time!("get_tags_info");
+
+    let repo = repo(repo_path)?;
+
+    let mut tags = Vec::new();
+
+    for name in repo.tag_names(None)?.iter().flatten() {
+        let object =
+            repo.revparse_single(&format!("refs/tags/{}", name))?;
+
+        let annotation = object
+            .as_tag()
+            .map(|tag| tag.message().unwrap_or_default().to_string());
+
+        if let Ok(commit) = object.peel_to_commit() {
+            tags.push(TagInfo {
+                name: name.to_string(),
+                commit: commit.id().into(),
+                annotation,
+            });
+        }
+    }
+
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(tags)
+}
+
+/// deletes the tag `name` (only locally)
+pub fn delete_tag(repo_path: &str, name: &str) -> Result<()> {
+    scope_time!("delete_tag");
+
+    let repo = repo(repo_path)?;
+
+    repo.tag_delete(name)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sync::tests::repo_init;
+    use crate::sync::{
+        get_head, tag, tag_annotated, tests::repo_init,
+    };
     use git2::ObjectType;
 
     #[test]
@@ -82,4 +136,39 @@ mod tests {
             vec!["a", "b"]
         );
     }
+
+    #[test]
+    fn test_tags_info() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let head = get_head(repo_path).unwrap();
+
+        tag(repo_path, &head, "b").unwrap();
+        tag_annotated(repo_path, &head, "a", "annotation").unwrap();
+
+        let tags = get_tags_info(repo_path).unwrap();
+
+        assert_eq!(
+            tags,
+            vec![
+                TagInfo {
+                    name: String::from("a"),
+                    commit: head,
+                    annotation: Some(String::from("annotation\n")),
+                },
+                TagInfo {
+                    name: String::from("b"),
+                    commit: head,
+                    annotation: None,
+                },
+            ]
+        );
+
+        delete_tag(repo_path, "a").unwrap();
+
+        assert_eq!(get_tags(repo_path).unwrap()[&head], vec!["b"]);
+        assert!(delete_tag(repo_path, "a").is_err());
+    }
 }
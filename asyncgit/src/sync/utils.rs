@@ -2,7 +2,9 @@
 
 use super::CommitId;
 use crate::error::{Error, Result};
-use git2::{IndexAddOption, Repository, RepositoryOpenFlags};
+use git2::{
+    IndexAddOption, Repository, RepositoryOpenFlags, StatusOptions,
+};
 use scopetime::scope_time;
 use std::path::Path;
 
@@ -56,6 +58,16 @@ pub(crate) fn work_dir(repo: &Repository) -> &Path {
     repo.workdir().expect("unable to query workdir")
 }
 
+/// true if tracked files are changed in the working dir or index
+pub(crate) fn has_uncommitted_changes(
+    repo: &Repository,
+) -> Result<bool> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+
+    Ok(!repo.statuses(Some(&mut opts))?.is_empty())
+}
+
 ///
 pub fn repo_work_dir(repo_path: &str) -> Result<String> {
     let repo = repo(repo_path)?;
@@ -66,6 +78,16 @@ pub fn repo_work_dir(repo_path: &str) -> Result<String> {
     }
 }
 
+/// path of the git dir (usually `.git` inside the workdir)
+pub fn repo_git_dir(repo_path: &str) -> Result<String> {
+    let repo = repo(repo_path)?;
+    if let Some(git_dir) = repo.path().to_str() {
+        Ok(git_dir.to_string())
+    } else {
+        Err(Error::Generic("invalid git dir".to_string()))
+    }
+}
+
 ///
 pub fn get_head(repo_path: &str) -> Result<CommitId> {
     let repo = repo(repo_path)?;
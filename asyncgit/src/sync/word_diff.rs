@@ -0,0 +1,239 @@
+//! word level changes between deleted and added lines of a hunk
+
+use super::diff::{DiffLine, DiffLineType};
+use std::ops::Range;
+
+/// lines with more tokens than this (product of both sides) are not
+/// compared to keep huge generated lines from stalling the diff
+const MAX_TABLE_SIZE: usize = 250_000;
+
+/// marks the changed parts of deleted lines and the added lines
+/// directly following them (paired up by position)
+pub(crate) fn emphasize_changes(lines: &mut [DiffLine]) {
+    let mut index = 0;
+
+    while index < lines.len() {
+        let deleted = run_length(lines, index, DiffLineType::Delete);
+        let added =
+            run_length(lines, index + deleted, DiffLineType::Add);
+
+        if deleted > 0 && added > 0 {
+            for i in 0..deleted.min(added) {
+                let (old, new) = changed_ranges(
+                    &lines[index + i].content,
+                    &lines[index + deleted + i].content,
+                );
+                lines[index + i].emphasis = old;
+                lines[index + deleted + i].emphasis = new;
+            }
+        }
+
+        index += (deleted + added).max(1);
+    }
+}
+
+fn run_length(
+    lines: &[DiffLine],
+    start: usize,
+    line_type: DiffLineType,
+) -> usize {
+    lines
+        .iter()
+        .skip(start)
+        .take_while(|line| line.line_type == line_type)
+        .count()
+}
+
+#[derive(PartialEq)]
+enum TokenKind {
+    Word,
+    Space,
+    Other,
+}
+
+impl TokenKind {
+    fn of(c: char) -> Self {
+        if c.is_alphanumeric() || c == '_' {
+            Self::Word
+        } else if c.is_whitespace() {
+            Self::Space
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// splits into runs of word characters, runs of whitespace and
+/// single other characters (as byte ranges)
+fn tokenize(s: &str) -> Vec<Range<usize>> {
+    let mut tokens: Vec<Range<usize>> = Vec::new();
+    let mut last_kind = None;
+
+    for (idx, c) in s.char_indices() {
+        let kind = TokenKind::of(c);
+        let end = idx + c.len_utf8();
+
+        match tokens.last_mut() {
+            Some(last)
+                if kind != TokenKind::Other
+                    && last_kind.as_ref() == Some(&kind) =>
+            {
+                last.end = end;
+            }
+            _ => tokens.push(idx..end),
+        }
+
+        last_kind = Some(kind);
+    }
+
+    tokens
+}
+
+/// byte ranges of `old` and `new` (ignoring the line ending) that are
+/// not part of the longest common token sequence, both empty if the
+/// lines share nothing but whitespace
+fn changed_ranges(
+    old: &str,
+    new: &str,
+) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let old = old.trim_end_matches(|c| c == '\n' || c == '\r');
+    let new = new.trim_end_matches(|c| c == '\n' || c == '\r');
+
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    let (n, m) = (old_tokens.len(), new_tokens.len());
+    if n * m > MAX_TABLE_SIZE {
+        return (Vec::new(), Vec::new());
+    }
+
+    let token_eq = |i: usize, j: usize| {
+        old[old_tokens[i].clone()] == new[new_tokens[j].clone()]
+    };
+
+    // lcs[i][j] = length of the common sequence of the suffixes
+    let width = m + 1;
+    let mut lcs = vec![0_usize; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * width + j] = if token_eq(i, j) {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut old_common = vec![false; n];
+    let mut new_common = vec![false; m];
+    let mut shares_content = false;
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if token_eq(i, j) {
+            old_common[i] = true;
+            new_common[j] = true;
+            shares_content |=
+                !old[old_tokens[i].clone()].trim().is_empty();
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    if !shares_content {
+        return (Vec::new(), Vec::new());
+    }
+
+    (
+        merge_ranges(&old_tokens, &old_common),
+        merge_ranges(&new_tokens, &new_common),
+    )
+}
+
+/// joins adjacent tokens that are not common into single ranges
+fn merge_ranges(
+    tokens: &[Range<usize>],
+    common: &[bool],
+) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+
+    for (token, _) in
+        tokens.iter().zip(common).filter(|(_, common)| !**common)
+    {
+        match ranges.last_mut() {
+            Some(last) if last.end == token.start => {
+                last.end = token.end;
+            }
+            _ => ranges.push(token.clone()),
+        }
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(content: &str, line_type: DiffLineType) -> DiffLine {
+        DiffLine {
+            content: String::from(content),
+            line_type,
+            ..DiffLine::default()
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let s = "let foo_1 = a+b;";
+        let tokens: Vec<&str> =
+            tokenize(s).into_iter().map(|r| &s[r]).collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                "let", " ", "foo_1", " ", "=", " ", "a", "+", "b",
+                ";"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changed_ranges() {
+        let (old, new) =
+            changed_ranges("let a = 1;\n", "let b = 1; // x\n");
+
+        assert_eq!(old, vec![4..5]);
+        assert_eq!(new, vec![4..5, 10..15]);
+    }
+
+    #[test]
+    fn test_unrelated_lines() {
+        let (old, new) = changed_ranges("foo bar\n", "baz\n");
+
+        assert!(old.is_empty());
+        assert!(new.is_empty());
+    }
+
+    #[test]
+    fn test_emphasize_runs() {
+        let mut lines = vec![
+            line("@@ -1,3 +1,3 @@\n", DiffLineType::Header),
+            line("a b\n", DiffLineType::Delete),
+            line("c d\n", DiffLineType::Delete),
+            line("a x\n", DiffLineType::Add),
+            line("unchanged\n", DiffLineType::None),
+            line("new\n", DiffLineType::Add),
+        ];
+
+        emphasize_changes(&mut lines);
+
+        assert_eq!(lines[1].emphasis, vec![2..3]);
+        assert!(lines[2].emphasis.is_empty());
+        assert_eq!(lines[3].emphasis, vec![2..3]);
+        assert!(lines[5].emphasis.is_empty());
+    }
+}
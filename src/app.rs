@@ -2,21 +2,28 @@ use crate::{
     accessors,
     cmdbar::CommandBar,
     components::{
-        event_pump, CommandBlocking, CommandInfo, CommitComponent,
-        Component, CreateBranchComponent, DrawableComponent,
-        ExternalEditorComponent, HelpComponent,
-        InspectCommitComponent, MsgComponent, PushComponent,
-        ResetComponent, StashMsgComponent, TagCommitComponent,
+        event_pump, BlameFileComponent, BranchListComponent,
+        CommandBlocking, CommandInfo, CommitComponent,
+        CompareCommitsComponent, Component, CreateBranchComponent,
+        DrawableComponent, ExternalEditorComponent,
+        FileHistoryComponent, HelpComponent, InspectCommitComponent,
+        MsgComponent, PullComponent, PushComponent, RebaseComponent,
+        RenameBranchComponent, ResetComponent, StashMsgComponent,
+        TagCommitComponent, TagListComponent,
     },
     input::{Input, InputEvent, InputState},
     keys::{KeyConfig, SharedKeyConfig},
+    options::{Options, SharedOptions},
     queue::{Action, InternalEvent, NeedsUpdate, Queue},
     strings::{self, order},
     tabs::{Revlog, StashList, Stashing, Status},
     ui::style::{SharedTheme, Theme},
 };
 use anyhow::{anyhow, Result};
-use asyncgit::{sync, AsyncNotification, CWD};
+use asyncgit::{
+    sync::{self, CommitId},
+    AsyncNotification, CWD,
+};
 use crossbeam_channel::Sender;
 use crossterm::event::{Event, KeyEvent};
 use std::{
@@ -34,16 +41,26 @@ use tui::{
 ///
 pub struct App {
     do_quit: bool,
+    /// changes were reported while a status query was still running
+    changes_pending: bool,
     help: HelpComponent,
     msg: MsgComponent,
     reset: ResetComponent,
     commit: CommitComponent,
     stashmsg_popup: StashMsgComponent,
     inspect_commit_popup: InspectCommitComponent,
+    blame_file_popup: BlameFileComponent,
+    file_history_popup: FileHistoryComponent,
+    compare_commits_popup: CompareCommitsComponent,
     external_editor_popup: ExternalEditorComponent,
     push_popup: PushComponent,
+    pull_popup: PullComponent,
     tag_commit_popup: TagCommitComponent,
     create_branch_popup: CreateBranchComponent,
+    rename_branch_popup: RenameBranchComponent,
+    select_branch_popup: BranchListComponent,
+    tag_list_popup: TagListComponent,
+    rebase_popup: RebaseComponent,
     cmdbar: RefCell<CommandBar>,
     tab: usize,
     revlog: Revlog,
@@ -53,11 +70,13 @@ pub struct App {
     queue: Queue,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
     input: Input,
 
     // "Flags"
     requires_redraw: Cell<bool>,
-    file_to_open: Option<String>,
+    file_to_open: Option<(String, Option<u32>)>,
+    tag_to_sign: Option<(CommitId, String, String)>,
 }
 
 // public interface
@@ -71,6 +90,7 @@ impl App {
 
         let theme = Rc::new(Theme::init());
         let key_config = Rc::new(KeyConfig::init());
+        let options = Rc::new(RefCell::new(Options::init()));
 
         Self {
             input,
@@ -94,6 +114,27 @@ impl App {
                 sender,
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
+            ),
+            blame_file_popup: BlameFileComponent::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+            ),
+            file_history_popup: FileHistoryComponent::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+                options.clone(),
+            ),
+            compare_commits_popup: CompareCommitsComponent::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+                options.clone(),
             ),
             external_editor_popup: ExternalEditorComponent::new(
                 theme.clone(),
@@ -105,6 +146,12 @@ impl App {
                 theme.clone(),
                 key_config.clone(),
             ),
+            pull_popup: PullComponent::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+            ),
             tag_commit_popup: TagCommitComponent::new(
                 queue.clone(),
                 theme.clone(),
@@ -115,7 +162,28 @@ impl App {
                 theme.clone(),
                 key_config.clone(),
             ),
+            rename_branch_popup: RenameBranchComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            select_branch_popup: BranchListComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            tag_list_popup: TagListComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            rebase_popup: RebaseComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
             do_quit: false,
+            changes_pending: false,
             cmdbar: RefCell::new(CommandBar::new(
                 theme.clone(),
                 key_config.clone(),
@@ -131,12 +199,14 @@ impl App {
                 sender,
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             status_tab: Status::new(
                 &queue,
                 sender,
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             stashing_tab: Stashing::new(
                 sender,
@@ -152,8 +222,10 @@ impl App {
             queue,
             theme,
             key_config,
+            options,
             requires_redraw: Cell::new(false),
             file_to_open: None,
+            tag_to_sign: None,
         }
     }
 
@@ -236,20 +308,14 @@ impl App {
         } else if let InputEvent::State(polling_state) = ev {
             self.external_editor_popup.hide();
             if let InputState::Paused = polling_state {
-                let result = match self.file_to_open.take() {
-                    Some(path) => {
-                        ExternalEditorComponent::open_file_in_editor(
-                            Path::new(&path),
-                        )
-                    }
-                    None => self.commit.show_editor(),
-                };
-
-                if let Err(e) = result {
-                    let msg =
-                        format!("failed to launch editor:\n{}", e);
-                    log::error!("{}", msg.as_str());
-                    self.msg.show_error(msg.as_str())?;
+                if let Some((id, name, message)) =
+                    self.tag_to_sign.take()
+                {
+                    self.tag_commit_popup
+                        .sign_tag(id, &name, &message)?;
+                    self.process_queue(NeedsUpdate::empty())?;
+                } else {
+                    self.open_external_editor()?;
                 }
 
                 self.requires_redraw.set(true);
@@ -270,11 +336,36 @@ impl App {
         self.stashing_tab.update()?;
         self.stashlist_tab.update()?;
 
+        if self.select_branch_popup.is_visible() {
+            self.select_branch_popup.update_branches()?;
+        }
+
+        if self.tag_list_popup.is_visible() {
+            self.tag_list_popup.update_tags()?;
+        }
+
+        self.rebase_popup.update()?;
+
         self.update_commands();
 
         Ok(())
     }
 
+    /// refreshes after the watcher saw changes in the repo
+    pub fn update_changes(&mut self) -> Result<()> {
+        // a running status query blocks new ones, so we have to
+        // refresh again once it finished
+        // refs might have changed as well
+        self.revlog.invalidate_tags();
+
+        if self.status_query_pending() {
+            self.changes_pending = true;
+            Ok(())
+        } else {
+            self.update()
+        }
+    }
+
     ///
     pub fn update_git(
         &mut self,
@@ -282,11 +373,20 @@ impl App {
     ) -> Result<()> {
         log::trace!("update_git: {:?}", ev);
 
+        if self.changes_pending && !self.status_query_pending() {
+            self.changes_pending = false;
+            self.update()?;
+        }
+
         self.status_tab.update_git(ev)?;
         self.stashing_tab.update_git(ev)?;
         self.revlog.update_git(ev)?;
         self.inspect_commit_popup.update_git(ev)?;
+        self.blame_file_popup.update_git(ev)?;
+        self.file_history_popup.update_git(ev)?;
+        self.compare_commits_popup.update_git(ev)?;
         self.push_popup.update_git(ev)?;
+        self.pull_popup.update_git(ev)?;
 
         //TODO: better system for this
         // can we simply process the queue here and everyone just uses the queue to schedule a cmd update?
@@ -295,6 +395,11 @@ impl App {
         Ok(())
     }
 
+    fn status_query_pending(&self) -> bool {
+        self.status_tab.anything_pending()
+            || self.stashing_tab.anything_pending()
+    }
+
     ///
     pub const fn is_quit(&self) -> bool {
         self.do_quit
@@ -306,6 +411,9 @@ impl App {
             || self.revlog.any_work_pending()
             || self.stashing_tab.anything_pending()
             || self.inspect_commit_popup.any_work_pending()
+            || self.blame_file_popup.any_work_pending()
+            || self.file_history_popup.any_work_pending()
+            || self.compare_commits_popup.any_work_pending()
             || self.input.is_state_changing()
     }
 
@@ -330,10 +438,18 @@ impl App {
             commit,
             stashmsg_popup,
             inspect_commit_popup,
+            blame_file_popup,
+            file_history_popup,
+            compare_commits_popup,
             external_editor_popup,
             push_popup,
+            pull_popup,
             tag_commit_popup,
             create_branch_popup,
+            rename_branch_popup,
+            select_branch_popup,
+            tag_list_popup,
+            rebase_popup,
             help,
             revlog,
             status_tab,
@@ -406,11 +522,35 @@ impl App {
         self.cmdbar.borrow_mut().set_cmds(self.commands(false));
     }
 
+    fn open_external_editor(&mut self) -> Result<()> {
+        let result = match self.file_to_open.take() {
+            Some((path, line)) => {
+                ExternalEditorComponent::open_file_in_editor(
+                    Path::new(&path),
+                    line,
+                )
+            }
+            None => self.commit.show_editor(),
+        };
+
+        if let Err(e) = result {
+            let msg = format!("failed to launch editor:\n{}", e);
+            log::error!("{}", msg.as_str());
+            self.msg.show_error(msg.as_str())?;
+        }
+
+        Ok(())
+    }
+
     fn process_queue(&mut self, flags: NeedsUpdate) -> Result<()> {
         let mut flags = flags;
         let new_flags = self.process_internal_events()?;
         flags.insert(new_flags);
 
+        if flags.contains(NeedsUpdate::TAGS) {
+            self.revlog.invalidate_tags();
+        }
+
         if flags.contains(NeedsUpdate::ALL) {
             self.update()?;
         }
@@ -419,6 +559,10 @@ impl App {
         if flags.contains(NeedsUpdate::DIFF) {
             self.status_tab.update_diff()?;
             self.inspect_commit_popup.update_diff()?;
+            if self.file_history_popup.is_visible() {
+                self.file_history_popup.update_diff()?;
+            }
+            self.compare_commits_popup.update_diff()?;
         }
         if flags.contains(NeedsUpdate::COMMANDS) {
             self.update_commands();
@@ -464,6 +608,95 @@ impl App {
                     sync::reset_hunk(CWD, path, hash)?;
                     flags.insert(NeedsUpdate::ALL);
                 }
+                Action::ResetLines(path, hash, lines) => {
+                    if let Err(e) = sync::reset_lines(
+                        CWD,
+                        path.as_str(),
+                        hash,
+                        &lines,
+                    ) {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ShowErrorMsg(format!(
+                                "reset lines error:\n{}",
+                                e
+                            )),
+                        );
+                    }
+                    flags.insert(NeedsUpdate::ALL);
+                }
+                Action::ResetBranch(id, reset_type) => {
+                    if let Err(e) =
+                        sync::reset_branch(CWD, id, reset_type)
+                    {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ShowErrorMsg(format!(
+                                "reset branch error:\n{}",
+                                e
+                            )),
+                        );
+                    }
+                    flags.insert(NeedsUpdate::ALL);
+                }
+                Action::DeleteTag(tag) => {
+                    if let Err(e) =
+                        sync::delete_tag(CWD, tag.as_str())
+                    {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ShowErrorMsg(format!(
+                                "delete tag error:\n{}",
+                                e
+                            )),
+                        );
+                    }
+                    flags
+                        .insert(NeedsUpdate::ALL | NeedsUpdate::TAGS);
+                }
+                Action::DeleteBranch(branch_ref) => {
+                    if let Err(e) =
+                        sync::delete_branch(CWD, branch_ref.as_str())
+                    {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ShowErrorMsg(format!(
+                                "delete branch error:\n{}",
+                                e
+                            )),
+                        );
+                    }
+                    flags.insert(NeedsUpdate::ALL);
+                }
+                Action::CherryPick(id) => {
+                    if let Err(e) = sync::cherry_pick(CWD, id) {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ShowErrorMsg(format!(
+                                "cherry-pick error:\n{}",
+                                e
+                            )),
+                        );
+                    }
+                    flags.insert(NeedsUpdate::ALL);
+                }
+                Action::RevertCommit(id) => {
+                    if let Err(e) = sync::revert_commit(CWD, id) {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ShowErrorMsg(format!(
+                                "revert error:\n{}",
+                                e
+                            )),
+                        );
+                    }
+                    flags.insert(NeedsUpdate::ALL);
+                }
+                Action::CheckoutCommit(id) => {
+                    if let Err(e) = sync::checkout_commit(CWD, id) {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ShowErrorMsg(format!(
+                                "checkout error:\n{}",
+                                e
+                            )),
+                        );
+                    }
+                    flags.insert(NeedsUpdate::ALL);
+                }
             },
             InternalEvent::ConfirmAction(action) => {
                 self.reset.open(action)?;
@@ -476,31 +709,91 @@ impl App {
             }
             InternalEvent::Update(u) => flags.insert(u),
             InternalEvent::OpenCommit => self.commit.show()?,
-            InternalEvent::PopupStashing(opts) => {
-                self.stashmsg_popup.options(opts);
+            InternalEvent::PopupStashing(opts, paths) => {
+                self.stashmsg_popup.options(opts, paths);
                 self.stashmsg_popup.show()?
             }
             InternalEvent::TagCommit(id) => {
                 self.tag_commit_popup.open(id)?;
             }
-            InternalEvent::CreateBranch => {
-                self.create_branch_popup.open()?;
+            InternalEvent::ShowTags => {
+                self.tag_list_popup.open()?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::PushTag(tag) => {
+                self.push_popup.push_tag(tag)?;
+                flags.insert(NeedsUpdate::ALL)
+            }
+            InternalEvent::CreateBranch(id) => {
+                self.create_branch_popup.open(id)?;
+            }
+            InternalEvent::StashBranch(id) => {
+                self.create_branch_popup.open_for_stash(id)?;
+            }
+            InternalEvent::SelectBranch(compare_base) => {
+                self.select_branch_popup.open(compare_base)?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::RenameBranch(branch_ref, cur_name) => {
+                self.rename_branch_popup
+                    .open(branch_ref, cur_name)?;
+                flags.insert(NeedsUpdate::COMMANDS);
             }
             InternalEvent::TabSwitch => self.set_tab(0)?,
             InternalEvent::InspectCommit(id, tags) => {
                 self.inspect_commit_popup.open(id, tags)?;
                 flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
             }
-            InternalEvent::OpenExternalEditor(path) => {
+            InternalEvent::BlameFile(path, commit_id) => {
+                self.inspect_commit_popup.hide();
+                self.file_history_popup.hide();
+                self.blame_file_popup.open(path, commit_id)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::FileHistory(path) => {
+                self.inspect_commit_popup.hide();
+                self.blame_file_popup.hide();
+                self.file_history_popup.open(path)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::CompareCommits(from, to) => {
+                self.compare_commits_popup.open(from, to)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::SignTag(id, name, message) => {
+                self.input.set_polling(false);
+                self.tag_to_sign = Some((id, name, message));
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenExternalEditor(path, line) => {
                 self.input.set_polling(false);
                 self.external_editor_popup.show()?;
-                self.file_to_open = path;
+                self.file_to_open = path.map(|path| (path, line));
                 flags.insert(NeedsUpdate::COMMANDS)
             }
             InternalEvent::Push(branch) => {
                 self.push_popup.push(branch)?;
                 flags.insert(NeedsUpdate::ALL)
             }
+            InternalEvent::Fetch(branch) => {
+                self.pull_popup.fetch(branch)?;
+                flags.insert(NeedsUpdate::ALL)
+            }
+            InternalEvent::Pull(branch) => {
+                self.pull_popup.pull(branch)?;
+                flags.insert(NeedsUpdate::ALL)
+            }
+            InternalEvent::RebaseInteractive(id) => {
+                if let Err(e) = self.rebase_popup.open(id) {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "rebase error:\n{}",
+                            e
+                        )),
+                    );
+                }
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
         };
 
         Ok(flags)
@@ -557,10 +850,18 @@ impl App {
             || self.msg.is_visible()
             || self.stashmsg_popup.is_visible()
             || self.inspect_commit_popup.is_visible()
+            || self.blame_file_popup.is_visible()
+            || self.file_history_popup.is_visible()
+            || self.compare_commits_popup.is_visible()
             || self.external_editor_popup.is_visible()
             || self.tag_commit_popup.is_visible()
             || self.create_branch_popup.is_visible()
+            || self.rename_branch_popup.is_visible()
+            || self.select_branch_popup.is_visible()
+            || self.tag_list_popup.is_visible()
+            || self.rebase_popup.is_visible()
             || self.push_popup.is_visible()
+            || self.pull_popup.is_visible()
     }
 
     fn draw_popups<B: Backend>(
@@ -578,16 +879,24 @@ impl App {
             )
             .split(f.size())[0];
 
+        self.select_branch_popup.draw(f, size)?;
+        self.tag_list_popup.draw(f, size)?;
+        self.rebase_popup.draw(f, size)?;
         self.commit.draw(f, size)?;
         self.stashmsg_popup.draw(f, size)?;
         self.reset.draw(f, size)?;
         self.help.draw(f, size)?;
+        self.blame_file_popup.draw(f, size)?;
+        self.file_history_popup.draw(f, size)?;
+        self.compare_commits_popup.draw(f, size)?;
         self.inspect_commit_popup.draw(f, size)?;
         self.msg.draw(f, size)?;
         self.external_editor_popup.draw(f, size)?;
         self.tag_commit_popup.draw(f, size)?;
         self.create_branch_popup.draw(f, size)?;
+        self.rename_branch_popup.draw(f, size)?;
         self.push_popup.draw(f, size)?;
+        self.pull_popup.draw(f, size)?;
 
         Ok(())
     }
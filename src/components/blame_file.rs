@@ -0,0 +1,386 @@
+use super::{
+    utils::{string_width_align, time_to_string},
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, ScrollType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::{self, calc_scroll_top},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{BlameLine, CommitId, FileBlame},
+    AsyncBlame, AsyncNotification, BlameParams,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use std::{borrow::Cow, cell::Cell};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+use ui::style::SharedTheme;
+
+const HASH_LENGTH: usize = 7;
+const AUTHOR_WIDTH: usize = 16;
+
+/// shows which commit last changed each line of a file
+pub struct BlameFileComponent {
+    params: Option<BlameParams>,
+    file_blame: Option<FileBlame>,
+    error: Option<String>,
+    git_blame: AsyncBlame,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_height: Cell<usize>,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for BlameFileComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let height = usize::from(rect.height.saturating_sub(2));
+            self.current_height.set(height);
+
+            self.scroll_top.set(calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            f.render_widget(Clear, rect);
+            f.render_widget(
+                Paragraph::new(self.get_text(height).iter()).block(
+                    Block::default()
+                        .title(self.get_title().as_str())
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick)
+                        .title_style(self.theme.title(true))
+                        .border_style(self.theme.block(true)),
+                ),
+                rect,
+            );
+
+            ui::draw_scrollbar(
+                f,
+                rect,
+                &self.theme,
+                self.lines_count(),
+                self.selection,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for BlameFileComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                self.lines_count() > 0,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::blame_inspect_commit(
+                    &self.key_config,
+                ),
+                self.selected_line().is_some(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.exit_popup {
+                    self.hide();
+                } else if e == self.key_config.move_down {
+                    self.move_selection(ScrollType::Down);
+                } else if e == self.key_config.move_up {
+                    self.move_selection(ScrollType::Up);
+                } else if e == self.key_config.page_down {
+                    self.move_selection(ScrollType::PageDown);
+                } else if e == self.key_config.page_up {
+                    self.move_selection(ScrollType::PageUp);
+                } else if e == self.key_config.home {
+                    self.move_selection(ScrollType::Home);
+                } else if e == self.key_config.end {
+                    self.move_selection(ScrollType::End);
+                } else if e == self.key_config.enter {
+                    if let Some(line) = self.selected_line() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::InspectCommit(
+                                line.commit_id,
+                                None,
+                            ),
+                        );
+                    }
+                }
+            }
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl BlameFileComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            params: None,
+            file_blame: None,
+            error: None,
+            git_blame: AsyncBlame::new(sender),
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_height: Cell::new(0),
+            visible: false,
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    /// blames `path` at `commit_id` (HEAD if `None`)
+    pub fn open(
+        &mut self,
+        path: String,
+        commit_id: Option<CommitId>,
+    ) -> Result<()> {
+        self.params = Some(BlameParams {
+            file_path: path,
+            commit_id,
+        });
+        self.file_blame = None;
+        self.error = None;
+        self.selection = 0;
+        self.scroll_top.set(0);
+        self.show()?;
+
+        self.update()
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_blame.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() && ev == AsyncNotification::Blame {
+            if let Some(error) = self.git_blame.take_last_error()? {
+                self.error = Some(error);
+            } else {
+                self.update()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<()> {
+        if let Some(params) = &self.params {
+            match self.git_blame.request(params.clone()) {
+                Ok(Some(file_blame)) => {
+                    self.file_blame = Some(file_blame);
+                }
+                Ok(None) => (),
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn lines_count(&self) -> usize {
+        self.file_blame.as_ref().map_or(0, |b| b.lines.len())
+    }
+
+    fn selected_line(&self) -> Option<&BlameLine> {
+        self.file_blame
+            .as_ref()
+            .and_then(|b| b.lines.get(self.selection))
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) {
+        let max = self.lines_count().saturating_sub(1);
+        let page = self.current_height.get().saturating_sub(1);
+
+        let new_selection = match scroll {
+            ScrollType::Down => self.selection.saturating_add(1),
+            ScrollType::Up => self.selection.saturating_sub(1),
+            ScrollType::PageDown => {
+                self.selection.saturating_add(page)
+            }
+            ScrollType::PageUp => self.selection.saturating_sub(page),
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+        };
+
+        self.selection = new_selection.min(max);
+    }
+
+    fn get_title(&self) -> String {
+        let title = strings::blame_title(&self.key_config);
+
+        match (&self.params, &self.file_blame) {
+            (_, Some(blame)) => format!(
+                "{} {} @ {}",
+                title,
+                blame.path,
+                short_hash(blame.commit_id)
+            ),
+            (Some(params), None) => {
+                format!("{} {}", title, params.file_path)
+            }
+            (None, None) => title,
+        }
+    }
+
+    fn get_text(&self, height: usize) -> Vec<Text> {
+        let blame = match &self.file_blame {
+            Some(blame) => blame,
+            None => {
+                let msg = match &self.error {
+                    Some(error) => strings::blame_failed_text(
+                        &self.key_config,
+                        error,
+                    ),
+                    None => strings::loading_text(&self.key_config),
+                };
+
+                return vec![Text::Styled(
+                    Cow::from(msg),
+                    self.theme.text(false, false),
+                )];
+            }
+        };
+
+        let lineno_width = blame.lines.len().to_string().len();
+        let scroll_top = self.scroll_top.get();
+
+        let mut txt = Vec::with_capacity(height * 5);
+
+        for (idx, line) in blame
+            .lines
+            .iter()
+            .enumerate()
+            .skip(scroll_top)
+            .take(height)
+        {
+            let selected = idx == self.selection;
+
+            // only the first line of a block changed in the same
+            // commit carries the commit info
+            let first_of_block = idx == 0
+                || blame.lines[idx - 1].commit_id != line.commit_id;
+
+            if first_of_block {
+                txt.push(Text::Styled(
+                    Cow::from(format!(
+                        "{} ",
+                        short_hash(line.commit_id)
+                    )),
+                    self.theme.commit_hash(selected),
+                ));
+                txt.push(Text::Styled(
+                    Cow::from(format!(
+                        "{} ",
+                        string_width_align(
+                            &line.author,
+                            AUTHOR_WIDTH
+                        )
+                    )),
+                    self.theme.commit_author(selected),
+                ));
+                txt.push(Text::Styled(
+                    Cow::from(format!(
+                        "{} ",
+                        time_to_string(line.time, true)
+                    )),
+                    self.theme.commit_time(selected),
+                ));
+            } else {
+                txt.push(Text::Styled(
+                    Cow::from(format!(
+                        "{:w$}",
+                        "",
+                        w = HASH_LENGTH + AUTHOR_WIDTH + 13
+                    )),
+                    self.theme.text(true, selected),
+                ));
+            }
+
+            txt.push(Text::Styled(
+                Cow::from(format!(
+                    "{:>w$} ",
+                    idx + 1,
+                    w = lineno_width
+                )),
+                self.theme.text(false, selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!(
+                    "{}\n",
+                    line.content.replace("\t", "  ")
+                )),
+                self.theme.text(true, selected),
+            ));
+        }
+
+        txt
+    }
+}
+
+fn short_hash(id: CommitId) -> String {
+    id.to_string().chars().take(HASH_LENGTH).collect()
+}
@@ -0,0 +1,364 @@
+use super::{
+    utils::string_width_align, visibility_blocking, CommandBlocking,
+    CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{Action, InternalEvent, NeedsUpdate, Queue},
+    strings, ui,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, BranchInfo, CommitId},
+    CWD,
+};
+use crossterm::event::Event;
+use std::{borrow::Cow, cmp, convert::TryFrom};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+use ui::style::SharedTheme;
+
+const HASH_LENGTH: usize = 7;
+const NAME_LENGTH: usize = 25;
+const COMPARE_LENGTH: usize = 10;
+
+///
+pub struct BranchListComponent {
+    branches: Vec<BranchInfo>,
+    /// marked commit branches can be compared with
+    compare_base: Option<CommitId>,
+    visible: bool,
+    selection: usize,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for BranchListComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const SIZE: (u16, u16) = (70, 20);
+            let scroll_threshold = usize::from(SIZE.1 / 3);
+            let scroll = u16::try_from(
+                self.selection.saturating_sub(scroll_threshold),
+            )
+            .unwrap_or_default();
+
+            let area =
+                ui::centered_rect_absolute(SIZE.0, SIZE.1, f.size());
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text().iter())
+                    .block(
+                        Block::default()
+                            .title(
+                                &strings::select_branch_popup_title(
+                                    &self.key_config,
+                                ),
+                            )
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Thick),
+                    )
+                    .scroll(scroll)
+                    .alignment(Alignment::Left),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for BranchListComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            let selected_is_head =
+                self.selected_branch().map_or(true, |b| b.is_head);
+
+            out.push(CommandInfo::new(
+                strings::commands::navigate_branch_popup(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::select_branch_popup(
+                    &self.key_config,
+                ),
+                !selected_is_head,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::rename_branch_popup(
+                    &self.key_config,
+                ),
+                self.selected_branch().is_some(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::delete_branch_popup(
+                    &self.key_config,
+                ),
+                !selected_is_head,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::compare_branch_popup(
+                    &self.key_config,
+                ),
+                self.selected_branch().is_some(),
+                self.compare_base.is_some() || force_all,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.exit_popup {
+                    self.hide()
+                } else if e == self.key_config.move_down {
+                    self.move_selection(true)
+                } else if e == self.key_config.move_up {
+                    self.move_selection(false)
+                } else if e == self.key_config.enter {
+                    self.switch_to_selected_branch()
+                } else if e == self.key_config.rename_branch {
+                    if let Some(b) = self.selected_branch() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::RenameBranch(
+                                b.reference.clone(),
+                                b.name.clone(),
+                            ),
+                        );
+                    }
+                } else if e == self.key_config.compare_commits {
+                    self.compare_selected_branch();
+                } else if e == self.key_config.delete_branch {
+                    if let Some(b) = self.selected_branch() {
+                        if !b.is_head {
+                            self.queue.borrow_mut().push_back(
+                                InternalEvent::ConfirmAction(
+                                    Action::DeleteBranch(
+                                        b.reference.clone(),
+                                    ),
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl BranchListComponent {
+    ///
+    pub const fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            branches: Vec::new(),
+            compare_base: None,
+            visible: false,
+            selection: 0,
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn open(
+        &mut self,
+        compare_base: Option<CommitId>,
+    ) -> Result<()> {
+        self.compare_base = compare_base;
+        self.branches = sync::get_branches_info(CWD, true)?;
+        self.selection = self
+            .branches
+            .iter()
+            .position(|b| b.is_head)
+            .unwrap_or_default();
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// fetch list of branches again (e.g. after rename/delete).
+    /// ahead/behind is only computed on `open`, branches whose tip
+    /// and upstream tip did not move keep theirs
+    pub fn update_branches(&mut self) -> Result<()> {
+        let mut branches = sync::get_branches_info(CWD, false)?;
+
+        for branch in &mut branches {
+            branch.compare = self
+                .branches
+                .iter()
+                .find(|b| {
+                    b.top_commit == branch.top_commit
+                        && b.upstream == branch.upstream
+                        && b.upstream_top == branch.upstream_top
+                })
+                .and_then(|b| b.compare);
+        }
+
+        self.branches = branches;
+        self.fix_selection();
+
+        Ok(())
+    }
+
+    fn selected_branch(&self) -> Option<&BranchInfo> {
+        self.branches.get(self.selection)
+    }
+
+    fn fix_selection(&mut self) {
+        self.selection = cmp::min(
+            self.selection,
+            self.branches.len().saturating_sub(1),
+        );
+    }
+
+    fn move_selection(&mut self, inc: bool) {
+        self.selection = if inc {
+            self.selection.saturating_add(1)
+        } else {
+            self.selection.saturating_sub(1)
+        };
+
+        self.fix_selection();
+    }
+
+    fn switch_to_selected_branch(&mut self) {
+        let reference = match self.selected_branch() {
+            Some(b) if !b.is_head => b.reference.clone(),
+            _ => return,
+        };
+
+        match sync::checkout_branch(CWD, reference.as_str()) {
+            Ok(_) => {
+                self.hide();
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+            }
+            Err(e) => {
+                log::error!("checkout branch: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "checkout branch error:\n{}",
+                        e
+                    )),
+                );
+            }
+        }
+    }
+
+    fn compare_selected_branch(&mut self) {
+        if let (Some(base), Some(branch)) =
+            (self.compare_base, self.selected_branch())
+        {
+            let top_commit = branch.top_commit;
+            self.hide();
+            self.queue.borrow_mut().push_back(
+                InternalEvent::CompareCommits(base, Some(top_commit)),
+            );
+        }
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        let mut txt = Vec::with_capacity(self.branches.len() * 4);
+
+        for (idx, branch) in self.branches.iter().enumerate() {
+            let selected = self.selection == idx;
+
+            let head_marker =
+                if branch.is_head { "* " } else { "  " };
+
+            let compare = branch.compare.map_or_else(
+                String::new,
+                |c| {
+                    format!(
+                        "\u{2191}{} \u{2193}{}",
+                        c.ahead, c.behind
+                    )
+                }, //↑ ↓
+            );
+
+            let hash: String = branch
+                .top_commit
+                .to_string()
+                .chars()
+                .take(HASH_LENGTH)
+                .collect();
+
+            txt.push(Text::Styled(
+                Cow::from(format!(
+                    "{}{} ",
+                    head_marker,
+                    string_width_align(&branch.name, NAME_LENGTH)
+                )),
+                self.theme.text(true, selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!("{} ", hash)),
+                self.theme.commit_hash(selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!(
+                    "{} ",
+                    string_width_align(&compare, COMPARE_LENGTH)
+                )),
+                self.theme.text(branch.upstream.is_some(), selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!("{}\n", branch.top_commit_message)),
+                self.theme.text(true, selected),
+            ));
+        }
+
+        txt
+    }
+}
@@ -1,7 +1,11 @@
 use super::{
-    textinput::TextInputComponent, visibility_blocking,
-    CommandBlocking, CommandInfo, Component, DrawableComponent,
-    ExternalEditorComponent,
+    textinput::TextInputComponent,
+    utils::{
+        commit_history::CommitMsgHistory,
+        commit_msg::{self, BODY_WIDTH, SUBJECT_WIDTH},
+    },
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, ExternalEditorComponent,
 };
 use crate::{
     get_app_config_path,
@@ -26,8 +30,16 @@ use tui::{backend::Backend, layout::Rect, Frame};
 pub struct CommitComponent {
     input: TextInputComponent,
     amend: Option<CommitId>,
+    /// `commit.template` the message started with
+    template: Option<String>,
+    /// recalled entry of the message history and the message typed
+    /// before the history was browsed
+    history: Option<(usize, String)>,
+    /// typed type prefix and index of the completed matching type
+    completion: Option<(String, usize)>,
     queue: Queue,
     key_config: SharedKeyConfig,
+    msg_history: CommitMsgHistory,
 }
 
 impl DrawableComponent for CommitComponent {
@@ -63,6 +75,26 @@ impl Component for CommitComponent {
                 true,
             ));
 
+            out.push(CommandInfo::new(
+                strings::commands::commit_history(&self.key_config),
+                !self.msg_history.is_empty(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::commit_complete_type(
+                    &self.key_config,
+                ),
+                self.can_complete_type(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::commit_wrap_body(&self.key_config),
+                !self.is_unchanged(),
+                true,
+            ));
+
             out.push(CommandInfo::new(
                 strings::commands::commit_open_editor(
                     &self.key_config,
@@ -78,6 +110,8 @@ impl Component for CommitComponent {
     fn event(&mut self, ev: Event) -> Result<bool> {
         if self.is_visible() {
             if self.input.event(ev)? {
+                self.completion = None;
+                self.update_title();
                 return Ok(true);
             }
 
@@ -88,9 +122,17 @@ impl Component for CommitComponent {
                     && self.can_amend()
                 {
                     self.amend()?;
+                } else if e == self.key_config.commit_history_prev {
+                    self.recall_history(true);
+                } else if e == self.key_config.commit_history_next {
+                    self.recall_history(false);
+                } else if e == self.key_config.commit_complete_type {
+                    self.complete_type();
+                } else if e == self.key_config.commit_wrap_body {
+                    self.wrap_body();
                 } else if e == self.key_config.open_commit_editor {
                     self.queue.borrow_mut().push_back(
-                        InternalEvent::OpenExternalEditor(None),
+                        InternalEvent::OpenExternalEditor(None, None),
                     );
                     self.hide();
                 } else {
@@ -113,10 +155,25 @@ impl Component for CommitComponent {
 
     fn show(&mut self) -> Result<()> {
         self.amend = None;
+        self.history = None;
+        self.completion = None;
 
         self.input.clear();
-        self.input
-            .set_title(strings::commit_title(&self.key_config));
+
+        self.template = match sync::commit_template(CWD) {
+            Ok(template) => template
+                .map(|t| commit_msg::strip_comments(&t))
+                .filter(|t| !t.is_empty()),
+            Err(e) => {
+                log::error!("commit template error: {}", e);
+                None
+            }
+        };
+        if let Some(template) = &self.template {
+            self.input.set_text(template.clone());
+        }
+
+        self.update_title();
         self.input.show()?;
 
         Ok(())
@@ -130,16 +187,23 @@ impl CommitComponent {
         theme: SharedTheme,
         key_config: SharedKeyConfig,
     ) -> Self {
+        let mut input = TextInputComponent::new(
+            theme,
+            key_config.clone(),
+            "",
+            &strings::commit_msg(&key_config),
+        );
+        input.set_line_limits(SUBJECT_WIDTH, BODY_WIDTH);
+
         Self {
             queue,
             amend: None,
-            input: TextInputComponent::new(
-                theme,
-                key_config.clone(),
-                "",
-                &strings::commit_msg(&key_config),
-            ),
+            template: None,
+            history: None,
+            completion: None,
+            input,
             key_config,
+            msg_history: CommitMsgHistory::load(),
         }
     }
 
@@ -161,7 +225,10 @@ impl CommitComponent {
             )?;
         }
 
-        ExternalEditorComponent::open_file_in_editor(&config_path)?;
+        ExternalEditorComponent::open_file_in_editor(
+            &config_path,
+            None,
+        )?;
 
         let mut message = String::new();
 
@@ -170,20 +237,8 @@ impl CommitComponent {
         drop(file);
         std::fs::remove_file(&config_path)?;
 
-        let message: String = message
-            .lines()
-            .flat_map(|l| {
-                if l.starts_with('#') {
-                    vec![]
-                } else {
-                    vec![l, "\n"]
-                }
-            })
-            .collect();
-
-        let message = message.trim().to_string();
-
-        self.input.set_text(message);
+        self.input.set_text(commit_msg::strip_comments(&message));
+        self.update_title();
         self.input.show()?;
 
         Ok(())
@@ -223,6 +278,8 @@ impl CommitComponent {
             return Ok(());
         }
 
+        self.msg_history.add(&msg);
+
         if let HookResult::NotOk(e) = sync::hooks_post_commit(CWD)? {
             log::error!("post-commit hook error: {}", e);
             self.queue.borrow_mut().push_back(
@@ -242,29 +299,130 @@ impl CommitComponent {
         Ok(())
     }
 
+    /// `true` if nothing was typed (the template does not count)
+    fn is_unchanged(&self) -> bool {
+        let msg = self.input.get_text();
+
+        msg.is_empty() || self.template.as_ref() == Some(msg)
+    }
+
     fn can_commit(&self) -> bool {
-        !self.input.get_text().is_empty()
+        !self.is_unchanged()
     }
 
     fn can_amend(&self) -> bool {
         self.amend.is_none()
             && sync::get_head(CWD).is_ok()
-            && self.input.get_text().is_empty()
+            && self.is_unchanged()
     }
 
     fn amend(&mut self) -> Result<()> {
         let id = sync::get_head(CWD)?;
         self.amend = Some(id);
+        self.template = None;
 
         let details = sync::get_commit_details(CWD, id)?;
 
-        self.input
-            .set_title(strings::commit_title_amend(&self.key_config));
-
         if let Some(msg) = details.message {
             self.input.set_text(msg.combine());
         }
 
+        self.update_title();
+
         Ok(())
     }
+
+    fn update_title(&mut self) {
+        let title = if self.amend.is_some() {
+            strings::commit_title_amend(&self.key_config)
+        } else {
+            strings::commit_title(&self.key_config)
+        };
+
+        self.input.set_title(strings::commit_title_subject(
+            &self.key_config,
+            &title,
+            commit_msg::subject_len(self.input.get_text()),
+            SUBJECT_WIDTH,
+        ));
+    }
+
+    /// replaces the message with an older (or newer) one of the
+    /// history, going past the newest restores what was typed before
+    fn recall_history(&mut self, older: bool) {
+        let len = self.msg_history.len();
+        if len == 0 {
+            return;
+        }
+
+        let (idx, draft) = match self.history.take() {
+            Some((idx, draft)) if older => {
+                ((idx + 1).min(len - 1), draft)
+            }
+            Some((0, draft)) => {
+                self.input.set_text(draft);
+                self.update_title();
+                return;
+            }
+            Some((idx, draft)) => (idx - 1, draft),
+            None if older => (0, self.input.get_text().clone()),
+            None => return,
+        };
+
+        if let Some(msg) = self.msg_history.get(idx) {
+            let end = msg.len();
+            self.input.set_text(msg);
+            self.input.set_cursor_position(end);
+        }
+
+        self.history = Some((idx, draft));
+        self.completion = None;
+        self.update_title();
+    }
+
+    fn wrap_body(&mut self) {
+        let msg =
+            commit_msg::wrap_body(self.input.get_text(), BODY_WIDTH);
+
+        if msg != *self.input.get_text() {
+            self.input.set_text(msg);
+            self.update_title();
+        }
+    }
+
+    fn can_complete_type(&self) -> bool {
+        self.completion.is_some()
+            || commit_msg::type_prefix(self.input.get_text())
+                .map_or(false, |t| {
+                    !commit_msg::matching_types(t).is_empty()
+                })
+    }
+
+    /// completes the conventional commit type at the start of the
+    /// subject, repeating it cycles through all matching types
+    fn complete_type(&mut self) {
+        let typed = match self.completion.take() {
+            Some((typed, idx)) => Some((typed, idx + 1)),
+            None => commit_msg::type_prefix(self.input.get_text())
+                .map(|t| (t.to_string(), 0)),
+        };
+
+        if let Some((typed, idx)) = typed {
+            let types = commit_msg::matching_types(&typed);
+            if types.is_empty() {
+                return;
+            }
+
+            let idx = idx % types.len();
+            let (msg, cursor) = commit_msg::set_type(
+                self.input.get_text(),
+                types[idx],
+            );
+
+            self.input.set_text(msg);
+            self.input.set_cursor_position(cursor);
+            self.completion = Some((typed, idx));
+            self.update_title();
+        }
+    }
 }
@@ -5,7 +5,11 @@ use super::{
     Component, DrawableComponent, FileTreeComponent,
 };
 use crate::{
-    accessors, keys::SharedKeyConfig, queue::Queue, strings,
+    accessors,
+    keys::SharedKeyConfig,
+    options::SharedOptions,
+    queue::{InternalEvent, Queue},
+    strings,
     ui::style::SharedTheme,
 };
 use anyhow::Result;
@@ -26,8 +30,11 @@ pub struct CommitDetailsComponent {
     details: DetailsComponent,
     file_tree: FileTreeComponent,
     git_commit_files: AsyncCommitFiles,
+    commit_id: Option<CommitId>,
     visible: bool,
+    queue: Queue,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
 }
 
 impl CommitDetailsComponent {
@@ -39,6 +46,7 @@ impl CommitDetailsComponent {
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
             details: DetailsComponent::new(
@@ -54,8 +62,11 @@ impl CommitDetailsComponent {
                 theme,
                 key_config.clone(),
             ),
+            commit_id: None,
             visible: false,
+            queue: queue.clone(),
             key_config,
+            options,
         }
     }
 
@@ -76,12 +87,22 @@ impl CommitDetailsComponent {
         tags: Option<CommitTags>,
     ) -> Result<()> {
         self.details.set_commit(id, tags)?;
+        self.commit_id = id;
 
-        if let Some(id) = id {
-            if let Some((fetched_id, res)) =
+        self.update_files()
+    }
+
+    /// shows the files of the commit, they are fetched again if the
+    /// diff options changed (rename detection)
+    pub fn update_files(&mut self) -> Result<()> {
+        if let Some(id) = self.commit_id {
+            let options = self.options.borrow().diff_options();
+
+            if let Some((fetched, fetched_options, res)) =
                 self.git_commit_files.current()?
             {
-                if fetched_id == id {
+                if fetched == id.into() && fetched_options == options
+                {
                     self.file_tree.update(res.as_slice())?;
                     self.file_tree.set_title(self.get_files_title());
 
@@ -90,7 +111,7 @@ impl CommitDetailsComponent {
             }
 
             self.file_tree.clear()?;
-            self.git_commit_files.fetch(id)?;
+            self.git_commit_files.fetch(id.into(), options)?;
         }
 
         self.file_tree.set_title(self.get_files_title());
@@ -103,6 +124,22 @@ impl CommitDetailsComponent {
         self.git_commit_files.is_pending()
     }
 
+    fn blame_selected_file(&self) {
+        if let Some(file) = self.file_tree.selection_file() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::BlameFile(file.path, self.commit_id),
+            );
+        }
+    }
+
+    fn history_of_selected_file(&self) {
+        if let Some(file) = self.file_tree.selection_file() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::FileHistory(file.path));
+        }
+    }
+
     ///
     pub const fn files(&self) -> &FileTreeComponent {
         &self.file_tree
@@ -145,6 +182,17 @@ impl Component for CommitDetailsComponent {
                 force_all,
                 self.components().as_slice(),
             );
+
+            out.push(CommandInfo::new(
+                strings::commands::blame_file(&self.key_config),
+                self.file_tree.selection_file().is_some(),
+                self.file_tree.focused() || force_all,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::file_history(&self.key_config),
+                self.file_tree.selection_file().is_some(),
+                self.file_tree.focused() || force_all,
+            ));
         }
 
         CommandBlocking::PassingOn
@@ -169,6 +217,16 @@ impl Component for CommitDetailsComponent {
                     self.file_tree.focus(false);
                     self.details.focus(true);
                     Ok(true)
+                } else if e == self.key_config.blame
+                    && self.file_tree.focused()
+                {
+                    self.blame_selected_file();
+                    Ok(true)
+                } else if e == self.key_config.file_history
+                    && self.file_tree.focused()
+                {
+                    self.history_of_selected_file();
+                    Ok(true)
                 } else {
                     Ok(false)
                 };
@@ -1,19 +1,26 @@
-use super::utils::logitems::{ItemBatch, LogEntry};
+use super::utils::{
+    logitems::{ItemBatch, LogEntry},
+    string_width_align,
+};
 use crate::{
     components::{
         CommandBlocking, CommandInfo, Component, DrawableComponent,
         ScrollType,
     },
     keys::SharedKeyConfig,
+    options::GraphStyle,
     strings,
     ui::calc_scroll_top,
     ui::style::{SharedTheme, Theme},
 };
 use anyhow::Result;
-use asyncgit::sync::Tags;
+use asyncgit::sync::{
+    CommitGraph, CommitId, GraphCell, GraphRow, Tags,
+};
 use crossterm::event::Event;
 use std::{
-    borrow::Cow, cell::Cell, cmp, convert::TryFrom, time::Instant,
+    borrow::Cow, cell::Cell, cmp, collections::HashSet,
+    convert::TryFrom, time::Instant,
 };
 use tui::{
     backend::Backend,
@@ -21,9 +28,10 @@ use tui::{
     widgets::{Block, Borders, Paragraph, Text},
     Frame,
 };
-use unicode_width::UnicodeWidthStr;
 
 const ELEMENTS_PER_LINE: usize = 10;
+/// lanes further right are cut off to leave room for the message
+const MAX_GRAPH_LANES: usize = 16;
 
 ///
 pub struct CommitList {
@@ -34,6 +42,10 @@ pub struct CommitList {
     items: ItemBatch,
     scroll_state: (Instant, f32),
     tags: Option<Tags>,
+    highlights: Option<HashSet<CommitId>>,
+    marked: Option<CommitId>,
+    graph: CommitGraph,
+    graph_style: GraphStyle,
     current_size: Cell<(u16, u16)>,
     scroll_top: Cell<usize>,
     theme: SharedTheme,
@@ -54,6 +66,10 @@ impl CommitList {
             count_total: 0,
             scroll_state: (Instant::now(), 0_f32),
             tags: None,
+            highlights: None,
+            marked: None,
+            graph: CommitGraph::default(),
+            graph_style: GraphStyle::Off,
             current_size: Cell::new((0, 0)),
             scroll_top: Cell::new(0),
             theme,
@@ -110,6 +126,39 @@ impl CommitList {
         self.tags = Some(tags);
     }
 
+    /// commits to highlight (e.g. search matches), `None` to stop
+    pub fn set_highlights(
+        &mut self,
+        highlights: Option<HashSet<CommitId>>,
+    ) {
+        self.highlights = highlights;
+    }
+
+    /// commit marked to compare other commits with
+    pub const fn marked(&self) -> Option<CommitId> {
+        self.marked
+    }
+
+    ///
+    pub fn set_marked(&mut self, marked: Option<CommitId>) {
+        self.marked = marked;
+    }
+
+    /// graph lanes of the log, filled from the top by the owner
+    pub fn graph(&mut self) -> &mut CommitGraph {
+        &mut self.graph
+    }
+
+    ///
+    pub fn set_graph_style(&mut self, style: GraphStyle) {
+        self.graph_style = style;
+    }
+
+    /// moves the selection to the entry at `position` in the log
+    pub fn select_entry(&mut self, position: usize) {
+        self.selection = cmp::min(position, self.selection_max());
+    }
+
     ///
     pub fn selected_entry(&self) -> Option<&LogEntry> {
         self.items.iter().nth(
@@ -181,6 +230,7 @@ impl CommitList {
     fn add_entry<'b>(
         e: &'b LogEntry,
         selected: bool,
+        highlighted: bool,
         txt: &mut Vec<Text<'b>>,
         tags: Option<String>,
         theme: &Theme,
@@ -235,16 +285,98 @@ impl CommitList {
         // commit msg
         txt.push(Text::Styled(
             Cow::from(e.msg.as_str()),
-            theme.text(true, selected),
+            if highlighted {
+                theme.search_match(selected)
+            } else {
+                theme.text(true, selected)
+            },
         ));
         txt.push(Text::Raw(Cow::from("\n")));
     }
 
+    const fn graph_symbol(cell: GraphCell, left: bool) -> char {
+        match cell {
+            GraphCell::Empty => ' ',
+            GraphCell::Line => '│',
+            GraphCell::Commit => '●',
+            GraphCell::Fork if left => '╭',
+            GraphCell::Fork => '╮',
+            GraphCell::Join if left => '╰',
+            GraphCell::Join => '╯',
+            GraphCell::Junction if left => '├',
+            GraphCell::Junction => '┤',
+            GraphCell::Horizontal => '─',
+            GraphCell::Cross => '┼',
+        }
+    }
+
+    const fn graph_symbol_ascii(symbol: char) -> char {
+        match symbol {
+            '│' => '|',
+            '●' => '*',
+            '╭' | '╯' => '/',
+            '╮' | '╰' => '\\',
+            '├' | '┤' | '┼' => '+',
+            '─' => '-',
+            _ => symbol,
+        }
+    }
+
+    /// two columns per lane, padded to `lanes`
+    fn graph_text(
+        row: Option<&GraphRow>,
+        lanes: usize,
+        ascii: bool,
+    ) -> String {
+        let mut txt = String::with_capacity(lanes * 2);
+
+        if let Some(row) = row {
+            let cells = row.cells();
+            let commit_lane = row.commit_lane();
+            let connected_from =
+                cells.iter().position(|c| c.is_connected());
+            let connected_to =
+                cells.iter().rposition(|c| c.is_connected());
+
+            for (idx, cell) in cells.iter().enumerate().take(lanes) {
+                let horizontal = matches!(
+                    (connected_from, connected_to),
+                    (Some(from), Some(to)) if idx >= from && idx < to
+                );
+
+                txt.push(Self::graph_symbol(
+                    *cell,
+                    idx < commit_lane,
+                ));
+                txt.push(if horizontal { '─' } else { ' ' });
+            }
+        }
+
+        if ascii {
+            txt = txt.chars().map(Self::graph_symbol_ascii).collect();
+        }
+
+        format!("{:width$}", txt, width = lanes * 2)
+    }
+
     fn get_text(&self, height: usize, width: usize) -> Vec<Text> {
         let selection = self.relative_selection();
 
         let mut txt = Vec::with_capacity(height * ELEMENTS_PER_LINE);
 
+        let first_idx =
+            self.items.index_offset() + self.scroll_top.get();
+        let graph_lanes = if self.graph_style == GraphStyle::Off {
+            0
+        } else {
+            (first_idx..first_idx + height)
+                .filter_map(|idx| self.graph.get(idx))
+                .map(|row| row.cells().len())
+                .max()
+                .unwrap_or_default()
+                .min(MAX_GRAPH_LANES)
+        };
+
         for (idx, e) in self
             .items
             .iter()
@@ -258,13 +390,44 @@ impl CommitList {
                 .and_then(|t| t.get(&e.id))
                 .map(|tags| tags.join(" "));
 
+            let highlighted = self
+                .highlights
+                .as_ref()
+                .map_or(false, |h| h.contains(&e.id));
+
+            let selected = idx + self.scroll_top.get() == selection;
+
+            // only take up space for the marker when needed
+            if let Some(marked) = self.marked {
+                txt.push(Text::Styled(
+                    Cow::from(if marked == e.id {
+                        "* "
+                    } else {
+                        "  "
+                    }),
+                    self.theme.text(true, selected),
+                ));
+            }
+
+            if graph_lanes > 0 {
+                txt.push(Text::Styled(
+                    Cow::from(Self::graph_text(
+                        self.graph.get(first_idx + idx),
+                        graph_lanes,
+                        self.graph_style == GraphStyle::Ascii,
+                    )),
+                    self.theme.text(true, selected),
+                ));
+            }
+
             Self::add_entry(
                 e,
-                idx + self.scroll_top.get() == selection,
+                selected,
+                highlighted,
                 &mut txt,
                 tags,
                 &self.theme,
-                width,
+                width.saturating_sub(graph_lanes * 2),
             );
         }
 
@@ -301,12 +464,21 @@ impl DrawableComponent for CommitList {
         let branch_post_fix =
             self.branch.as_ref().map(|b| format!("- {{{}}}", b));
 
+        let highlights_post_fix = self.highlights.as_ref().map(|h| {
+            format!(
+                "[{} {}]",
+                h.len(),
+                strings::log_search_matches(&self.key_config)
+            )
+        });
+
         let title = format!(
-            "{} {}/{} {}",
+            "{} {}/{} {}{}",
             self.title,
             self.count_total.saturating_sub(self.selection),
             self.count_total,
             branch_post_fix.as_deref().unwrap_or(""),
+            highlights_post_fix.as_deref().unwrap_or(""),
         );
 
         f.render_widget(
@@ -373,53 +545,3 @@ impl Component for CommitList {
         CommandBlocking::PassingOn
     }
 }
-
-#[inline]
-fn string_width_align(s: &str, width: usize) -> String {
-    static POSTFIX: &str = "..";
-
-    let len = UnicodeWidthStr::width(s);
-    let width_wo_postfix = width.saturating_sub(POSTFIX.len());
-
-    if (len >= width_wo_postfix && len <= width)
-        || (len <= width_wo_postfix)
-    {
-        format!("{:w$}", s, w = width)
-    } else {
-        let mut s = s.to_string();
-        s.truncate(find_truncate_point(&s, width_wo_postfix));
-        format!("{}{}", s, POSTFIX)
-    }
-}
-
-#[inline]
-fn find_truncate_point(s: &str, chars: usize) -> usize {
-    s.chars().take(chars).map(char::len_utf8).sum()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_string_width_align() {
-        assert_eq!(string_width_align("123", 3), "123");
-        assert_eq!(string_width_align("123", 2), "..");
-        assert_eq!(string_width_align("123", 3), "123");
-        assert_eq!(string_width_align("12345", 6), "12345 ");
-        assert_eq!(string_width_align("1234556", 4), "12..");
-    }
-
-    #[test]
-    fn test_string_width_align_unicode() {
-        assert_eq!(string_width_align("äste", 3), "ä..");
-        assert_eq!(
-            string_width_align("wüsten äste", 10),
-            "wüsten ä.."
-        );
-        assert_eq!(
-            string_width_align("Jon Grythe Stødle", 19),
-            "Jon Grythe Stødle  "
-        );
-    }
-}
@@ -0,0 +1,307 @@
+use super::{
+    command_pump, event_pump, visibility_blocking, CommandBlocking,
+    CommandInfo, Component, DiffComponent, DrawableComponent,
+    FileTreeComponent,
+};
+use crate::{
+    accessors, keys::SharedKeyConfig, options::SharedOptions,
+    queue::Queue, strings, ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::CommitId, AsyncCommitFiles, AsyncDiff, AsyncNotification,
+    CommitFilesParams, DiffParams, DiffType,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::Clear,
+    Frame,
+};
+
+/// lists the files changed between two commits (or a commit and the
+/// working dir) and shows the diff of the selected one
+pub struct CompareCommitsComponent {
+    /// from this commit to the second one (working dir if `None`)
+    ids: Option<(CommitId, Option<CommitId>)>,
+    files: FileTreeComponent,
+    diff: DiffComponent,
+    git_files: AsyncCommitFiles,
+    git_diff: AsyncDiff,
+    visible: bool,
+    sender: Sender<AsyncNotification>,
+    key_config: SharedKeyConfig,
+    options: SharedOptions,
+}
+
+impl DrawableComponent for CompareCommitsComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.is_visible() {
+            let percentages = if self.diff.focused() {
+                (30, 70)
+            } else {
+                (50, 50)
+            };
+
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(percentages.0),
+                        Constraint::Percentage(percentages.1),
+                    ]
+                    .as_ref(),
+                )
+                .split(rect);
+
+            f.render_widget(Clear, rect);
+
+            self.files.draw(f, chunks[0])?;
+            self.diff.draw(f, chunks[1])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for CompareCommitsComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            command_pump(
+                out,
+                force_all,
+                self.components().as_slice(),
+            );
+
+            out.push(
+                CommandInfo::new(
+                    strings::commands::close_popup(&self.key_config),
+                    true,
+                    true,
+                )
+                .order(1),
+            );
+
+            out.push(CommandInfo::new(
+                strings::commands::diff_focus_right(&self.key_config),
+                self.can_focus_diff(),
+                !self.diff.focused() || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::diff_focus_left(&self.key_config),
+                true,
+                self.diff.focused() || force_all,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if event_pump(ev, self.components_mut().as_mut_slice())? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.exit_popup {
+                    self.hide();
+                } else if e == self.key_config.focus_right
+                    && self.can_focus_diff()
+                {
+                    self.files.focus(false);
+                    self.diff.focus(true);
+                } else if e == self.key_config.focus_left
+                    && self.diff.focused()
+                {
+                    self.files.focus(true);
+                    self.diff.focus(false);
+                }
+
+                // stop key event propagation
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.files.focus(true);
+        self.diff.focus(false);
+        self.update_diff()?;
+        Ok(())
+    }
+}
+
+impl CompareCommitsComponent {
+    accessors!(self, [files, diff]);
+
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        options: SharedOptions,
+    ) -> Self {
+        Self {
+            files: FileTreeComponent::new(
+                "",
+                true,
+                Some(queue.clone()),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            diff: DiffComponent::new(
+                queue.clone(),
+                theme,
+                key_config.clone(),
+                options.clone(),
+                sender,
+                true,
+            ),
+            ids: None,
+            git_files: AsyncCommitFiles::new(sender),
+            git_diff: AsyncDiff::new(sender),
+            visible: false,
+            sender: sender.clone(),
+            key_config,
+            options,
+        }
+    }
+
+    /// compares `from` with `to` (the working dir if `None`)
+    pub fn open(
+        &mut self,
+        from: CommitId,
+        to: Option<CommitId>,
+    ) -> Result<()> {
+        self.ids = Some((from, to));
+        // the working dir may have changed since the last comparison
+        self.git_files = AsyncCommitFiles::new(&self.sender);
+        self.files.clear()?;
+        self.files.set_title(strings::compare_title(
+            &self.key_config,
+            &from.get_short_string(),
+            to.map(|id| id.get_short_string()).as_deref(),
+        ));
+        self.diff.clear(false)?;
+        self.show()?;
+
+        Ok(())
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_diff.is_pending() || self.git_files.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() {
+            if let AsyncNotification::CommitFiles = ev {
+                self.update_diff()?
+            } else if let AsyncNotification::Diff = ev {
+                self.update_diff()?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// called when any tree component changed selection
+    pub fn update_diff(&mut self) -> Result<()> {
+        if self.is_visible() {
+            self.update_files()?;
+
+            if let Some(ids) = self.ids {
+                if let Some(f) = self.files.selection_file() {
+                    let diff_params = DiffParams {
+                        path: f.path.clone(),
+                        diff_type: match ids {
+                            (from, Some(to)) => {
+                                DiffType::Compare(from, to)
+                            }
+                            (from, None) => {
+                                DiffType::CompareWorkdir(from)
+                            }
+                        },
+                        options: self.options.borrow().diff_options(),
+                    };
+
+                    if let Some((params, last)) =
+                        self.git_diff.last()?
+                    {
+                        if params == diff_params {
+                            self.diff.update(f.path, false, last)?;
+                            return Ok(());
+                        }
+                    }
+
+                    self.git_diff.request(diff_params)?;
+                    self.diff.clear(true)?;
+                    return Ok(());
+                }
+            }
+
+            self.diff.clear(false)?;
+        }
+
+        Ok(())
+    }
+
+    /// fetches the changed files again if the diff options changed
+    /// (rename detection)
+    fn update_files(&mut self) -> Result<()> {
+        if let Some(ids) = self.ids {
+            let params = match ids {
+                (from, Some(to)) => {
+                    CommitFilesParams::Compare(from, to)
+                }
+                (from, None) => {
+                    CommitFilesParams::CompareWorkdir(from)
+                }
+            };
+
+            let options = self.options.borrow().diff_options();
+
+            match self.git_files.current()? {
+                Some((fetched, fetched_options, res))
+                    if fetched == params
+                        && fetched_options == options =>
+                {
+                    self.files.update(res.as_slice())?;
+                }
+                _ => self.git_files.fetch(params, options)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn can_focus_diff(&self) -> bool {
+        self.files.selection_file().is_some()
+    }
+}
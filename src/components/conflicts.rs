@@ -0,0 +1,195 @@
+use super::{
+    filetree::FileTreeComponent,
+    utils::filetree::{FileTreeItem, FileTreeItemKind},
+    CommandBlocking, DrawableComponent,
+};
+use crate::{
+    components::{CommandInfo, Component},
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings, try_or_popup,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, ConflictSide},
+    StatusItem, CWD,
+};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// list of conflicted files (part of the status tab),
+/// allows taking either side and marking files as resolved
+pub struct ConflictsComponent {
+    files: FileTreeComponent,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl ConflictsComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            files: FileTreeComponent::new(
+                &strings::title_conflicts(&key_config),
+                false,
+                Some(queue.clone()),
+                theme,
+                key_config.clone(),
+            ),
+            queue,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn set_items(&mut self, list: &[StatusItem]) -> Result<()> {
+        self.files.update(list)?;
+        Ok(())
+    }
+
+    ///
+    pub fn selection(&self) -> Option<FileTreeItem> {
+        self.files.selection()
+    }
+
+    ///
+    pub fn focus_select(&mut self, focus: bool) {
+        self.files.focus(focus);
+        self.files.show_selection(focus);
+    }
+
+    /// returns true if list is empty
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    ///
+    pub fn is_file_seleted(&self) -> bool {
+        self.files.is_file_seleted()
+    }
+
+    fn selected_file(&self) -> Option<String> {
+        self.selection().and_then(|item| {
+            if let FileTreeItemKind::File(i) = item.kind {
+                Some(i.path)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn take_side(&mut self, side: ConflictSide) -> Result<()> {
+        if let Some(path) = self.selected_file() {
+            sync::checkout_conflict_side(CWD, path.as_str(), side)?;
+
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+        }
+
+        Ok(())
+    }
+
+    fn mark_resolved(&mut self) -> Result<()> {
+        if let Some(path) = self.selected_file() {
+            sync::resolve_conflict(CWD, path.as_str())?;
+
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+        }
+
+        Ok(())
+    }
+}
+
+impl DrawableComponent for ConflictsComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        r: Rect,
+    ) -> Result<()> {
+        self.files.draw(f, r)?;
+
+        Ok(())
+    }
+}
+
+impl Component for ConflictsComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        self.files.commands(out, force_all);
+
+        let file_selected = self.is_file_seleted();
+
+        out.push(CommandInfo::new(
+            strings::commands::conflict_take_ours(&self.key_config),
+            file_selected,
+            self.focused() || force_all,
+        ));
+        out.push(CommandInfo::new(
+            strings::commands::conflict_take_theirs(&self.key_config),
+            file_selected,
+            self.focused() || force_all,
+        ));
+        out.push(CommandInfo::new(
+            strings::commands::conflict_resolve(&self.key_config),
+            file_selected,
+            self.focused() || force_all,
+        ));
+
+        CommandBlocking::PassingOn
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.files.event(ev)? {
+            return Ok(true);
+        }
+
+        if self.focused() {
+            if let Event::Key(e) = ev {
+                return if e == self.key_config.conflict_take_ours {
+                    try_or_popup!(
+                        self,
+                        "take ours error:",
+                        self.take_side(ConflictSide::Ours)
+                    );
+                    Ok(true)
+                } else if e == self.key_config.conflict_take_theirs {
+                    try_or_popup!(
+                        self,
+                        "take theirs error:",
+                        self.take_side(ConflictSide::Theirs)
+                    );
+                    Ok(true)
+                } else if e == self.key_config.enter {
+                    try_or_popup!(
+                        self,
+                        "resolve error:",
+                        self.mark_resolved()
+                    );
+                    Ok(true)
+                } else {
+                    Ok(false)
+                };
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn focused(&self) -> bool {
+        self.files.focused()
+    }
+    fn focus(&mut self, focus: bool) {
+        self.files.focus(focus)
+    }
+}
@@ -19,6 +19,7 @@ use tui::{backend::Backend, layout::Rect, Frame};
 pub struct CreateBranchComponent {
     input: TextInputComponent,
     commit_id: Option<CommitId>,
+    stash: Option<CommitId>,
     queue: Queue,
     key_config: SharedKeyConfig,
 }
@@ -104,13 +105,29 @@ impl CreateBranchComponent {
                 &strings::create_branch_popup_msg(&key_config),
             ),
             commit_id: None,
+            stash: None,
             key_config,
         }
     }
 
-    ///
-    pub fn open(&mut self) -> Result<()> {
+    /// opens the popup to create a branch at `commit_id` (HEAD if
+    /// `None`)
+    pub fn open(
+        &mut self,
+        commit_id: Option<CommitId>,
+    ) -> Result<()> {
+        self.commit_id = commit_id;
+        self.stash = None;
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// opens the popup to create a branch at the commit the stash was
+    /// based on and pop the stash onto it
+    pub fn open_for_stash(&mut self, stash: CommitId) -> Result<()> {
         self.commit_id = None;
+        self.stash = Some(stash);
         self.show()?;
 
         Ok(())
@@ -118,8 +135,16 @@ impl CreateBranchComponent {
 
     ///
     pub fn create_branch(&mut self) {
-        let res =
-            sync::create_branch(CWD, self.input.get_text().as_str());
+        let name = self.input.get_text();
+        let res = match (self.stash, self.commit_id) {
+            (Some(stash), _) => {
+                sync::stash_to_branch(CWD, stash, name.as_str())
+            }
+            (None, Some(id)) => {
+                sync::create_branch_at(CWD, name.as_str(), id)
+            }
+            (None, None) => sync::create_branch(CWD, name.as_str()),
+        };
 
         self.input.clear();
         self.hide();
@@ -0,0 +1,160 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{keys::SharedKeyConfig, strings, ui::style::SharedTheme};
+use anyhow::Result;
+use asyncgit::sync::BasicAuthCredential;
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for username and then password (subcomponent of
+/// `PushComponent` and `PullComponent`)
+pub struct CredComponent {
+    visible: bool,
+    key_config: SharedKeyConfig,
+    input_username: TextInputComponent,
+    input_password: TextInputComponent,
+    cred: BasicAuthCredential,
+}
+
+impl CredComponent {
+    ///
+    pub fn new(
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        let mut input_password = TextInputComponent::new(
+            theme.clone(),
+            key_config.clone(),
+            &strings::cred_popup_password_title(&key_config),
+            &strings::cred_popup_password_msg(&key_config),
+        );
+        input_password.set_masked(true);
+
+        Self {
+            visible: false,
+            input_username: TextInputComponent::new(
+                theme,
+                key_config.clone(),
+                &strings::cred_popup_username_title(&key_config),
+                &strings::cred_popup_username_msg(&key_config),
+            ),
+            input_password,
+            key_config,
+            cred: BasicAuthCredential::default(),
+        }
+    }
+
+    /// asks for whatever is missing in `cred`
+    pub fn open(&mut self, cred: BasicAuthCredential) -> Result<()> {
+        self.cred = cred;
+        self.input_username.clear();
+        self.input_password.clear();
+        self.show()
+    }
+
+    ///
+    pub const fn get_cred(&self) -> &BasicAuthCredential {
+        &self.cred
+    }
+
+    fn confirm(&mut self) -> Result<()> {
+        if self.input_username.is_visible() {
+            self.cred.username =
+                Some(self.input_username.get_text().clone());
+            self.input_username.hide();
+        } else if self.input_password.is_visible() {
+            self.cred.password =
+                Some(self.input_password.get_text().clone());
+            self.input_password.hide();
+        }
+
+        self.show()
+    }
+}
+
+impl DrawableComponent for CredComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            self.input_username.draw(f, rect)?;
+            self.input_password.draw(f, rect)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for CredComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::validate_msg(&self.key_config),
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.exit_popup {
+                    self.hide();
+                } else if e == self.key_config.enter {
+                    self.confirm()?;
+                } else if self.input_username.event(ev)?
+                    || self.input_password.event(ev)?
+                {
+                    return Ok(true);
+                }
+            }
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.cred = BasicAuthCredential::default();
+        self.input_username.hide();
+        self.input_password.hide();
+        self.visible = false;
+    }
+
+    /// shows the input for the first missing field,
+    /// stays hidden once the credential is complete
+    fn show(&mut self) -> Result<()> {
+        if self.cred.username.is_none() {
+            self.input_username.show()?;
+        } else if self.cred.password.is_none() {
+            self.input_password.show()?;
+        }
+
+        self.visible = !self.cred.is_complete();
+
+        Ok(())
+    }
+}
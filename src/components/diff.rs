@@ -1,25 +1,42 @@
 use super::{
-    CommandBlocking, Direction, DrawableComponent, ScrollType,
+    utils::string_width_align, CommandBlocking, Direction,
+    DrawableComponent, ScrollType,
 };
 use crate::{
     components::{CommandInfo, Component},
     keys::SharedKeyConfig,
+    options::{Options, SharedOptions},
     queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
     strings, try_or_popup,
-    ui::{self, calc_scroll_top, style::SharedTheme},
+    ui::{
+        self, calc_scroll_top,
+        style::SharedTheme,
+        syntax::{AsyncHighlighter, LineHighlights},
+    },
 };
 use anyhow::Result;
-use asyncgit::{hash, sync, DiffLine, DiffLineType, FileDiff, CWD};
+use asyncgit::{
+    hash,
+    sync::{self, diff::DiffKind},
+    AsyncNotification, DiffLine, DiffLineType, DiffOptions, FileDiff,
+    CWD,
+};
 use bytesize::ByteSize;
+use crossbeam_channel::Sender;
 use crossterm::event::Event;
-use std::{borrow::Cow, cell::Cell, cmp, path::Path};
+use std::{
+    borrow::Cow, cell::Cell, cmp, convert::TryFrom, ops::Range,
+    path::Path, sync::Arc,
+};
 use tui::{
     backend::Backend,
     layout::Rect,
+    style::{Color, Style},
     symbols,
     widgets::{Block, Borders, Paragraph, Text},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Default)]
 struct Current {
@@ -77,6 +94,12 @@ impl Selection {
         };
     }
 
+    /// true once the selection was extended, even if it is back to
+    /// a single line
+    const fn is_multiple(&self) -> bool {
+        matches!(self, Self::Multiple(..))
+    }
+
     fn contains(&self, index: usize) -> bool {
         match self {
             Self::Single(start) => index == *start,
@@ -91,10 +114,112 @@ impl Selection {
     }
 }
 
+/// a row of the side-by-side view referencing lines of the flattened
+/// diff
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SplitRow {
+    Header(usize),
+    Lines {
+        old: Option<usize>,
+        new: Option<usize>,
+    },
+}
+
+impl SplitRow {
+    fn contains(&self, index: usize) -> bool {
+        match self {
+            Self::Header(i) => *i == index,
+            Self::Lines { old, new } => {
+                *old == Some(index) || *new == Some(index)
+            }
+        }
+    }
+}
+
+/// lays out the lines of each hunk in two columns: context lines on
+/// both sides, deletions on the left paired with the additions
+/// following them on the right
+fn split_rows(diff: &FileDiff) -> Vec<SplitRow> {
+    let mut rows = Vec::with_capacity(diff.lines);
+    let mut index = 0_usize;
+
+    for hunk in &diff.hunks {
+        let mut deleted = Vec::new();
+        let mut added = Vec::new();
+
+        let flush = |rows: &mut Vec<SplitRow>,
+                     deleted: &mut Vec<usize>,
+                     added: &mut Vec<usize>| {
+            for i in 0..cmp::max(deleted.len(), added.len()) {
+                rows.push(SplitRow::Lines {
+                    old: deleted.get(i).copied(),
+                    new: added.get(i).copied(),
+                });
+            }
+            deleted.clear();
+            added.clear();
+        };
+
+        for line in &hunk.lines {
+            match line.line_type {
+                DiffLineType::Header => {
+                    flush(&mut rows, &mut deleted, &mut added);
+                    rows.push(SplitRow::Header(index));
+                }
+                DiffLineType::Delete => {
+                    if !added.is_empty() {
+                        flush(&mut rows, &mut deleted, &mut added);
+                    }
+                    deleted.push(index);
+                }
+                DiffLineType::Add => {
+                    added.push(index);
+                }
+                DiffLineType::None => {
+                    flush(&mut rows, &mut deleted, &mut added);
+                    rows.push(SplitRow::Lines {
+                        old: Some(index),
+                        new: Some(index),
+                    });
+                }
+            }
+
+            index += 1;
+        }
+
+        flush(&mut rows, &mut deleted, &mut added);
+    }
+
+    rows
+}
+
+/// width needed to print the largest line number of `diff`
+fn lineno_width(diff: &FileDiff) -> usize {
+    diff.hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.iter())
+        .filter_map(|line| cmp::max(line.old_lineno, line.new_lineno))
+        .max()
+        .unwrap_or_default()
+        .to_string()
+        .len()
+}
+
+fn format_lineno(lineno: Option<u32>, width: usize) -> String {
+    lineno.map_or_else(
+        || format!("{:w$}", "", w = width),
+        |n| format!("{:>w$}", n, w = width),
+    )
+}
+
 ///
 pub struct DiffComponent {
     diff: Option<FileDiff>,
+    /// syntax colors of each line of `diff`
+    syntax: Option<Arc<Vec<LineHighlights>>>,
+    highlighter: AsyncHighlighter,
     pending: bool,
+    split: bool,
     selection: Selection,
     selected_hunk: Option<usize>,
     current_size: Cell<(u16, u16)>,
@@ -104,6 +229,7 @@ pub struct DiffComponent {
     queue: Queue,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
     is_immutable: bool,
 }
 
@@ -113,6 +239,8 @@ impl DiffComponent {
         queue: Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
+        sender: &Sender<AsyncNotification>,
         is_immutable: bool,
     ) -> Self {
         Self {
@@ -120,13 +248,17 @@ impl DiffComponent {
             queue,
             current: Current::default(),
             pending: false,
+            split: false,
             selected_hunk: None,
             diff: None,
+            syntax: None,
+            highlighter: AsyncHighlighter::new(sender),
             current_size: Cell::new((0, 0)),
             selection: Selection::Single(0),
             scroll_top: Cell::new(0),
             theme,
             key_config,
+            options,
             is_immutable,
         }
     }
@@ -141,10 +273,34 @@ impl DiffComponent {
     pub fn current(&self) -> (String, bool) {
         (self.current.path.clone(), self.current.is_stage)
     }
+    /// line in the new version of the file the selection points
+    /// at, deleted lines and hunk headers resolve to the next line
+    /// that still exists
+    pub fn selected_new_lineno(&self) -> Option<u32> {
+        let diff = self.diff.as_ref()?;
+        let selected = self.selection.get_end();
+
+        let mut line_cursor = 0_usize;
+        for hunk in &diff.hunks {
+            if selected < line_cursor + hunk.lines.len() {
+                return hunk
+                    .lines
+                    .iter()
+                    .skip(selected - line_cursor)
+                    .find_map(|line| line.new_lineno)
+                    .or(Some(hunk.header.new_start));
+            }
+
+            line_cursor += hunk.lines.len();
+        }
+
+        None
+    }
     ///
     pub fn clear(&mut self, pending: bool) -> Result<()> {
         self.current = Current::default();
         self.diff = None;
+        self.syntax = None;
         self.scroll_top.set(0);
         self.selection = Selection::Single(0);
         self.selected_hunk = None;
@@ -164,6 +320,8 @@ impl DiffComponent {
         let hash = hash(&diff);
 
         if self.current.hash != hash {
+            self.syntax = self.highlight(&path, hash, &diff);
+
             self.current = Current {
                 path,
                 is_stage,
@@ -178,11 +336,24 @@ impl DiffComponent {
             self.diff = Some(diff);
             self.scroll_top.set(0);
             self.selection = Selection::Single(0);
+        } else if self.syntax.is_none() {
+            // the highlighting might have finished meanwhile
+            self.syntax = self.highlight(&path, hash, &diff);
         }
 
         Ok(())
     }
 
+    fn highlight(
+        &self,
+        path: &str,
+        hash: u64,
+        diff: &FileDiff,
+    ) -> Option<Arc<Vec<LineHighlights>>> {
+        let theme = self.theme.syntax()?;
+        self.highlighter.get(path, hash, diff, theme)
+    }
+
     fn move_selection(
         &mut self,
         move_type: ScrollType,
@@ -223,6 +394,13 @@ impl DiffComponent {
         Ok(())
     }
 
+    fn line_highlights(
+        &self,
+        index: usize,
+    ) -> Option<&LineHighlights> {
+        self.syntax.as_ref().and_then(|syntax| syntax.get(index))
+    }
+
     fn lines_count(&self) -> usize {
         self.diff
             .as_ref()
@@ -307,6 +485,26 @@ impl DiffComponent {
                 let delta_byte_size =
                     ByteSize::b(diff.size_delta.abs() as u64);
                 let sign = if is_positive { "+" } else { "-" };
+
+                let kind = match diff.kind {
+                    DiffKind::Text => None,
+                    DiffKind::Binary => {
+                        Some(strings::diff_binary(&self.key_config))
+                    }
+                    DiffKind::TooLarge => Some(
+                        strings::diff_too_large(&self.key_config),
+                    ),
+                    DiffKind::Submodule => Some(
+                        strings::diff_submodule(&self.key_config),
+                    ),
+                };
+                if let Some(kind) = kind {
+                    res.push(Text::Styled(
+                        Cow::from(format!("{}\n", kind)),
+                        self.theme.text(true, false),
+                    ));
+                }
+
                 res.extend(vec![
                     Text::Raw(Cow::from("size: ")),
                     Text::Styled(
@@ -348,6 +546,10 @@ impl DiffComponent {
                 let mut line_cursor = 0_usize;
                 let mut lines_added = 0_usize;
 
+                // old and new line number followed by a space each
+                let lineno_width = lineno_width(diff);
+                let gutter_width = (lineno_width + 1) * 2;
+
                 for (i, hunk) in diff.hunks.iter().enumerate() {
                     let hunk_selected = self.focused()
                         && self
@@ -370,10 +572,28 @@ impl DiffComponent {
                             if line_cursor >= min
                                 && line_cursor <= max
                             {
+                                res.push(Text::Styled(
+                                    Cow::from(format!(
+                                        "{} {} ",
+                                        format_lineno(
+                                            line.old_lineno,
+                                            lineno_width
+                                        ),
+                                        format_lineno(
+                                            line.new_lineno,
+                                            lineno_width
+                                        ),
+                                    )),
+                                    self.theme.text(false, false),
+                                ));
                                 Self::add_line(
                                     &mut res,
-                                    width,
+                                    width.saturating_sub(
+                                        u16::try_from(gutter_width)
+                                            .unwrap_or_default(),
+                                    ),
                                     line,
+                                    self.line_highlights(line_cursor),
                                     self.focused()
                                         && self
                                             .selection
@@ -396,10 +616,143 @@ impl DiffComponent {
         Ok(res)
     }
 
+    fn get_text_split(
+        &self,
+        rows: &[SplitRow],
+        width: u16,
+        height: u16,
+    ) -> Vec<Text> {
+        let mut res = Vec::new();
+
+        let diff = match &self.diff {
+            Some(diff) => diff,
+            None => return res,
+        };
+
+        let lines: Vec<&DiffLine> =
+            diff.hunks.iter().flat_map(|h| h.lines.iter()).collect();
+
+        let lineno_width = lineno_width(diff);
+
+        // hunk marker and separator take one column each
+        let side_width = usize::from(width.saturating_sub(2)) / 2;
+        let content_width =
+            side_width.saturating_sub(lineno_width + 1);
+
+        let hunk_marker = |index: usize| {
+            self.theme.diff_hunk_marker(
+                self.focused()
+                    && self.selected_hunk.is_some()
+                    && Self::find_selected_hunk(diff, index)
+                        .ok()
+                        .flatten()
+                        == self.selected_hunk,
+            )
+        };
+
+        for row in rows
+            .iter()
+            .skip(self.scroll_top.get())
+            .take(usize::from(height))
+        {
+            match row {
+                SplitRow::Header(index) => {
+                    let selected = self.focused()
+                        && self.selection.contains(*index);
+                    res.push(Text::Styled(
+                        Cow::from(symbols::line::TOP_LEFT),
+                        hunk_marker(*index),
+                    ));
+                    res.push(Text::Styled(
+                        Cow::from(format!(
+                            "{}\n",
+                            string_width_align(
+                                trim_line(&lines[*index].content),
+                                usize::from(width.saturating_sub(1))
+                            )
+                        )),
+                        self.theme.diff_line(
+                            DiffLineType::Header,
+                            selected,
+                        ),
+                    ));
+                }
+                SplitRow::Lines { old, new } => {
+                    let index = old.or(*new).unwrap_or_default();
+                    res.push(Text::Styled(
+                        Cow::from(symbols::line::VERTICAL),
+                        hunk_marker(index),
+                    ));
+
+                    for (side, is_old, separator) in &[
+                        (old, true, symbols::line::VERTICAL),
+                        (new, false, "\n"),
+                    ] {
+                        let (lineno, parts, fill_style) = match side {
+                            Some(index) => {
+                                let line = lines[*index];
+                                let selected = self.focused()
+                                    && self
+                                        .selection
+                                        .contains(*index);
+                                (
+                                    if *is_old {
+                                        line.old_lineno
+                                    } else {
+                                        line.new_lineno
+                                    },
+                                    styled_parts(
+                                        line,
+                                        self.line_highlights(*index),
+                                        selected,
+                                        &self.theme,
+                                    ),
+                                    fill_style(
+                                        line,
+                                        self.line_highlights(*index),
+                                        selected,
+                                        &self.theme,
+                                    ),
+                                )
+                            }
+                            None => (
+                                None,
+                                Vec::new(),
+                                self.theme.text(false, false),
+                            ),
+                        };
+
+                        res.push(Text::Styled(
+                            Cow::from(format!(
+                                "{} ",
+                                format_lineno(lineno, lineno_width)
+                            )),
+                            self.theme.text(false, false),
+                        ));
+                        fit_parts(
+                            &mut res,
+                            parts,
+                            content_width,
+                            fill_style,
+                        );
+                        res.push(Text::Styled(
+                            Cow::from(*separator),
+                            self.theme.diff_hunk_marker(false),
+                        ));
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn add_line(
         text: &mut Vec<Text>,
         width: u16,
         line: &DiffLine,
+        highlights: Option<&LineHighlights>,
         selected: bool,
         selected_hunk: bool,
         end_of_hunk: bool,
@@ -427,8 +780,29 @@ impl DiffComponent {
             }
         }
 
-        let trimmed =
-            line.content.trim_matches(|c| c == '\n' || c == '\r');
+        let trimmed = trim_line(&line.content);
+        let style = theme.diff_line(line.line_type, selected);
+
+        if !line.emphasis.is_empty() || highlights.is_some() {
+            for (part, style) in
+                styled_parts(line, highlights, selected, theme)
+            {
+                text.push(Text::Styled(Cow::from(part), style));
+            }
+
+            let fill = if selected {
+                usize::from(width)
+                    .saturating_sub(trimmed.chars().count())
+            } else {
+                0
+            };
+            text.push(Text::Styled(
+                Cow::from(format!("{:w$}\n", "", w = fill)),
+                fill_style(line, highlights, selected, theme),
+            ));
+
+            return;
+        }
 
         let filled = if selected {
             // selected line
@@ -440,10 +814,7 @@ impl DiffComponent {
         //TODO: allow customize tabsize
         let content = Cow::from(filled.replace("\t", "  "));
 
-        text.push(Text::Styled(
-            content,
-            theme.diff_line(line.line_type, selected),
-        ));
+        text.push(Text::Styled(content, style));
     }
 
     const fn hunk_visible(
@@ -501,6 +872,52 @@ impl DiffComponent {
         Ok(())
     }
 
+    /// indices of the selected lines, `None` unless lines were
+    /// explicitly selected (see `Selection::is_multiple`)
+    fn selected_lines(&self) -> Option<Vec<usize>> {
+        let untracked =
+            self.diff.as_ref().map_or(true, |diff| diff.untracked);
+
+        if self.selection.is_multiple() && !untracked {
+            Some(
+                (self.selection.get_top()
+                    ..=self.selection.get_bottom())
+                    .collect(),
+            )
+        } else {
+            None
+        }
+    }
+
+    fn stage_lines(&mut self, lines: &[usize]) -> Result<()> {
+        let path = self.current.path.clone();
+
+        if self.is_stage() {
+            sync::unstage_lines(
+                CWD,
+                &path,
+                self.current.hash,
+                lines,
+            )?;
+        } else {
+            sync::stage_lines(CWD, &path, self.current.hash, lines)?;
+        }
+
+        self.queue_update();
+
+        Ok(())
+    }
+
+    fn reset_lines(&self, lines: Vec<usize>) {
+        self.queue.as_ref().borrow_mut().push_back(
+            InternalEvent::ConfirmAction(Action::ResetLines(
+                self.current.path.clone(),
+                self.current.hash,
+                lines,
+            )),
+        );
+    }
+
     fn queue_update(&mut self) {
         self.queue
             .as_ref()
@@ -538,6 +955,54 @@ impl DiffComponent {
     const fn is_stage(&self) -> bool {
         self.current.is_stage
     }
+
+    fn diff_options(&self) -> DiffOptions {
+        self.options.borrow().diff_options()
+    }
+
+    /// hunks and lines are staged/reset with patches of the default
+    /// diff, so they only match what is shown while whitespace and
+    /// context options are untouched (untracked files are added whole)
+    fn can_stage_partially(&self) -> bool {
+        let options = self.diff_options();
+        let default = DiffOptions::default();
+
+        !self.is_immutable
+            && (self.diff.as_ref().map_or(false, |d| d.untracked)
+                || (options.ignore_whitespace
+                    == default.ignore_whitespace
+                    && options.context == default.context))
+    }
+
+    /// applies a change of the diff options and requests a new diff
+    fn change_options<F: FnOnce(&mut Options)>(&self, change: F) {
+        change(&mut self.options.borrow_mut());
+        self.queue
+            .as_ref()
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::DIFF));
+    }
+
+    /// short summary of the diff options that differ from default
+    fn options_title(&self) -> String {
+        let options = self.diff_options();
+        let mut parts = Vec::new();
+        if options.ignore_whitespace {
+            parts.push(String::from("-b"));
+        }
+        if options.context != DiffOptions::default().context {
+            parts.push(format!("U{}", options.context));
+        }
+        if options.find_renames && self.is_immutable {
+            parts.push(String::from("renames"));
+        }
+
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", parts.join(" "))
+        }
+    }
 }
 
 impl DrawableComponent for DiffComponent {
@@ -551,16 +1016,39 @@ impl DrawableComponent for DiffComponent {
             r.height.saturating_sub(2),
         ));
 
+        let split_layout = self
+            .diff
+            .as_ref()
+            .filter(|_| self.split)
+            .map(split_rows)
+            .filter(|rows| !rows.is_empty());
+
+        // in split mode scrolling happens in rows instead of lines
+        let (scroll_pos, scroll_max) =
+            if let Some(rows) = &split_layout {
+                (
+                    rows.iter()
+                        .position(|row| {
+                            row.contains(self.selection.get_end())
+                        })
+                        .unwrap_or_default(),
+                    rows.len().saturating_sub(1),
+                )
+            } else {
+                (self.selection.get_end(), self.lines_count())
+            };
+
         self.scroll_top.set(calc_scroll_top(
             self.scroll_top.get(),
             self.current_size.get().1 as usize,
-            self.selection.get_end(),
+            scroll_pos,
         ));
 
         let title = format!(
-            "{}{}",
+            "{}{}{}",
             strings::title_diff(&self.key_config),
-            self.current.path
+            self.current.path,
+            self.options_title(),
         );
 
         let txt = if self.pending {
@@ -568,6 +1056,12 @@ impl DrawableComponent for DiffComponent {
                 Cow::from(strings::loading_text(&self.key_config)),
                 self.theme.text(false, false),
             )]
+        } else if let Some(rows) = &split_layout {
+            self.get_text_split(
+                rows,
+                self.current_size.get().0,
+                self.current_size.get().1,
+            )
         } else {
             self.get_text(r.width, self.current_size.get().1)?
         };
@@ -587,8 +1081,8 @@ impl DrawableComponent for DiffComponent {
                 f,
                 r,
                 &self.theme,
-                self.lines_count(),
-                self.selection.get_end(),
+                scroll_max,
+                scroll_pos,
             );
         }
 
@@ -616,6 +1110,36 @@ impl Component for DiffComponent {
             ));
         }
 
+        out.push(CommandInfo::new(
+            strings::commands::diff_toggle_split(&self.key_config),
+            self.diff.is_some(),
+            self.focused,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::diff_toggle_whitespace(
+                &self.key_config,
+            ),
+            true,
+            self.focused,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::diff_context(&self.key_config),
+            true,
+            self.focused,
+        ));
+
+        if self.is_immutable {
+            out.push(CommandInfo::new(
+                strings::commands::diff_toggle_renames(
+                    &self.key_config,
+                ),
+                true,
+                self.focused,
+            ));
+        }
+
         out.push(
             CommandInfo::new(
                 strings::commands::diff_home_end(&self.key_config),
@@ -625,20 +1149,45 @@ impl Component for DiffComponent {
             .hidden(),
         );
 
-        if !self.is_immutable {
+        if self.can_stage_partially()
+            && self.selected_lines().is_some()
+        {
+            out.push(CommandInfo::new(
+                strings::commands::diff_lines_remove(
+                    &self.key_config,
+                ),
+                true,
+                self.focused && self.is_stage(),
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::diff_lines_add(&self.key_config),
+                true,
+                self.focused && !self.is_stage(),
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::diff_lines_revert(
+                    &self.key_config,
+                ),
+                true,
+                self.focused && !self.is_stage(),
+            ));
+        } else if !self.is_immutable {
+            let enabled = self.can_stage_partially()
+                && self.selected_hunk.is_some();
+
             out.push(CommandInfo::new(
                 strings::commands::diff_hunk_remove(&self.key_config),
-                self.selected_hunk.is_some(),
+                enabled,
                 self.focused && self.is_stage(),
             ));
             out.push(CommandInfo::new(
                 strings::commands::diff_hunk_add(&self.key_config),
-                self.selected_hunk.is_some(),
+                enabled,
                 self.focused && !self.is_stage(),
             ));
             out.push(CommandInfo::new(
                 strings::commands::diff_hunk_revert(&self.key_config),
-                self.selected_hunk.is_some(),
+                enabled,
                 self.focused && !self.is_stage(),
             ));
         }
@@ -674,19 +1223,27 @@ impl Component for DiffComponent {
                     self.move_selection(ScrollType::PageDown)?;
                     Ok(true)
                 } else if e == self.key_config.enter
-                    && !self.is_immutable
+                    && self.can_stage_partially()
                 {
-                    if self.current.is_stage {
+                    if let Some(lines) = self.selected_lines() {
+                        try_or_popup!(
+                            self,
+                            "stage lines error:",
+                            self.stage_lines(&lines)
+                        );
+                    } else if self.current.is_stage {
                         self.unstage_hunk()?;
                     } else {
                         self.stage_hunk()?;
                     }
                     Ok(true)
                 } else if e == self.key_config.status_reset_item
-                    && !self.is_immutable
+                    && self.can_stage_partially()
                     && !self.is_stage()
                 {
-                    if let Some(diff) = &self.diff {
+                    if let Some(lines) = self.selected_lines() {
+                        self.reset_lines(lines);
+                    } else if let Some(diff) = &self.diff {
                         if diff.untracked {
                             self.reset_untracked()?;
                         } else {
@@ -694,6 +1251,31 @@ impl Component for DiffComponent {
                         }
                     }
                     Ok(true)
+                } else if e == self.key_config.diff_toggle_split {
+                    self.split = !self.split;
+                    self.scroll_top.set(0);
+                    Ok(true)
+                } else if e == self.key_config.diff_toggle_whitespace
+                {
+                    self.change_options(
+                        Options::diff_toggle_whitespace,
+                    );
+                    Ok(true)
+                } else if e == self.key_config.diff_context_more {
+                    self.change_options(|o| {
+                        o.diff_context_change(true)
+                    });
+                    Ok(true)
+                } else if e == self.key_config.diff_context_less {
+                    self.change_options(|o| {
+                        o.diff_context_change(false)
+                    });
+                    Ok(true)
+                } else if e == self.key_config.diff_toggle_renames
+                    && self.is_immutable
+                {
+                    self.change_options(Options::diff_toggle_renames);
+                    Ok(true)
                 } else if e == self.key_config.copy
                     && crate::clipboard::is_supported()
                 {
@@ -716,9 +1298,146 @@ impl Component for DiffComponent {
     }
 }
 
+fn trim_line(content: &str) -> &str {
+    content.trim_matches(|c| c == '\n' || c == '\r')
+}
+
+/// splits the trimmed content of `line` into byte ranges that
+/// changed (`true`) and ranges that did not according to
+/// `DiffLine::emphasis`
+fn emphasized_ranges(line: &DiffLine) -> Vec<(Range<usize>, bool)> {
+    let content = line.content.as_str();
+    let trimmed = trim_line(content);
+    let start = content.len()
+        - content
+            .trim_start_matches(|c| c == '\n' || c == '\r')
+            .len();
+    let end = start + trimmed.len();
+
+    let mut ranges = Vec::new();
+    let mut pos = start;
+    for range in &line.emphasis {
+        let from = cmp::min(cmp::max(range.start, pos), end);
+        let to = cmp::max(cmp::min(range.end, end), from);
+
+        if from > pos {
+            ranges.push((pos..from, false));
+        }
+        if to > from {
+            ranges.push((from..to, true));
+        }
+        pos = to;
+    }
+
+    if pos < end || ranges.is_empty() {
+        ranges.push((pos..end, false));
+    }
+
+    ranges
+}
+
+/// content of `line` split into differently styled parts (tabs
+/// expanded): changed words are emphasized, the rest uses the
+/// syntax colors if available
+fn styled_parts(
+    line: &DiffLine,
+    highlights: Option<&LineHighlights>,
+    selected: bool,
+    theme: &SharedTheme,
+) -> Vec<(String, Style)> {
+    let text = |range: Range<usize>| {
+        line.content
+            .get(range)
+            .unwrap_or_default()
+            .replace("\t", "  ")
+    };
+
+    let mut parts = Vec::new();
+
+    for (range, emphasized) in emphasized_ranges(line) {
+        if emphasized {
+            parts.push((
+                text(range),
+                theme.diff_line_emphasis(line.line_type),
+            ));
+        } else if let Some(highlights) = highlights {
+            for (syntax_range, color) in highlights {
+                let from = cmp::max(syntax_range.start, range.start);
+                let to = cmp::min(syntax_range.end, range.end);
+
+                if to > from {
+                    parts.push((
+                        text(from..to),
+                        theme.diff_line_syntax(
+                            line.line_type,
+                            *color,
+                            selected,
+                        ),
+                    ));
+                }
+            }
+        } else {
+            parts.push((
+                text(range),
+                theme.diff_line(line.line_type, selected),
+            ));
+        }
+    }
+
+    parts
+}
+
+/// style filling up the rest of a line
+fn fill_style(
+    line: &DiffLine,
+    highlights: Option<&LineHighlights>,
+    selected: bool,
+    theme: &SharedTheme,
+) -> Style {
+    if highlights.is_some() {
+        theme.diff_line_syntax(line.line_type, Color::Reset, selected)
+    } else {
+        theme.diff_line(line.line_type, selected)
+    }
+}
+
+/// pushes `parts` filling exactly `width` columns (truncated with
+/// `..` like `string_width_align` if too long)
+fn fit_parts(
+    text: &mut Vec<Text>,
+    parts: Vec<(String, Style)>,
+    width: usize,
+    fill_style: Style,
+) {
+    let mut remaining = width;
+
+    for (part, style) in parts {
+        let part_width = UnicodeWidthStr::width(part.as_str());
+
+        if part_width > remaining {
+            text.push(Text::Styled(
+                Cow::from(string_width_align(&part, remaining)),
+                style,
+            ));
+            return;
+        }
+
+        remaining -= part_width;
+        text.push(Text::Styled(Cow::from(part), style));
+    }
+
+    if remaining > 0 {
+        text.push(Text::Styled(
+            Cow::from(format!("{:w$}", "", w = remaining)),
+            fill_style,
+        ));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use asyncgit::sync::diff::Hunk;
 
     #[test]
     fn test_lineendings() {
@@ -729,7 +1448,9 @@ mod tests {
             &DiffLine {
                 content: String::from("line 1\r\n"),
                 line_type: DiffLineType::None,
+                ..DiffLine::default()
             },
+            None,
             false,
             false,
             false,
@@ -744,4 +1465,117 @@ mod tests {
             panic!("err")
         }
     }
+
+    #[test]
+    fn test_split_rows() {
+        let line = |content: &str, line_type| DiffLine {
+            content: String::from(content),
+            line_type,
+            ..DiffLine::default()
+        };
+
+        let diff = FileDiff {
+            hunks: vec![Hunk {
+                lines: vec![
+                    line("@@ -3,4 +3,4 @@", DiffLineType::Header),
+                    line("a\n", DiffLineType::None),
+                    line("b\n", DiffLineType::Delete),
+                    line("c\n", DiffLineType::Delete),
+                    line("B\n", DiffLineType::Add),
+                    line("d\n", DiffLineType::None),
+                    line("e\n", DiffLineType::Add),
+                ],
+                ..Hunk::default()
+            }],
+            lines: 7,
+            ..FileDiff::default()
+        };
+
+        assert_eq!(
+            split_rows(&diff),
+            vec![
+                SplitRow::Header(0),
+                SplitRow::Lines {
+                    old: Some(1),
+                    new: Some(1)
+                },
+                SplitRow::Lines {
+                    old: Some(2),
+                    new: Some(4)
+                },
+                SplitRow::Lines {
+                    old: Some(3),
+                    new: None
+                },
+                SplitRow::Lines {
+                    old: Some(5),
+                    new: Some(5)
+                },
+                SplitRow::Lines {
+                    old: None,
+                    new: Some(6)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lineno_gutter() {
+        let line = |new_lineno| DiffLine {
+            new_lineno,
+            ..DiffLine::default()
+        };
+
+        let diff = FileDiff {
+            hunks: vec![Hunk {
+                lines: vec![
+                    line(None),
+                    line(Some(9)),
+                    line(Some(10)),
+                ],
+                ..Hunk::default()
+            }],
+            lines: 3,
+            ..FileDiff::default()
+        };
+
+        assert_eq!(lineno_width(&diff), 2);
+        assert_eq!(format_lineno(Some(9), 2), " 9");
+        assert_eq!(format_lineno(None, 2), "  ");
+    }
+
+    #[test]
+    fn test_emphasized_ranges() {
+        let line = DiffLine {
+            content: String::from("let b = 1; // x\n"),
+            line_type: DiffLineType::Add,
+            emphasis: vec![4..5, 10..16],
+            ..DiffLine::default()
+        };
+
+        assert_eq!(
+            emphasized_ranges(&line),
+            vec![
+                (0..4, false),
+                (4..5, true),
+                (5..10, false),
+                (10..15, true)
+            ]
+        );
+
+        let mut text = Vec::new();
+        DiffComponent::add_line(
+            &mut text,
+            10,
+            &line,
+            None,
+            false,
+            false,
+            false,
+            &SharedTheme::default(),
+        );
+
+        // hunk marker, four parts and the line ending
+        assert_eq!(text.len(), 6);
+    }
 }
@@ -44,8 +44,12 @@ impl ExternalEditorComponent {
         }
     }
 
-    /// opens file at given `path` in an available editor
-    pub fn open_file_in_editor(path: &Path) -> Result<()> {
+    /// opens file at given `path` in an available editor,
+    /// optionally placing the cursor on `line`
+    pub fn open_file_in_editor(
+        path: &Path,
+        line: Option<u32>,
+    ) -> Result<()> {
         let work_dir = repo_work_dir(CWD)?;
 
         let path = if path.is_relative() {
@@ -80,6 +84,13 @@ impl ExternalEditorComponent {
         let mut editor: Vec<&OsStr> =
             editor.map(|s| OsStr::new(s)).collect();
 
+        let line = line
+            .filter(|_| supports_line_arg(command))
+            .map(|line| format!("+{}", line));
+        if let Some(line) = &line {
+            editor.push(OsStr::new(line));
+        }
+
         editor.push(path.as_os_str());
 
         Command::new(command)
@@ -92,6 +103,18 @@ impl ExternalEditorComponent {
     }
 }
 
+/// editors known to understand `+<line>` before the file, others
+/// would open a file of that name
+fn supports_line_arg(command: &str) -> bool {
+    const EDITORS: &[&str] =
+        &["vi", "vim", "nvim", "nano", "emacs", "kak", "hx"];
+
+    Path::new(command)
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .map_or(false, |name| EDITORS.contains(&name))
+}
+
 impl DrawableComponent for ExternalEditorComponent {
     fn draw<B: Backend>(
         &self,
@@ -158,3 +181,17 @@ impl Component for ExternalEditorComponent {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::supports_line_arg;
+
+    #[test]
+    fn test_supports_line_arg() {
+        assert!(supports_line_arg("vim"));
+        assert!(supports_line_arg("/usr/bin/nvim"));
+        assert!(supports_line_arg("hx"));
+        assert!(!supports_line_arg("code"));
+        assert!(!supports_line_arg("subl"));
+    }
+}
@@ -0,0 +1,318 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, CommitList,
+    Component, DiffComponent, DrawableComponent,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    options::SharedOptions,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, FileHistoryEntry},
+    AsyncDiff, AsyncFileHistory, AsyncNotification, DiffParams,
+    DiffType, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::Clear,
+    Frame,
+};
+
+const SLICE_SIZE: usize = 1200;
+
+/// lists the commits that changed a file and shows the file's diff
+/// in the selected commit
+pub struct FileHistoryComponent {
+    list: CommitList,
+    diff: DiffComponent,
+    git_history: AsyncFileHistory,
+    git_diff: AsyncDiff,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+    options: SharedOptions,
+}
+
+impl DrawableComponent for FileHistoryComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let percentages = if self.diff.focused() {
+                (30, 70)
+            } else {
+                (50, 50)
+            };
+
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(percentages.0),
+                        Constraint::Percentage(percentages.1),
+                    ]
+                    .as_ref(),
+                )
+                .split(rect);
+
+            f.render_widget(Clear, rect);
+
+            self.list.draw(f, chunks[0])?;
+            self.diff.draw(f, chunks[1])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for FileHistoryComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            if self.diff.focused() {
+                self.diff.commands(out, force_all);
+            } else {
+                self.list.commands(out, force_all);
+            }
+
+            out.push(
+                CommandInfo::new(
+                    strings::commands::close_popup(&self.key_config),
+                    true,
+                    true,
+                )
+                .order(1),
+            );
+
+            out.push(CommandInfo::new(
+                strings::commands::file_history_inspect_commit(
+                    &self.key_config,
+                ),
+                self.list.selected_entry().is_some(),
+                !self.diff.focused() || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::diff_focus_right(&self.key_config),
+                self.list.selected_entry().is_some(),
+                !self.diff.focused() || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::diff_focus_left(&self.key_config),
+                true,
+                self.diff.focused() || force_all,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if self.diff.focused() {
+                if self.diff.event(ev)? {
+                    return Ok(true);
+                }
+            } else if self.list.event(ev)? {
+                self.update()?;
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.exit_popup {
+                    self.hide();
+                } else if e == self.key_config.focus_right
+                    && self.list.selected_entry().is_some()
+                {
+                    self.diff.focus(true);
+                } else if e == self.key_config.focus_left
+                    && self.diff.focused()
+                {
+                    self.diff.focus(false);
+                } else if e == self.key_config.enter
+                    && !self.diff.focused()
+                {
+                    if let Some(entry) = self.list.selected_entry() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::InspectCommit(
+                                entry.id, None,
+                            ),
+                        );
+                    }
+                }
+            }
+
+            // stop key event propagation
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.diff.focus(false);
+
+        Ok(())
+    }
+}
+
+impl FileHistoryComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        options: SharedOptions,
+    ) -> Self {
+        Self {
+            list: CommitList::new(
+                &strings::file_history_title(&key_config, ""),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            diff: DiffComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                options.clone(),
+                sender,
+                true,
+            ),
+            git_history: AsyncFileHistory::new(sender),
+            git_diff: AsyncDiff::new(sender),
+            visible: false,
+            queue: queue.clone(),
+            theme,
+            key_config,
+            options,
+        }
+    }
+
+    /// shows the history of the file at `path` as of HEAD
+    pub fn open(&mut self, path: String) -> Result<()> {
+        self.list = CommitList::new(
+            &strings::file_history_title(&self.key_config, &path),
+            self.theme.clone(),
+            self.key_config.clone(),
+        );
+        self.diff.clear(false)?;
+        self.git_history.fetch(path)?;
+        self.show()?;
+
+        self.update()
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_history.is_pending() || self.git_diff.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() {
+            match ev {
+                AsyncNotification::FileHistory => self.update()?,
+                AsyncNotification::Diff => self.update_diff()?,
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<()> {
+        self.list.set_count_total(self.git_history.count()?);
+
+        let selection = self.list.selection();
+        let selection_max = self.list.selection_max();
+        if self.list.items().needs_data(selection, selection_max)
+            || self.list.selected_entry().is_none()
+        {
+            self.fetch_commits()?;
+        }
+
+        self.update_diff()
+    }
+
+    fn fetch_commits(&mut self) -> Result<()> {
+        let want_min =
+            self.list.selection().saturating_sub(SLICE_SIZE / 2);
+
+        let ids: Vec<_> = self
+            .git_history
+            .get_slice(want_min, SLICE_SIZE)?
+            .iter()
+            .map(|e| e.commit_id)
+            .collect();
+
+        let commits = sync::get_commits_info(
+            CWD,
+            &ids,
+            self.list.current_size().0.into(),
+        );
+
+        if let Ok(commits) = commits {
+            self.list.items().set_items(want_min, commits);
+        }
+
+        Ok(())
+    }
+
+    fn selected_entry(&self) -> Result<Option<FileHistoryEntry>> {
+        Ok(self
+            .git_history
+            .get_slice(self.list.selection(), 1)?
+            .into_iter()
+            .next())
+    }
+
+    ///
+    pub fn update_diff(&mut self) -> Result<()> {
+        if let Some(entry) = self.selected_entry()? {
+            let diff_params = DiffParams {
+                path: entry.path.clone(),
+                diff_type: DiffType::Commit(entry.commit_id),
+                options: self.options.borrow().diff_options(),
+            };
+
+            if let Some((params, last)) = self.git_diff.last()? {
+                if params == diff_params {
+                    self.diff.update(entry.path, false, last)?;
+                    return Ok(());
+                }
+            }
+
+            self.git_diff.request(diff_params)?;
+            self.diff.clear(true)?;
+            return Ok(());
+        }
+
+        self.diff.clear(false)
+    }
+}
@@ -16,7 +16,10 @@ use crate::{
 use anyhow::Result;
 use asyncgit::{hash, StatusItem, StatusItemType};
 use crossterm::event::Event;
-use std::{borrow::Cow, cell::Cell, convert::From, path::Path};
+use std::{
+    borrow::Cow, cell::Cell, collections::BTreeSet, convert::From,
+    path::Path,
+};
 use tui::{backend::Backend, layout::Rect, widgets::Text, Frame};
 
 ///
@@ -31,6 +34,8 @@ pub struct FileTreeComponent {
     theme: SharedTheme,
     key_config: SharedKeyConfig,
     scroll_top: Cell<usize>,
+    /// marked files (`None` if marking is not supported)
+    marked: Option<BTreeSet<String>>,
 }
 
 impl FileTreeComponent {
@@ -53,9 +58,15 @@ impl FileTreeComponent {
             key_config,
             scroll_top: Cell::new(0),
             pending: true,
+            marked: None,
         }
     }
 
+    /// allows marking files (see `toggle_mark`)
+    pub fn enable_marking(&mut self) {
+        self.marked = Some(BTreeSet::new());
+    }
+
     ///
     pub fn update(&mut self, list: &[StatusItem]) -> Result<()> {
         self.pending = false;
@@ -63,6 +74,12 @@ impl FileTreeComponent {
         if self.current_hash != new_hash {
             self.tree.update(list)?;
             self.current_hash = new_hash;
+
+            if let Some(marked) = &mut self.marked {
+                marked.retain(|path| {
+                    list.iter().any(|item| &item.path == path)
+                });
+            }
         }
 
         Ok(())
@@ -111,6 +128,53 @@ impl FileTreeComponent {
         self.tree.update(&[])
     }
 
+    /// marks the selected file or all files in the selected folder
+    /// (unmarks them if they are all marked already)
+    pub fn toggle_mark(&mut self) {
+        let files = self.selected_files();
+
+        if let Some(marked) = &mut self.marked {
+            if files.iter().all(|f| marked.contains(f)) {
+                for f in &files {
+                    marked.remove(f);
+                }
+            } else {
+                marked.extend(files);
+            }
+        }
+    }
+
+    /// paths of the marked files
+    pub fn marked(&self) -> Vec<String> {
+        self.marked
+            .as_ref()
+            .map_or_else(Vec::new, |m| m.iter().cloned().collect())
+    }
+
+    fn selected_files(&self) -> Vec<String> {
+        self.tree.selected_item().map_or_else(Vec::new, |item| {
+            match item.kind {
+                FileTreeItemKind::File(f) => vec![f.path],
+                FileTreeItemKind::Path(..) => {
+                    let folder = format!("{}/", item.info.full_path);
+                    self.tree
+                        .tree
+                        .items()
+                        .iter()
+                        .filter_map(|e| match &e.kind {
+                            FileTreeItemKind::File(f)
+                                if f.path.starts_with(&folder) =>
+                            {
+                                Some(f.path.clone())
+                            }
+                            _ => None,
+                        })
+                        .collect()
+                }
+            }
+        })
+    }
+
     ///
     pub fn is_file_seleted(&self) -> bool {
         self.tree.selected_item().map_or(false, |item| {
@@ -139,6 +203,7 @@ impl FileTreeComponent {
         item: &FileTreeItem,
         width: u16,
         selected: bool,
+        marked: Option<bool>,
         theme: &'b SharedTheme,
     ) -> Option<Text<'b>> {
         let indent_str = if item.info.indent == 0 {
@@ -147,6 +212,13 @@ impl FileTreeComponent {
             format!("{:w$}", " ", w = (item.info.indent as usize) * 2)
         };
 
+        // only take up space for the marker when needed
+        let marker = match marked {
+            Some(true) => "* ",
+            Some(false) => "  ",
+            None => "",
+        };
+
         if !item.info.visible {
             return None;
         }
@@ -162,14 +234,18 @@ impl FileTreeComponent {
 
                 let txt = if selected {
                     format!(
-                        "{} {}{:w$}",
+                        "{}{} {}{:w$}",
+                        marker,
                         status_char,
                         indent_str,
                         file,
                         w = width as usize
                     )
                 } else {
-                    format!("{} {}{}", status_char, indent_str, file)
+                    format!(
+                        "{}{} {}{}",
+                        marker, status_char, indent_str, file
+                    )
                 };
 
                 Some(Text::Styled(
@@ -184,7 +260,8 @@ impl FileTreeComponent {
 
                 let txt = if selected {
                     format!(
-                        "  {}{}{:w$}",
+                        "{}  {}{}{:w$}",
+                        marker,
                         indent_str,
                         collapse_char,
                         item.info.path,
@@ -192,8 +269,11 @@ impl FileTreeComponent {
                     )
                 } else {
                     format!(
-                        "  {}{}{}",
-                        indent_str, collapse_char, item.info.path,
+                        "{}  {}{}{}",
+                        marker,
+                        indent_str,
+                        collapse_char,
+                        item.info.path,
                     )
                 };
 
@@ -212,6 +292,7 @@ impl FileTreeComponent {
             StatusItemType::Deleted => '-',
             StatusItemType::Renamed => 'R',
             StatusItemType::Typechange => ' ',
+            StatusItemType::Conflicted => '!',
         }
     }
 }
@@ -267,6 +348,11 @@ impl DrawableComponent for FileTreeComponent {
                 select,
             ));
 
+            let marked = self
+                .marked
+                .as_ref()
+                .filter(|marked| !marked.is_empty());
+
             let items = self
                 .tree
                 .tree
@@ -282,6 +368,9 @@ impl DrawableComponent for FileTreeComponent {
                                 .tree
                                 .selection
                                 .map_or(false, |e| e == idx),
+                        marked.map(|marked| {
+                            marked.contains(&e.info.full_path)
+                        }),
                         &self.theme,
                     )
                 })
@@ -4,8 +4,8 @@ use super::{
     DrawableComponent,
 };
 use crate::{
-    accessors, keys::SharedKeyConfig, queue::Queue, strings,
-    ui::style::SharedTheme,
+    accessors, keys::SharedKeyConfig, options::SharedOptions,
+    queue::Queue, strings, ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
@@ -29,6 +29,7 @@ pub struct InspectCommitComponent {
     git_diff: AsyncDiff,
     visible: bool,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
 }
 
 impl DrawableComponent for InspectCommitComponent {
@@ -157,6 +158,7 @@ impl InspectCommitComponent {
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
             details: CommitDetailsComponent::new(
@@ -164,11 +166,14 @@ impl InspectCommitComponent {
                 sender,
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             diff: DiffComponent::new(
                 queue.clone(),
                 theme,
                 key_config.clone(),
+                options.clone(),
+                sender,
                 true,
             ),
             commit_id: None,
@@ -176,6 +181,7 @@ impl InspectCommitComponent {
             git_diff: AsyncDiff::new(sender),
             visible: false,
             key_config,
+            options,
         }
     }
 
@@ -216,12 +222,15 @@ impl InspectCommitComponent {
     /// called when any tree component changed selection
     pub fn update_diff(&mut self) -> Result<()> {
         if self.is_visible() {
+            self.details.update_files()?;
+
             if let Some(id) = self.commit_id {
                 if let Some(f) = self.details.files().selection_file()
                 {
                     let diff_params = DiffParams {
                         path: f.path.clone(),
                         diff_type: DiffType::Commit(id),
+                        options: self.options.borrow().diff_options(),
                     };
 
                     if let Some((params, last)) =
@@ -1,41 +1,61 @@
+mod blame_file;
+mod branchlist;
 mod changes;
 mod command;
 mod commit;
 mod commit_details;
 mod commitlist;
+mod compare_commits;
+mod conflicts;
 mod create_branch;
+mod cred;
 mod diff;
 mod externaleditor;
+mod file_history;
 mod filetree;
 mod help;
 mod inspect_commit;
 mod msg;
+mod pull;
 mod push;
+mod rebase;
+mod rename_branch;
 mod reset;
 mod stashmsg;
 mod tag_commit;
+mod taglist;
 mod textinput;
 mod utils;
 
 use anyhow::Result;
 use crossterm::event::Event;
 
+pub use blame_file::BlameFileComponent;
+pub use branchlist::BranchListComponent;
 pub use changes::ChangesComponent;
 pub use command::{CommandInfo, CommandText};
 pub use commit::CommitComponent;
 pub use commit_details::CommitDetailsComponent;
 pub use commitlist::CommitList;
+pub use compare_commits::CompareCommitsComponent;
+pub use conflicts::ConflictsComponent;
 pub use create_branch::CreateBranchComponent;
+pub use cred::CredComponent;
 pub use diff::DiffComponent;
 pub use externaleditor::ExternalEditorComponent;
+pub use file_history::FileHistoryComponent;
 pub use filetree::FileTreeComponent;
 pub use help::HelpComponent;
 pub use inspect_commit::InspectCommitComponent;
 pub use msg::MsgComponent;
+pub use pull::PullComponent;
 pub use push::PushComponent;
+pub use rebase::RebaseComponent;
+pub use rename_branch::RenameBranchComponent;
 pub use reset::ResetComponent;
 pub use stashmsg::StashMsgComponent;
 pub use tag_commit::TagCommitComponent;
+pub use taglist::TagListComponent;
 pub use textinput::TextInputComponent;
 pub use utils::filetree::FileTreeItemKind;
 
@@ -0,0 +1,300 @@
+use crate::{
+    components::{
+        visibility_blocking, CommandBlocking, CommandInfo, Component,
+        CredComponent, DrawableComponent,
+    },
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, BasicAuthCredential},
+    AsyncFetch, AsyncNotification, FetchRequest, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// fetches a branch from its remote and optionally merges
+/// the upstream into it afterwards (pull)
+pub struct PullComponent {
+    visible: bool,
+    git_fetch: AsyncFetch,
+    pending: bool,
+    branch: String,
+    remote: String,
+    merge: bool,
+    input_cred: CredComponent,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl PullComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            queue: queue.clone(),
+            pending: false,
+            visible: false,
+            branch: String::new(),
+            remote: String::new(),
+            merge: false,
+            git_fetch: AsyncFetch::new(sender),
+            input_cred: CredComponent::new(
+                theme.clone(),
+                key_config.clone(),
+            ),
+            theme,
+            key_config,
+        }
+    }
+
+    /// only fetch `branch`
+    pub fn fetch(&mut self, branch: String) -> Result<()> {
+        self.request(branch, false)
+    }
+
+    /// fetch `branch` and merge its upstream into it
+    pub fn pull(&mut self, branch: String) -> Result<()> {
+        self.request(branch, true)
+    }
+
+    fn request(&mut self, branch: String, merge: bool) -> Result<()> {
+        if self.pending {
+            return Ok(());
+        }
+
+        self.remote =
+            match sync::get_branch_remote(CWD, branch.as_str()) {
+                Ok(remote) => remote,
+                Err(e) => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "fetch failed:\n{}",
+                            e
+                        )),
+                    );
+                    return Ok(());
+                }
+            };
+
+        self.merge = merge;
+        self.branch = branch;
+
+        // the credential helpers are asked inside the fetch job
+        self.fetch_from_remote(None)
+    }
+
+    fn fetch_from_remote(
+        &mut self,
+        basic_credential: Option<BasicAuthCredential>,
+    ) -> Result<()> {
+        self.pending = true;
+        self.git_fetch.request(FetchRequest {
+            remote: self.remote.clone(),
+            branch: self.branch.clone(),
+            basic_credential,
+            merge: self.merge,
+        })?;
+        self.show()?;
+
+        Ok(())
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        // the popup may have been closed while the job was running
+        if let AsyncNotification::Fetch = ev {
+            self.update()?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    fn update(&mut self) -> Result<()> {
+        self.pending = self.git_fetch.is_pending()?;
+
+        if !self.pending {
+            if let Some(cred) = self.git_fetch.missing_credential()? {
+                self.hide();
+                return self.input_cred.open(cred);
+            }
+
+            if let Some(err) = self.git_fetch.last_result()? {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "{} failed:\n{}",
+                        if self.merge { "pull" } else { "fetch" },
+                        err
+                    )),
+                );
+            }
+
+            self.queue.borrow_mut().push_back(InternalEvent::Update(
+                NeedsUpdate::ALL | NeedsUpdate::TAGS,
+            ));
+
+            self.hide();
+        }
+
+        Ok(())
+    }
+}
+
+impl DrawableComponent for PullComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input_cred.draw(f, rect)?;
+
+        if self.visible {
+            let txt = vec![Text::Raw(
+                if self.merge {
+                    strings::PULL_POPUP_MSG
+                } else {
+                    strings::FETCH_POPUP_MSG
+                }
+                .into(),
+            )];
+
+            let area = ui::centered_rect_absolute(25, 3, f.size());
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(txt.iter())
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Thick)
+                            .title_style(self.theme.title(true))
+                            .border_style(self.theme.block(true)),
+                    )
+                    .style(self.theme.text_danger()),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for PullComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.input_cred.is_visible() {
+            return self.input_cred.commands(out, force_all);
+        }
+
+        if self.is_visible() {
+            out.clear();
+        }
+
+        out.push(CommandInfo::new(
+            strings::commands::close_msg(&self.key_config),
+            !self.pending,
+            self.visible,
+        ));
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.input_cred.is_visible() {
+            self.input_cred.event(ev)?;
+
+            if self.input_cred.get_cred().is_complete() {
+                self.fetch_from_remote(Some(
+                    self.input_cred.get_cred().clone(),
+                ))?;
+            }
+
+            return Ok(true);
+        }
+
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.enter {
+                    self.hide();
+                }
+            }
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible || self.input_cred.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+    use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+    #[test]
+    fn test_fetch_again_after_closing_popup() {
+        let queue: Queue = Rc::new(RefCell::new(VecDeque::new()));
+        let (tx, rx) = unbounded();
+        let key_config = SharedKeyConfig::default();
+        let mut comp = PullComponent::new(
+            &queue,
+            &tx,
+            SharedTheme::default(),
+            key_config.clone(),
+        );
+
+        // fails right away without touching the network
+        comp.remote = String::from("gitui-test-no-such-remote");
+        comp.branch = String::from("master");
+
+        comp.fetch_from_remote(None).unwrap();
+        assert!(comp.pending);
+
+        comp.event(Event::Key(key_config.enter)).unwrap();
+        assert!(!comp.is_visible());
+
+        comp.update_git(rx.recv().unwrap()).unwrap();
+        assert!(!comp.pending);
+
+        comp.fetch_from_remote(None).unwrap();
+        assert!(comp.pending);
+        assert!(comp.is_visible());
+
+        comp.update_git(rx.recv().unwrap()).unwrap();
+        assert!(!comp.pending);
+        assert!(!comp.is_visible());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     components::{
         visibility_blocking, CommandBlocking, CommandInfo, Component,
-        DrawableComponent,
+        CredComponent, DrawableComponent,
     },
     keys::SharedKeyConfig,
     queue::{InternalEvent, Queue},
@@ -9,13 +9,19 @@ use crate::{
     ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
-use asyncgit::{AsyncNotification, AsyncPush, PushRequest};
+use asyncgit::{
+    sync::{self, BasicAuthCredential},
+    AsyncNotification, AsyncPush, PushProgress, PushProgressState,
+    PushRequest, CWD,
+};
+use bytesize::ByteSize;
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
+use std::convert::TryFrom;
 use tui::{
     backend::Backend,
-    layout::Rect,
-    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Block, BorderType, Borders, Clear, Gauge},
     Frame,
 };
 
@@ -24,6 +30,12 @@ pub struct PushComponent {
     visible: bool,
     git_push: AsyncPush,
     pending: bool,
+    progress: Option<PushProgress>,
+    branch: String,
+    /// pushing this tag instead of `branch`
+    tag: Option<String>,
+    remote: String,
+    input_cred: CredComponent,
     queue: Queue,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
@@ -40,8 +52,16 @@ impl PushComponent {
         Self {
             queue: queue.clone(),
             pending: false,
+            progress: None,
+            branch: String::new(),
+            tag: None,
+            remote: String::new(),
             visible: false,
             git_push: AsyncPush::new(sender),
+            input_cred: CredComponent::new(
+                theme.clone(),
+                key_config.clone(),
+            ),
             theme,
             key_config,
         }
@@ -49,10 +69,54 @@ impl PushComponent {
 
     ///
     pub fn push(&mut self, branch: String) -> Result<()> {
+        self.tag = None;
+        let remote = sync::get_branch_remote(CWD, branch.as_str());
+
+        self.start(remote, branch)
+    }
+
+    /// pushes `tag` to the default remote
+    pub fn push_tag(&mut self, tag: String) -> Result<()> {
+        self.tag = Some(tag);
+        let remote = sync::get_default_remote(CWD);
+
+        self.start(remote, String::new())
+    }
+
+    fn start(
+        &mut self,
+        remote: asyncgit::error::Result<String>,
+        branch: String,
+    ) -> Result<()> {
+        self.remote = match remote {
+            Ok(remote) => remote,
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "push failed:\n{}",
+                        e
+                    )),
+                );
+                return Ok(());
+            }
+        };
+        self.branch = branch;
+
+        // the credential helpers are asked inside the push job
+        self.push_to_remote(None)
+    }
+
+    fn push_to_remote(
+        &mut self,
+        basic_credential: Option<BasicAuthCredential>,
+    ) -> Result<()> {
         self.pending = true;
+        self.progress = None;
         self.git_push.request(PushRequest {
-            remote: String::from("origin"),
-            branch,
+            remote: self.remote.clone(),
+            branch: self.branch.clone(),
+            tag: self.tag.clone(),
+            basic_credential,
         })?;
         self.show()?;
         Ok(())
@@ -63,10 +127,9 @@ impl PushComponent {
         &mut self,
         ev: AsyncNotification,
     ) -> Result<()> {
-        if self.is_visible() {
-            if let AsyncNotification::Push = ev {
-                self.update()?;
-            }
+        // the popup may have been closed while the job was running
+        if let AsyncNotification::Push = ev {
+            self.update()?;
         }
 
         Ok(())
@@ -75,8 +138,14 @@ impl PushComponent {
     ///
     fn update(&mut self) -> Result<()> {
         self.pending = self.git_push.is_pending()?;
+        self.progress = self.git_push.progress()?;
 
         if !self.pending {
+            if let Some(cred) = self.git_push.missing_credential()? {
+                self.hide();
+                return self.input_cred.open(cred);
+            }
+
             if let Some(err) = self.git_push.last_result()? {
                 self.queue.borrow_mut().push_back(
                     InternalEvent::ShowErrorMsg(format!(
@@ -93,29 +162,102 @@ impl PushComponent {
     }
 }
 
+impl PushComponent {
+    fn draw_gauge<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        r: Rect,
+        title: &str,
+        (current, total): (usize, usize),
+        active: bool,
+    ) {
+        f.render_widget(
+            Gauge::default()
+                .block(
+                    Block::default()
+                        .title(title)
+                        .borders(Borders::ALL)
+                        .title_style(self.theme.title(active))
+                        .border_style(self.theme.block(active)),
+                )
+                .style(self.theme.push_gauge())
+                .percent(progress_percent(current, total)),
+            r,
+        );
+    }
+}
+
+/// percentage of `current` relative to `total`, clamped to `[0,100]`
+fn progress_percent(current: usize, total: usize) -> u16 {
+    if total == 0 {
+        return 0;
+    }
+
+    u16::try_from(current.saturating_mul(100) / total)
+        .unwrap_or(100)
+        .min(100)
+}
+
 impl DrawableComponent for PushComponent {
     fn draw<B: Backend>(
         &self,
         f: &mut Frame<B>,
-        _rect: Rect,
+        rect: Rect,
     ) -> Result<()> {
+        self.input_cred.draw(f, rect)?;
+
         if self.visible {
-            let txt = vec![Text::Raw(strings::PUSH_POPUP_MSG.into())];
+            let progress = self.progress.unwrap_or_default();
 
-            let area = ui::centered_rect_absolute(25, 3, f.size());
+            let area = ui::centered_rect_absolute(50, 8, f.size());
             f.render_widget(Clear, area);
             f.render_widget(
-                Paragraph::new(txt.iter())
-                    .block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .border_type(BorderType::Thick)
-                            .title_style(self.theme.title(true))
-                            .border_style(self.theme.block(true)),
-                    )
-                    .style(self.theme.text_danger()),
+                Block::default()
+                    .title(strings::PUSH_POPUP_MSG)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Thick)
+                    .title_style(self.theme.title(true))
+                    .border_style(self.theme.block(true)),
                 area,
             );
+
+            let chunks = Layout::default()
+                .vertical_margin(1)
+                .horizontal_margin(1)
+                .direction(Direction::Vertical)
+                .constraints(
+                    [Constraint::Length(3), Constraint::Length(3)]
+                        .as_ref(),
+                )
+                .split(area);
+
+            let packing_title = strings::push_popup_packing(
+                &self.key_config,
+                progress.packed.0,
+                progress.packed.1,
+            );
+            self.draw_gauge(
+                f,
+                chunks[0],
+                &packing_title,
+                progress.packed,
+                true,
+            );
+
+            let pushing_title = strings::push_popup_pushing(
+                &self.key_config,
+                progress.pushed.0,
+                progress.pushed.1,
+                &ByteSize::b(progress.bytes as u64)
+                    .to_string_as(true),
+            );
+            self.draw_gauge(
+                f,
+                chunks[1],
+                &pushing_title,
+                progress.pushed,
+                progress.state == PushProgressState::Pushing,
+            );
         }
 
         Ok(())
@@ -126,8 +268,12 @@ impl Component for PushComponent {
     fn commands(
         &self,
         out: &mut Vec<CommandInfo>,
-        _force_all: bool,
+        force_all: bool,
     ) -> CommandBlocking {
+        if self.input_cred.is_visible() {
+            return self.input_cred.commands(out, force_all);
+        }
+
         if self.is_visible() {
             out.clear();
         }
@@ -142,6 +288,18 @@ impl Component for PushComponent {
     }
 
     fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.input_cred.is_visible() {
+            self.input_cred.event(ev)?;
+
+            if self.input_cred.get_cred().is_complete() {
+                self.push_to_remote(Some(
+                    self.input_cred.get_cred().clone(),
+                ))?;
+            }
+
+            return Ok(true);
+        }
+
         if self.visible {
             if let Event::Key(e) = ev {
                 if e == self.key_config.enter {
@@ -154,7 +312,7 @@ impl Component for PushComponent {
     }
 
     fn is_visible(&self) -> bool {
-        self.visible
+        self.visible || self.input_cred.is_visible()
     }
 
     fn hide(&mut self) {
@@ -167,3 +325,46 @@ impl Component for PushComponent {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+    use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+    #[test]
+    fn test_push_again_after_closing_popup() {
+        let queue: Queue = Rc::new(RefCell::new(VecDeque::new()));
+        let (tx, rx) = unbounded();
+        let key_config = SharedKeyConfig::default();
+        let mut comp = PushComponent::new(
+            &queue,
+            &tx,
+            SharedTheme::default(),
+            key_config.clone(),
+        );
+
+        // fails right away without touching the network
+        comp.remote = String::from("gitui-test-no-such-remote");
+        comp.branch = String::from("master");
+
+        comp.push_to_remote(None).unwrap();
+        assert!(comp.pending);
+
+        comp.event(Event::Key(key_config.enter)).unwrap();
+        assert!(!comp.is_visible());
+
+        while comp.pending {
+            comp.update_git(rx.recv().unwrap()).unwrap();
+        }
+
+        comp.push_to_remote(None).unwrap();
+        assert!(comp.pending);
+        assert!(comp.is_visible());
+
+        while comp.pending {
+            comp.update_git(rx.recv().unwrap()).unwrap();
+        }
+        assert!(!comp.is_visible());
+    }
+}
@@ -0,0 +1,512 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings, ui,
+};
+use anyhow::Result;
+use asyncgit::{
+    error::Error,
+    sync::{
+        self, CommitId, InteractiveRebase, RebaseAction,
+        RebaseStatus, RebaseStep,
+    },
+    CWD,
+};
+use crossterm::event::Event;
+use std::{borrow::Cow, cmp, convert::TryFrom};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+use ui::style::SharedTheme;
+
+const HASH_LENGTH: usize = 7;
+
+/// plans an interactive rebase of a commit and its descendants and
+/// runs it, staying around while it is paused on conflicts
+pub struct RebaseComponent {
+    steps: Vec<RebaseStep>,
+    rebase: Option<InteractiveRebase>,
+    paused_at: Option<usize>,
+    conflicts: Vec<String>,
+    resume_failed: bool,
+    visible: bool,
+    selection: usize,
+    input_reword: TextInputComponent,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for RebaseComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const SIZE: (u16, u16) = (70, 20);
+            let scroll_threshold = usize::from(SIZE.1 / 3);
+            let scroll = u16::try_from(
+                self.selection.saturating_sub(scroll_threshold),
+            )
+            .unwrap_or_default();
+
+            let area =
+                ui::centered_rect_absolute(SIZE.0, SIZE.1, f.size());
+
+            let title = if self.is_paused() {
+                strings::rebase_popup_paused_title(&self.key_config)
+            } else {
+                strings::rebase_popup_title(&self.key_config)
+            };
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text().iter())
+                    .block(
+                        Block::default()
+                            .title(&title)
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Thick),
+                    )
+                    .scroll(scroll)
+                    .alignment(Alignment::Left),
+                area,
+            );
+
+            self.input_reword.draw(f, rect)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for RebaseComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.input_reword.is_visible() {
+            self.input_reword.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::validate_msg(&self.key_config),
+                true,
+                true,
+            ));
+        } else if self.visible || force_all {
+            let planning = !self.is_paused();
+
+            out.push(CommandInfo::new(
+                strings::commands::navigate_branch_popup(
+                    &self.key_config,
+                ),
+                planning,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::rebase_set_action(
+                    &self.key_config,
+                ),
+                planning,
+                planning || force_all,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::rebase_move_step(&self.key_config),
+                planning,
+                planning || force_all,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::rebase_start(&self.key_config),
+                planning,
+                planning || force_all,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::rebase_continue(&self.key_config),
+                self.conflicts.is_empty(),
+                !planning || force_all,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::rebase_abort(&self.key_config),
+                true,
+                !planning || force_all,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if self.input_reword.is_visible() {
+                if self.input_reword.event(ev)? {
+                    return Ok(true);
+                }
+
+                if let Event::Key(e) = ev {
+                    if e == self.key_config.enter {
+                        self.reword();
+                    }
+                }
+
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.exit_popup {
+                    self.hide()
+                } else if self.is_paused() {
+                    if e == self.key_config.enter {
+                        self.run();
+                    } else if e == self.key_config.rebase_abort {
+                        self.abort();
+                    }
+                } else if e == self.key_config.move_down {
+                    self.move_selection(true)
+                } else if e == self.key_config.move_up {
+                    self.move_selection(false)
+                } else if e == self.key_config.shift_down {
+                    self.move_step(true)
+                } else if e == self.key_config.shift_up {
+                    self.move_step(false)
+                } else if e == self.key_config.rebase_pick {
+                    self.set_action(RebaseAction::Pick)
+                } else if e == self.key_config.rebase_reword {
+                    self.open_reword()?
+                } else if e == self.key_config.rebase_squash {
+                    self.set_action(RebaseAction::Squash)
+                } else if e == self.key_config.rebase_fixup {
+                    self.set_action(RebaseAction::Fixup)
+                } else if e == self.key_config.rebase_drop {
+                    self.set_action(RebaseAction::Drop)
+                } else if e == self.key_config.enter {
+                    self.start();
+                }
+            }
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.input_reword.hide();
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl RebaseComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            steps: Vec::new(),
+            rebase: None,
+            paused_at: None,
+            conflicts: Vec::new(),
+            resume_failed: false,
+            visible: false,
+            selection: 0,
+            input_reword: TextInputComponent::new(
+                theme.clone(),
+                key_config.clone(),
+                &strings::rebase_reword_popup_title(&key_config),
+                &strings::rebase_reword_popup_msg(&key_config),
+            ),
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    /// plans rebasing `id` and all its descendants up to HEAD,
+    /// shows the paused rebase instead if there is one
+    pub fn open(&mut self, id: CommitId) -> Result<()> {
+        if !self.is_paused() {
+            self.steps = sync::get_rebase_plan(CWD, id)?;
+            self.selection = 0;
+        }
+
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// refresh conflicts of a paused rebase (e.g. after resolving),
+    /// picks up a rebase left paused by a previous session or
+    /// started by another tool
+    pub fn update(&mut self) -> Result<()> {
+        // one of another tool may move on outside of gitui
+        if !self
+            .rebase
+            .as_ref()
+            .map_or(false, InteractiveRebase::is_own)
+        {
+            self.resume();
+        }
+
+        if self.is_paused() {
+            self.conflicts = sync::get_conflicts(CWD)?;
+        }
+
+        Ok(())
+    }
+
+    fn resume(&mut self) {
+        match InteractiveRebase::resume(CWD) {
+            Ok(Some(rebase)) => {
+                if self.rebase.is_none() {
+                    self.selection = rebase.position();
+                }
+                self.steps = rebase.steps().to_vec();
+                self.paused_at = Some(rebase.position());
+                // rebases of other tools only show up as repo state,
+                // the planner opens for them on request
+                if rebase.is_own() {
+                    self.visible = true;
+                }
+                self.rebase = Some(rebase);
+                self.resume_failed = false;
+            }
+            Ok(None) => {
+                // finished or aborted outside of gitui
+                if self.rebase.is_some() {
+                    self.reset();
+                }
+                self.resume_failed = false;
+            }
+            Err(e) => {
+                // report it once, not on every update
+                if !self.resume_failed {
+                    self.resume_failed = true;
+                    self.show_error("rebase error:", &e);
+                }
+            }
+        }
+    }
+
+    const fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    fn selected_step(&mut self) -> Option<&mut RebaseStep> {
+        self.steps.get_mut(self.selection)
+    }
+
+    fn move_selection(&mut self, inc: bool) {
+        self.selection = if inc {
+            self.selection.saturating_add(1)
+        } else {
+            self.selection.saturating_sub(1)
+        };
+
+        self.selection = cmp::min(
+            self.selection,
+            self.steps.len().saturating_sub(1),
+        );
+    }
+
+    fn move_step(&mut self, down: bool) {
+        let other = if down {
+            self.selection.saturating_add(1)
+        } else {
+            self.selection.saturating_sub(1)
+        };
+
+        if other != self.selection && other < self.steps.len() {
+            self.steps.swap(self.selection, other);
+            self.selection = other;
+        }
+    }
+
+    fn set_action(&mut self, action: RebaseAction) {
+        if let Some(step) = self.selected_step() {
+            step.action = action;
+        }
+    }
+
+    fn open_reword(&mut self) -> Result<()> {
+        if let Some(step) = self.steps.get(self.selection) {
+            self.input_reword
+                .set_text(step.message.trim_end().to_string());
+            self.input_reword.show()?;
+        }
+
+        Ok(())
+    }
+
+    fn reword(&mut self) {
+        let msg = self.input_reword.get_text().clone();
+        self.input_reword.hide();
+
+        if let Some(step) = self.selected_step() {
+            step.action = RebaseAction::Reword;
+            step.message = msg;
+        }
+    }
+
+    fn start(&mut self) {
+        match InteractiveRebase::new(CWD, self.steps.clone()) {
+            Ok(rebase) => {
+                self.rebase = Some(rebase);
+                self.run();
+            }
+            Err(e) => self.show_error("rebase error:", &e),
+        }
+    }
+
+    fn run(&mut self) {
+        let res = match self.rebase.as_mut() {
+            Some(rebase) => rebase.run(),
+            None => return,
+        };
+
+        match res {
+            Ok(RebaseStatus::Finished) => self.reset(),
+            Ok(RebaseStatus::Conflicts(position, conflicts)) => {
+                self.paused_at = Some(position);
+                self.selection = position;
+                self.conflicts = conflicts;
+            }
+            Err(e) => {
+                self.show_error("rebase error:", &e);
+
+                // keep what got applied, aborting is up to the user
+                match self.rebase.as_ref() {
+                    Some(rebase) if rebase.is_paused() => {
+                        self.paused_at = Some(rebase.position());
+                        self.selection = rebase.position();
+                        self.conflicts.clear();
+                    }
+                    // failed to start, nothing changed
+                    _ => self.rebase = None,
+                }
+            }
+        }
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+
+    fn abort(&mut self) {
+        if let Some(rebase) = self.rebase.as_mut() {
+            if let Err(e) = rebase.abort() {
+                self.show_error("abort rebase error:", &e);
+            }
+        }
+
+        self.reset();
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+
+    fn reset(&mut self) {
+        self.rebase = None;
+        self.paused_at = None;
+        self.conflicts.clear();
+        self.steps.clear();
+        self.hide();
+    }
+
+    fn show_error(&self, msg: &str, e: &Error) {
+        log::error!("{} {}", msg, e);
+        self.queue.borrow_mut().push_back(
+            InternalEvent::ShowErrorMsg(format!("{}\n{}", msg, e)),
+        );
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        let mut txt = Vec::with_capacity(self.steps.len() * 3);
+
+        for (idx, step) in self.steps.iter().enumerate() {
+            let selected = self.selection == idx;
+            let dropped = step.action == RebaseAction::Drop;
+
+            let marker = match self.paused_at {
+                Some(pos) if pos == idx => "> ",
+                Some(pos) if pos > idx => "\u{2713} ", //✓
+                _ => "  ",
+            };
+
+            let hash: String = step
+                .id
+                .to_string()
+                .chars()
+                .take(HASH_LENGTH)
+                .collect();
+
+            txt.push(Text::Styled(
+                Cow::from(format!(
+                    "{}{:<7}",
+                    marker,
+                    action_name(step.action)
+                )),
+                self.theme.text(true, selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!("{} ", hash)),
+                self.theme.commit_hash(selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!("{}\n", step.summary())),
+                self.theme.text(!dropped, selected),
+            ));
+        }
+
+        if !self.conflicts.is_empty() {
+            txt.push(Text::Styled(
+                Cow::from("\nconflicts:\n"),
+                self.theme.text_danger(),
+            ));
+
+            for path in &self.conflicts {
+                txt.push(Text::Styled(
+                    Cow::from(format!("  {}\n", path)),
+                    self.theme.text(true, false),
+                ));
+            }
+        }
+
+        txt
+    }
+}
+
+const fn action_name(action: RebaseAction) -> &'static str {
+    match action {
+        RebaseAction::Pick => "pick",
+        RebaseAction::Reword => "reword",
+        RebaseAction::Squash => "squash",
+        RebaseAction::Fixup => "fixup",
+        RebaseAction::Drop => "drop",
+    }
+}
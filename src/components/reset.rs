@@ -8,6 +8,7 @@ use crate::{
     strings, ui,
 };
 use anyhow::Result;
+use asyncgit::sync::ResetType;
 use crossterm::event::Event;
 use std::borrow::Cow;
 use tui::{
@@ -74,6 +75,15 @@ impl Component for ResetComponent {
             true,
             self.visible,
         ));
+        out.push(CommandInfo::new(
+            strings::commands::reset_branch_type(&self.key_config),
+            true,
+            self.visible
+                && matches!(
+                    self.target,
+                    Some(Action::ResetBranch(..))
+                ),
+        ));
 
         visibility_blocking(self)
     }
@@ -85,6 +95,10 @@ impl Component for ResetComponent {
                     self.hide();
                 } else if e == self.key_config.enter {
                     self.confirm();
+                } else if e == self.key_config.focus_left {
+                    self.change_reset_type(false);
+                } else if e == self.key_config.focus_right {
+                    self.change_reset_type(true);
                 }
 
                 return Ok(true);
@@ -142,6 +156,21 @@ impl ResetComponent {
         self.hide();
     }
 
+    fn change_reset_type(&mut self, forward: bool) {
+        if let Some(Action::ResetBranch(_, reset_type)) =
+            self.target.as_mut()
+        {
+            *reset_type = match (*reset_type, forward) {
+                (ResetType::Soft, true)
+                | (ResetType::Hard, false) => ResetType::Mixed,
+                (ResetType::Mixed, true)
+                | (ResetType::Soft, false) => ResetType::Hard,
+                (ResetType::Hard, true)
+                | (ResetType::Mixed, false) => ResetType::Soft,
+            };
+        }
+    }
+
     fn get_text(&self) -> (String, String) {
         if let Some(ref a) = self.target {
             return match a {
@@ -159,6 +188,68 @@ impl ResetComponent {
                     strings::confirm_title_reset(&self.key_config),
                     strings::confirm_msg_resethunk(&self.key_config),
                 ),
+                Action::ResetLines(_, _, lines) => (
+                    strings::confirm_title_reset(&self.key_config),
+                    strings::confirm_msg_resetlines(
+                        &self.key_config,
+                        lines.len(),
+                    ),
+                ),
+                Action::DeleteBranch(branch_ref) => (
+                    strings::confirm_title_delete_branch(
+                        &self.key_config,
+                    ),
+                    strings::confirm_msg_delete_branch(
+                        &self.key_config,
+                        branch_ref,
+                    ),
+                ),
+                Action::DeleteTag(tag) => (
+                    strings::confirm_title_delete_tag(
+                        &self.key_config,
+                    ),
+                    strings::confirm_msg_delete_tag(
+                        &self.key_config,
+                        tag,
+                    ),
+                ),
+                Action::ResetBranch(id, reset_type) => (
+                    strings::confirm_title_reset_branch(
+                        &self.key_config,
+                    ),
+                    strings::confirm_msg_reset_branch(
+                        &self.key_config,
+                        &id.get_short_string(),
+                        *reset_type,
+                    ),
+                ),
+                Action::CherryPick(id) => (
+                    strings::confirm_title_cherry_pick(
+                        &self.key_config,
+                    ),
+                    strings::confirm_msg_cherry_pick(
+                        &self.key_config,
+                        &id.get_short_string(),
+                    ),
+                ),
+                Action::RevertCommit(id) => (
+                    strings::confirm_title_revert_commit(
+                        &self.key_config,
+                    ),
+                    strings::confirm_msg_revert_commit(
+                        &self.key_config,
+                        &id.get_short_string(),
+                    ),
+                ),
+                Action::CheckoutCommit(id) => (
+                    strings::confirm_title_checkout_commit(
+                        &self.key_config,
+                    ),
+                    strings::confirm_msg_checkout_commit(
+                        &self.key_config,
+                        &id.get_short_string(),
+                    ),
+                ),
             };
         }
 
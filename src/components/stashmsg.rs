@@ -10,12 +10,16 @@ use crate::{
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{sync, CWD};
+use asyncgit::{
+    sync::{self, CommitId},
+    CWD,
+};
 use crossterm::event::Event;
 use tui::{backend::Backend, layout::Rect, Frame};
 
 pub struct StashMsgComponent {
     options: StashingOptions,
+    paths: Vec<String>,
     input: TextInputComponent,
     queue: Queue,
     key_config: SharedKeyConfig,
@@ -62,16 +66,7 @@ impl Component for StashMsgComponent {
 
             if let Event::Key(e) = ev {
                 if e == self.key_config.enter {
-                    match sync::stash_save(
-                        CWD,
-                        if self.input.get_text().is_empty() {
-                            None
-                        } else {
-                            Some(self.input.get_text().as_str())
-                        },
-                        self.options.stash_untracked,
-                        self.options.keep_index,
-                    ) {
+                    match self.stash() {
                         Ok(_) => {
                             self.input.clear();
                             self.hide();
@@ -130,6 +125,7 @@ impl StashMsgComponent {
     ) -> Self {
         Self {
             options: StashingOptions::default(),
+            paths: Vec::new(),
             queue,
             input: TextInputComponent::new(
                 theme,
@@ -141,8 +137,39 @@ impl StashMsgComponent {
         }
     }
 
-    ///
-    pub fn options(&mut self, options: StashingOptions) {
+    /// options and files (everything if empty) of the next stash
+    pub fn options(
+        &mut self,
+        options: StashingOptions,
+        paths: Vec<String>,
+    ) {
         self.options = options;
+        self.paths = paths;
+    }
+
+    fn stash(&self) -> asyncgit::error::Result<CommitId> {
+        let text = self.input.get_text();
+        let message = if text.is_empty() {
+            None
+        } else {
+            Some(text.as_str())
+        };
+
+        if self.paths.is_empty() {
+            sync::stash_save(
+                CWD,
+                message,
+                self.options.stash_untracked,
+                self.options.keep_index,
+            )
+        } else {
+            sync::stash_save_paths(
+                CWD,
+                message,
+                &self.paths,
+                self.options.stash_untracked,
+                self.options.keep_index,
+            )
+        }
     }
 }
@@ -13,12 +13,21 @@ use asyncgit::{
     sync::{self, CommitId},
     CWD,
 };
-use crossterm::event::Event;
+use crossterm::{
+    event::Event,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use scopeguard::defer;
+use std::io;
 use tui::{backend::Backend, layout::Rect, Frame};
 
 pub struct TagCommitComponent {
     input: TextInputComponent,
     commit_id: Option<CommitId>,
+    /// name entered in the first step, the input asks for the
+    /// (optional) message of an annotated tag afterwards
+    tag_name: Option<String>,
     queue: Queue,
     key_config: SharedKeyConfig,
 }
@@ -64,7 +73,11 @@ impl Component for TagCommitComponent {
 
             if let Event::Key(e) = ev {
                 if e == self.key_config.enter {
-                    self.tag()
+                    if self.tag_name.is_some() {
+                        self.tag()
+                    } else if !self.input.get_text().is_empty() {
+                        self.ask_message()
+                    }
                 }
 
                 return Ok(true);
@@ -104,6 +117,7 @@ impl TagCommitComponent {
                 &strings::tag_commit_popup_msg(&key_config),
             ),
             commit_id: None,
+            tag_name: None,
             key_config,
         }
     }
@@ -111,33 +125,103 @@ impl TagCommitComponent {
     ///
     pub fn open(&mut self, id: CommitId) -> Result<()> {
         self.commit_id = Some(id);
+        self.tag_name = None;
+        self.input.clear();
+        self.input.set_title(strings::tag_commit_popup_title(
+            &self.key_config,
+        ));
+        self.input.set_default_msg(strings::tag_commit_popup_msg(
+            &self.key_config,
+        ));
         self.show()?;
 
         Ok(())
     }
 
-    ///
+    fn ask_message(&mut self) {
+        let name = self.input.get_text().clone();
+
+        self.input.clear();
+        self.input.set_title(strings::tag_message_popup_title(
+            &self.key_config,
+            &name,
+        ));
+        self.input.set_default_msg(strings::tag_message_popup_msg(
+            &self.key_config,
+        ));
+        self.tag_name = Some(name);
+    }
+
+    /// creates the tag, annotated if a message was entered
     pub fn tag(&mut self) {
-        if let Some(commit_id) = self.commit_id {
-            match sync::tag(CWD, &commit_id, self.input.get_text()) {
-                Ok(_) => {
-                    self.input.clear();
-                    self.hide();
-
-                    self.queue.borrow_mut().push_back(
-                        InternalEvent::Update(NeedsUpdate::ALL),
-                    );
-                }
-                Err(e) => {
-                    self.hide();
-                    log::error!("e: {}", e,);
-                    self.queue.borrow_mut().push_back(
-                        InternalEvent::ShowErrorMsg(format!(
-                            "tag error:\n{}",
-                            e,
-                        )),
-                    );
-                }
+        if let (Some(commit_id), Some(name)) =
+            (self.commit_id, self.tag_name.take())
+        {
+            let message = self.input.get_text().clone();
+
+            if message.trim().is_empty() {
+                let res = sync::tag(CWD, &commit_id, &name);
+                self.tag_created(res);
+            } else if sync::tag_gpg_sign(CWD).unwrap_or_default() {
+                // gpg might ask for a passphrase, which needs the
+                // terminal the ui is drawn on
+                self.input.clear();
+                self.hide();
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::SignTag(commit_id, name, message),
+                );
+            } else {
+                let res = sync::tag_annotated(
+                    CWD, &commit_id, &name, &message,
+                );
+                self.tag_created(res);
+            }
+        }
+    }
+
+    /// creates a signed tag with the terminal handed over to gpg
+    /// (input polling has to be paused already)
+    pub fn sign_tag(
+        &mut self,
+        commit_id: CommitId,
+        name: &str,
+        message: &str,
+    ) -> Result<()> {
+        io::stdout().execute(LeaveAlternateScreen)?;
+        defer! {
+            io::stdout().execute(EnterAlternateScreen).expect("reset terminal");
+        }
+
+        let res = sync::tag_annotated(CWD, &commit_id, name, message);
+        self.tag_created(res);
+
+        Ok(())
+    }
+
+    fn tag_created(
+        &mut self,
+        res: asyncgit::error::Result<CommitId>,
+    ) {
+        match res {
+            Ok(_) => {
+                self.input.clear();
+                self.hide();
+
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(
+                        NeedsUpdate::ALL | NeedsUpdate::TAGS,
+                    ),
+                );
+            }
+            Err(e) => {
+                self.hide();
+                log::error!("e: {}", e,);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "tag error:\n{}",
+                        e,
+                    )),
+                );
             }
         }
     }
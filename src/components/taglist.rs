@@ -0,0 +1,250 @@
+use super::{
+    utils::string_width_align, visibility_blocking, CommandBlocking,
+    CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{Action, InternalEvent, Queue},
+    strings, ui,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, TagInfo},
+    CWD,
+};
+use crossterm::event::Event;
+use std::{borrow::Cow, cmp, convert::TryFrom};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+use ui::style::SharedTheme;
+
+const HASH_LENGTH: usize = 7;
+const NAME_LENGTH: usize = 25;
+
+///
+pub struct TagListComponent {
+    tags: Vec<TagInfo>,
+    visible: bool,
+    selection: usize,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for TagListComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const SIZE: (u16, u16) = (70, 20);
+            let scroll_threshold = usize::from(SIZE.1 / 3);
+            let scroll = u16::try_from(
+                self.selection.saturating_sub(scroll_threshold),
+            )
+            .unwrap_or_default();
+
+            let area =
+                ui::centered_rect_absolute(SIZE.0, SIZE.1, f.size());
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text().iter())
+                    .block(
+                        Block::default()
+                            .title(&strings::tag_list_popup_title(
+                                &self.key_config,
+                            ))
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Thick),
+                    )
+                    .scroll(scroll)
+                    .alignment(Alignment::Left),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for TagListComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.push(CommandInfo::new(
+                strings::commands::navigate_tag_popup(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::delete_tag_popup(&self.key_config),
+                self.selected_tag().is_some(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::push_tag_popup(&self.key_config),
+                self.selected_tag().is_some(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.exit_popup {
+                    self.hide()
+                } else if e == self.key_config.move_down {
+                    self.move_selection(true)
+                } else if e == self.key_config.move_up {
+                    self.move_selection(false)
+                } else if e == self.key_config.delete_tag {
+                    if let Some(tag) = self.selected_tag() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ConfirmAction(
+                                Action::DeleteTag(tag.name.clone()),
+                            ),
+                        );
+                    }
+                } else if e == self.key_config.push {
+                    if let Some(tag) = self.selected_tag() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::PushTag(tag.name.clone()),
+                        );
+                    }
+                }
+            }
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl TagListComponent {
+    ///
+    pub const fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            tags: Vec::new(),
+            visible: false,
+            selection: 0,
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn open(&mut self) -> Result<()> {
+        self.selection = 0;
+        self.update_tags()?;
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// fetch list of tags again (e.g. after delete)
+    pub fn update_tags(&mut self) -> Result<()> {
+        self.tags = sync::get_tags_info(CWD)?;
+        self.fix_selection();
+
+        Ok(())
+    }
+
+    fn selected_tag(&self) -> Option<&TagInfo> {
+        self.tags.get(self.selection)
+    }
+
+    fn fix_selection(&mut self) {
+        self.selection = cmp::min(
+            self.selection,
+            self.tags.len().saturating_sub(1),
+        );
+    }
+
+    fn move_selection(&mut self, inc: bool) {
+        self.selection = if inc {
+            self.selection.saturating_add(1)
+        } else {
+            self.selection.saturating_sub(1)
+        };
+
+        self.fix_selection();
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        let mut txt = Vec::with_capacity(self.tags.len() * 3);
+
+        for (idx, tag) in self.tags.iter().enumerate() {
+            let selected = self.selection == idx;
+
+            let hash: String = tag
+                .commit
+                .to_string()
+                .chars()
+                .take(HASH_LENGTH)
+                .collect();
+
+            let annotation = tag
+                .annotation
+                .as_ref()
+                .and_then(|msg| msg.lines().next())
+                .unwrap_or_default();
+
+            txt.push(Text::Styled(
+                Cow::from(format!(
+                    "{} ",
+                    string_width_align(&tag.name, NAME_LENGTH)
+                )),
+                self.theme.text(true, selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!("{} ", hash)),
+                self.theme.commit_hash(selected),
+            ));
+            txt.push(Text::Styled(
+                Cow::from(format!("{}\n", annotation)),
+                self.theme.text(true, selected),
+            ));
+        }
+
+        txt
+    }
+}
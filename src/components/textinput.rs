@@ -9,10 +9,11 @@ use crate::{
 };
 use anyhow::Result;
 use crossterm::event::{Event, KeyCode, KeyModifiers};
+use std::{borrow::Cow, ops::Range};
 use tui::{
     backend::Backend,
     layout::Rect,
-    style::Modifier,
+    style::{Modifier, Style},
     widgets::{Clear, Text},
     Frame,
 };
@@ -26,6 +27,10 @@ pub struct TextInputComponent {
     theme: SharedTheme,
     key_config: SharedKeyConfig,
     cursor_position: usize,
+    masked: bool,
+    /// chars beyond these columns (of the first and of all following
+    /// lines) are highlighted
+    line_limits: Option<(usize, usize)>,
 }
 
 impl TextInputComponent {
@@ -44,9 +49,22 @@ impl TextInputComponent {
             title: title.to_string(),
             default_msg: default_msg.to_string(),
             cursor_position: 0,
+            masked: false,
+            line_limits: None,
         }
     }
 
+    /// draw every char as `*` (e.g. for passwords)
+    pub fn set_masked(&mut self, masked: bool) {
+        self.masked = masked;
+    }
+
+    /// highlight chars beyond `first` in the first line and beyond
+    /// `others` in the following ones (e.g. the 50/72 commit rule)
+    pub fn set_line_limits(&mut self, first: usize, others: usize) {
+        self.line_limits = Some((first, others));
+    }
+
     /// Clear the `msg`.
     pub fn clear(&mut self) {
         self.msg.clear();
@@ -103,30 +121,121 @@ impl TextInputComponent {
         self.cursor_position = 0;
     }
 
+    /// Move the cursor to `pos` (clamped to the `msg`).
+    pub fn set_cursor_position(&mut self, pos: usize) {
+        let mut pos = pos.min(self.msg.len());
+        while !self.msg.is_char_boundary(pos) {
+            pos -= 1;
+        }
+        self.cursor_position = pos;
+    }
+
     /// Set the `title`.
     pub fn set_title(&mut self, t: String) {
         self.title = t;
     }
 
+    /// Set the placeholder shown while `msg` is empty.
+    pub fn set_default_msg(&mut self, v: String) {
+        self.default_msg = v;
+    }
+
+    fn mask<'a>(&self, txt: &'a str) -> Cow<'a, str> {
+        if self.masked {
+            Cow::from("*".repeat(txt.chars().count()))
+        } else {
+            Cow::from(txt)
+        }
+    }
+
+    /// byte ranges of the chars beyond the `line_limits`
+    fn overflow_ranges(&self) -> Vec<Range<usize>> {
+        let mut res = Vec::new();
+
+        if let Some((first, others)) = self.line_limits {
+            let mut line_start = 0;
+
+            for (idx, line) in self.msg.split('\n').enumerate() {
+                let limit = if idx == 0 { first } else { others };
+
+                if let Some((offset, _)) =
+                    line.char_indices().nth(limit)
+                {
+                    res.push(
+                        line_start + offset..line_start + line.len(),
+                    );
+                }
+
+                line_start += line.len() + 1;
+            }
+        }
+
+        res
+    }
+
+    /// adds `msg[range]` to `txt`, overflowing parts in danger style
+    fn push_styled<'a>(
+        &'a self,
+        txt: &mut Vec<Text<'a>>,
+        range: Range<usize>,
+        overflow: &[Range<usize>],
+        style: Style,
+    ) {
+        let mut start = range.start;
+
+        for over in overflow {
+            let from = over.start.max(start);
+            let to = over.end.min(range.end);
+
+            if from >= to {
+                continue;
+            }
+
+            if from > start {
+                txt.push(Text::styled(
+                    self.mask(&self.msg[start..from]),
+                    style,
+                ));
+            }
+            txt.push(Text::styled(
+                self.mask(&self.msg[from..to]),
+                self.theme.text_danger(),
+            ));
+
+            start = to;
+        }
+
+        if start < range.end {
+            txt.push(Text::styled(
+                self.mask(&self.msg[start..range.end]),
+                style,
+            ));
+        }
+    }
+
     fn get_draw_text(&self) -> Vec<Text> {
         let style = self.theme.text(true, false);
+        let overflow = self.overflow_ranges();
 
         let mut txt = Vec::new();
 
         // the portion of the text before the cursor is added
         // if the cursor is not at the first character
         if self.cursor_position > 0 {
-            txt.push(Text::styled(
-                &self.msg[..self.cursor_position],
+            self.push_styled(
+                &mut txt,
+                0..self.cursor_position,
+                &overflow,
                 style,
-            ));
+            );
         }
 
-        let cursor_str = self
-            .next_char_position()
+        let cursor_str = self.next_char_position().map_or(
             // if the cursor is at the end of the msg
             // a whitespace is used to underline
-            .map_or(" ", |pos| &self.msg[self.cursor_position..pos]);
+            Cow::from(" "),
+            |pos| self.mask(&self.msg[self.cursor_position..pos]),
+        );
 
         if cursor_str == "\n" {
             txt.push(Text::styled(
@@ -137,16 +246,30 @@ impl TextInputComponent {
             ));
         }
 
+        let cursor_style = if overflow
+            .iter()
+            .any(|r| r.contains(&self.cursor_position))
+        {
+            self.theme.text_danger()
+        } else {
+            style
+        };
+
         txt.push(Text::styled(
             cursor_str,
-            style.modifier(Modifier::UNDERLINED),
+            cursor_style.modifier(Modifier::UNDERLINED),
         ));
 
         // the final portion of the text is added if there is
         // still remaining characters
         if let Some(pos) = self.next_char_position() {
             if pos < self.msg.len() {
-                txt.push(Text::styled(&self.msg[pos..], style));
+                self.push_styled(
+                    &mut txt,
+                    pos..self.msg.len(),
+                    &overflow,
+                    style,
+                );
             }
         }
 
@@ -303,6 +426,14 @@ mod tests {
         }
     }
 
+    fn get_style(t: &Text) -> Option<Style> {
+        if let Text::Styled(_, style) = t {
+            Some(*style)
+        } else {
+            None
+        }
+    }
+
     #[test]
     fn test_visualize_newline() {
         let mut comp = TextInputComponent::new(
@@ -324,4 +455,79 @@ mod tests {
         assert_eq!(get_text(&txt[2]), Some("\n"));
         assert_eq!(get_text(&txt[3]), Some("b"));
     }
+
+    #[test]
+    fn test_masked() {
+        let mut comp = TextInputComponent::new(
+            SharedTheme::default(),
+            SharedKeyConfig::default(),
+            "",
+            "",
+        );
+
+        comp.set_masked(true);
+        comp.set_text(String::from("p\u{e4}ss"));
+        comp.incr_cursor();
+
+        let txt = comp.get_draw_text();
+
+        assert_eq!(txt.len(), 3);
+        assert_eq!(get_text(&txt[0]), Some("*"));
+        assert_eq!(get_text(&txt[1]), Some("*"));
+        assert_eq!(get_text(&txt[2]), Some("**"));
+    }
+
+    #[test]
+    fn test_masked_cursor_at_end() {
+        let mut comp = TextInputComponent::new(
+            SharedTheme::default(),
+            SharedKeyConfig::default(),
+            "",
+            "",
+        );
+
+        comp.set_masked(true);
+        comp.set_text(String::from("pass"));
+        comp.set_cursor_position(4);
+
+        let txt = comp.get_draw_text();
+
+        assert_eq!(txt.len(), 2);
+        assert_eq!(get_text(&txt[0]), Some("****"));
+        assert_eq!(get_text(&txt[1]), Some(" "));
+    }
+
+    #[test]
+    fn test_line_limits() {
+        let mut comp = TextInputComponent::new(
+            SharedTheme::default(),
+            SharedKeyConfig::default(),
+            "",
+            "",
+        );
+
+        comp.set_line_limits(3, 2);
+        comp.set_text(String::from("abcde\n\nxy\nxyz"));
+
+        assert_eq!(comp.overflow_ranges(), vec![3..5, 12..13]);
+
+        comp.set_cursor_position(4);
+
+        let txt = comp.get_draw_text();
+
+        assert_eq!(txt.len(), 5);
+        assert_eq!(get_text(&txt[0]), Some("abc"));
+        assert_eq!(get_text(&txt[1]), Some("d"));
+        assert_eq!(get_text(&txt[2]), Some("e"));
+        assert_eq!(get_text(&txt[3]), Some("\n\nxy\nxy"));
+        assert_eq!(get_text(&txt[4]), Some("z"));
+
+        let danger = Some(comp.theme.text_danger());
+        assert_eq!(
+            get_style(&txt[0]),
+            Some(comp.theme.text(true, false))
+        );
+        assert_eq!(get_style(&txt[1]), danger);
+        assert_eq!(get_style(&txt[4]), danger);
+    }
 }
@@ -0,0 +1,86 @@
+//! recent commit messages of the repo, kept inside its git dir so
+//! they do not mix between repositories
+
+use anyhow::Result;
+use asyncgit::{sync::utils::repo_git_dir, CWD};
+use ron::{
+    de::from_bytes,
+    ser::{to_string_pretty, PrettyConfig},
+};
+use std::{fs, path::PathBuf};
+
+/// number of recent commit messages that are remembered
+const HISTORY_LENGTH: usize = 20;
+
+/// recent commit messages (newest first), persisted in
+/// `.git/gitui/commit_msgs.ron`
+#[derive(Default)]
+pub struct CommitMsgHistory {
+    msgs: Vec<String>,
+}
+
+impl CommitMsgHistory {
+    pub fn load() -> Self {
+        Self::read().unwrap_or_else(|e| {
+            log::warn!(
+                "failed to read commit message history: {}",
+                e
+            );
+            Self::default()
+        })
+    }
+
+    /// the `idx`th most recent commit message
+    pub fn get(&self, idx: usize) -> Option<String> {
+        self.msgs.get(idx).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.msgs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.msgs.is_empty()
+    }
+
+    pub fn add(&mut self, msg: &str) {
+        self.msgs.retain(|m| m != msg);
+        self.msgs.insert(0, msg.to_string());
+        self.msgs.truncate(HISTORY_LENGTH);
+
+        if self.save().is_err() {
+            log::warn!("failed to store commit message history.")
+        }
+    }
+
+    fn get_file() -> Result<PathBuf> {
+        let git_dir = repo_git_dir(CWD)?;
+        Ok(PathBuf::from(git_dir)
+            .join("gitui")
+            .join("commit_msgs.ron"))
+    }
+
+    fn read() -> Result<Self> {
+        let file = Self::get_file()?;
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+
+        Ok(Self {
+            msgs: from_bytes(&fs::read(file)?)?,
+        })
+    }
+
+    fn save(&self) -> Result<()> {
+        let file = Self::get_file()?;
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let data =
+            to_string_pretty(&self.msgs, PrettyConfig::default())?;
+        fs::write(file, data)?;
+
+        Ok(())
+    }
+}
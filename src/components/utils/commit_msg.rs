@@ -0,0 +1,241 @@
+//! helpers shaping commit messages: the 50/72 rule, comments and
+//! conventional commit types
+
+/// recommended max length of the subject line
+pub const SUBJECT_WIDTH: usize = 50;
+/// width the lines of the body get wrapped at
+pub const BODY_WIDTH: usize = 72;
+
+/// types of the conventional commits spec offered for completion
+static COMMIT_TYPES: &[&str] = &[
+    "build", "chore", "ci", "docs", "feat", "fix", "perf",
+    "refactor", "revert", "style", "test",
+];
+
+/// removes lines starting with `#` (like git does for the editor)
+pub fn strip_comments(msg: &str) -> String {
+    let msg: String = msg
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .flat_map(|l| vec![l, "\n"])
+        .collect();
+
+    msg.trim().to_string()
+}
+
+/// number of chars of the subject line
+pub fn subject_len(msg: &str) -> usize {
+    msg.lines().next().map_or(0, |l| l.chars().count())
+}
+
+/// wraps the lines of the body (everything after the subject) that
+/// are longer than `width` at whitespace.
+/// indented lines (code, lists continued by hand), lines containing
+/// urls and the trailers (`Signed-off-by: ..`) are kept as they are
+pub fn wrap_body(msg: &str, width: usize) -> String {
+    let lines: Vec<&str> = msg.split('\n').collect();
+    let trailers_start = trailers_start(&lines);
+
+    let mut res =
+        lines.first().copied().unwrap_or_default().to_string();
+
+    for (idx, line) in lines.iter().enumerate().skip(1) {
+        res.push('\n');
+
+        if line.chars().count() <= width
+            || idx >= trailers_start
+            || line.starts_with(char::is_whitespace)
+            || line.contains("://")
+        {
+            res.push_str(line);
+            continue;
+        }
+
+        let mut len = 0;
+        for word in line.split_whitespace() {
+            let word_len = word.chars().count();
+
+            if len > 0 && len + 1 + word_len > width {
+                res.push('\n');
+                len = 0;
+            } else if len > 0 {
+                res.push(' ');
+                len += 1;
+            }
+
+            res.push_str(word);
+            len += word_len;
+        }
+    }
+
+    res
+}
+
+/// index of the first line of the trailers: the last paragraph of
+/// the body if all its lines look like `Token: value`
+fn trailers_start(lines: &[&str]) -> usize {
+    let last = lines
+        .iter()
+        .rposition(|l| !l.trim().is_empty())
+        .unwrap_or_default();
+    let start = lines[..last]
+        .iter()
+        .rposition(|l| l.trim().is_empty())
+        .map_or(last, |idx| idx + 1);
+
+    // the subject is never a trailer
+    if start > 0 && lines[start..=last].iter().all(|l| is_trailer(l))
+    {
+        start
+    } else {
+        lines.len()
+    }
+}
+
+/// `Token: value` like `Signed-off-by: name <mail>`
+fn is_trailer(line: &str) -> bool {
+    line.find(": ").map_or(false, |idx| {
+        idx > 0
+            && line[..idx]
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// the (partial) conventional commit type at the start of the
+/// subject: everything before the `:` if it is a single lowercase word
+pub fn type_prefix(msg: &str) -> Option<&str> {
+    let subject = msg.lines().next().unwrap_or_default();
+    let prefix = subject.split(':').next().unwrap_or_default();
+
+    if prefix.chars().all(|c| c.is_ascii_lowercase()) {
+        Some(prefix)
+    } else {
+        None
+    }
+}
+
+/// conventional commit types starting with `prefix`
+pub fn matching_types(prefix: &str) -> Vec<&'static str> {
+    COMMIT_TYPES
+        .iter()
+        .copied()
+        .filter(|t| t.starts_with(prefix))
+        .collect()
+}
+
+/// replaces the type prefix of the subject with `typ: `, returns the
+/// new message and the position right after the prefix
+pub fn set_type(msg: &str, typ: &str) -> (String, usize) {
+    let subject_end = msg.find('\n').unwrap_or(msg.len());
+    let subject = &msg[..subject_end];
+
+    let description = subject
+        .find(':')
+        .map_or("", |idx| subject[idx + 1..].trim_start());
+
+    let prefix = format!("{}: ", typ);
+    let cursor = prefix.len();
+
+    (
+        format!("{}{}{}", prefix, description, &msg[subject_end..]),
+        cursor,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_comments() {
+        assert_eq!(
+            strip_comments("subject\n# comment\n\nbody\n#"),
+            "subject\n\nbody"
+        );
+        assert_eq!(strip_comments("# only comments\n"), "");
+    }
+
+    #[test]
+    fn test_wrap_body() {
+        let msg = "a subject line that is not wrapped at all\n\n\
+            one two three four\n  indented line stays as is\nfive";
+
+        assert_eq!(
+            wrap_body(msg, 9),
+            "a subject line that is not wrapped at all\n\n\
+            one two\nthree\nfour\n  indented line stays as is\nfive"
+        );
+
+        assert_eq!(
+            wrap_body("s\n\nword1 word2", 100),
+            "s\n\nword1 word2"
+        );
+        assert_eq!(
+            wrap_body("s\n\nsee https://example.com/too/long", 10),
+            "s\n\nsee https://example.com/too/long"
+        );
+        assert_eq!(
+            wrap_body("s\n\nwords-too-long-for-a-line x", 10),
+            "s\n\nwords-too-long-for-a-line\nx"
+        );
+    }
+
+    #[test]
+    fn test_wrap_body_keeps_trailers() {
+        let msg =
+            "s\n\nSigned-off-by: Some Name <some@example.com>\n\
+            Co-authored-by: Other Name <other@example.com>";
+
+        assert_eq!(wrap_body(msg, 20), msg);
+
+        // only the last paragraph holds trailers
+        assert_eq!(
+            wrap_body("s\n\nNote: a b c\n\nAcked-by: a b c", 8),
+            "s\n\nNote: a\nb c\n\nAcked-by: a b c"
+        );
+        assert_eq!(
+            wrap_body("s\n\nNote: a b c\nnot a trailer", 8),
+            "s\n\nNote: a\nb c\nnot a\ntrailer"
+        );
+
+        assert!(is_trailer("Reviewed-by: x"));
+        assert!(!is_trailer("a sentence: with a colon"));
+    }
+
+    #[test]
+    fn test_type_prefix() {
+        assert_eq!(type_prefix(""), Some(""));
+        assert_eq!(type_prefix("fe"), Some("fe"));
+        assert_eq!(type_prefix("feat: foo\nbody"), Some("feat"));
+        assert_eq!(type_prefix("fix the bug"), None);
+        assert_eq!(type_prefix("Fix"), None);
+    }
+
+    #[test]
+    fn test_matching_types() {
+        assert_eq!(matching_types("f"), vec!["feat", "fix"]);
+        assert_eq!(matching_types("re"), vec!["refactor", "revert"]);
+        assert!(matching_types("x").is_empty());
+        assert_eq!(matching_types("").len(), COMMIT_TYPES.len());
+    }
+
+    #[test]
+    fn test_set_type() {
+        assert_eq!(
+            set_type("fe", "feat"),
+            (String::from("feat: "), 6)
+        );
+        assert_eq!(
+            set_type("feat: add x\n\nbody", "fix"),
+            (String::from("fix: add x\n\nbody"), 5)
+        );
+        assert_eq!(set_type("", "ci"), (String::from("ci: "), 4));
+    }
+
+    #[test]
+    fn test_subject_len() {
+        assert_eq!(subject_len(""), 0);
+        assert_eq!(subject_len("s\u{e4}bject\nbody"), 7);
+    }
+}
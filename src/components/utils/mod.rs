@@ -1,5 +1,8 @@
 use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use unicode_width::UnicodeWidthStr;
 
+pub mod commit_history;
+pub mod commit_msg;
 pub mod filetree;
 pub mod logitems;
 pub mod statustree;
@@ -33,3 +36,54 @@ pub fn time_to_string(secs: i64, short: bool) -> String {
     })
     .to_string()
 }
+
+/// pads `s` to `width` or truncates it (postfixing `..`) if it is too long
+#[inline]
+pub fn string_width_align(s: &str, width: usize) -> String {
+    static POSTFIX: &str = "..";
+
+    let len = UnicodeWidthStr::width(s);
+    let width_wo_postfix = width.saturating_sub(POSTFIX.len());
+
+    if (len >= width_wo_postfix && len <= width)
+        || (len <= width_wo_postfix)
+    {
+        format!("{:w$}", s, w = width)
+    } else {
+        let mut s = s.to_string();
+        s.truncate(find_truncate_point(&s, width_wo_postfix));
+        format!("{}{}", s, POSTFIX)
+    }
+}
+
+#[inline]
+fn find_truncate_point(s: &str, chars: usize) -> usize {
+    s.chars().take(chars).map(char::len_utf8).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_width_align() {
+        assert_eq!(string_width_align("123", 3), "123");
+        assert_eq!(string_width_align("123", 2), "..");
+        assert_eq!(string_width_align("123", 3), "123");
+        assert_eq!(string_width_align("12345", 6), "12345 ");
+        assert_eq!(string_width_align("1234556", 4), "12..");
+    }
+
+    #[test]
+    fn test_string_width_align_unicode() {
+        assert_eq!(string_width_align("äste", 3), "ä..");
+        assert_eq!(
+            string_width_align("wüsten äste", 10),
+            "wüsten ä.."
+        );
+        assert_eq!(
+            string_width_align("Jon Grythe Stødle", 19),
+            "Jon Grythe Stødle  "
+        );
+    }
+}
@@ -15,6 +15,68 @@ use std::{
 
 pub type SharedKeyConfig = Rc<KeyConfig>;
 
+// keys added after the first release of the key config file get
+// their default from `KeyConfig::default`, so an existing
+// `key_config.ron` lacking them still loads
+macro_rules! key_defaults {
+    ($($name:ident),* $(,)?) => {
+        mod defaults {
+            use super::KeyConfig;
+            use crossterm::event::KeyEvent;
+
+            $(
+                pub fn $name() -> KeyEvent {
+                    KeyConfig::default().$name
+                }
+            )*
+        }
+    };
+}
+
+key_defaults!(
+    stashing_mark_file,
+    stash_pop,
+    stash_branch,
+    commit_history_prev,
+    commit_history_next,
+    commit_complete_type,
+    commit_wrap_body,
+    fetch,
+    pull,
+    select_branch,
+    rename_branch,
+    delete_branch,
+    delete_tag,
+    conflict_take_ours,
+    conflict_take_theirs,
+    rebase_interactive,
+    rebase_pick,
+    rebase_reword,
+    rebase_squash,
+    rebase_fixup,
+    rebase_drop,
+    rebase_abort,
+    blame,
+    file_history,
+    log_search,
+    log_search_next,
+    log_search_prev,
+    diff_toggle_split,
+    diff_toggle_whitespace,
+    diff_context_more,
+    diff_context_less,
+    diff_toggle_renames,
+    log_mark_commit,
+    compare_commits,
+    log_compare_workdir,
+    log_cherry_pick,
+    log_revert_commit,
+    log_checkout_commit,
+    log_reset_commit,
+    log_graph_style,
+    log_open_tags,
+);
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct KeyConfig {
     pub tab_status: KeyEvent,
@@ -53,14 +115,96 @@ pub struct KeyConfig {
     pub stashing_save: KeyEvent,
     pub stashing_toggle_untracked: KeyEvent,
     pub stashing_toggle_index: KeyEvent,
+    #[serde(default = "defaults::stashing_mark_file")]
+    pub stashing_mark_file: KeyEvent,
     pub stash_open: KeyEvent,
     pub stash_drop: KeyEvent,
+    #[serde(default = "defaults::stash_pop")]
+    pub stash_pop: KeyEvent,
+    #[serde(default = "defaults::stash_branch")]
+    pub stash_branch: KeyEvent,
     pub cmd_bar_toggle: KeyEvent,
     pub log_tag_commit: KeyEvent,
     pub commit_amend: KeyEvent,
+    #[serde(default = "defaults::commit_history_prev")]
+    pub commit_history_prev: KeyEvent,
+    #[serde(default = "defaults::commit_history_next")]
+    pub commit_history_next: KeyEvent,
+    #[serde(default = "defaults::commit_complete_type")]
+    pub commit_complete_type: KeyEvent,
+    #[serde(default = "defaults::commit_wrap_body")]
+    pub commit_wrap_body: KeyEvent,
     pub copy: KeyEvent,
     pub create_branch: KeyEvent,
     pub push: KeyEvent,
+    #[serde(default = "defaults::fetch")]
+    pub fetch: KeyEvent,
+    #[serde(default = "defaults::pull")]
+    pub pull: KeyEvent,
+    #[serde(default = "defaults::select_branch")]
+    pub select_branch: KeyEvent,
+    #[serde(default = "defaults::rename_branch")]
+    pub rename_branch: KeyEvent,
+    #[serde(default = "defaults::delete_branch")]
+    pub delete_branch: KeyEvent,
+    #[serde(default = "defaults::delete_tag")]
+    pub delete_tag: KeyEvent,
+    #[serde(default = "defaults::conflict_take_ours")]
+    pub conflict_take_ours: KeyEvent,
+    #[serde(default = "defaults::conflict_take_theirs")]
+    pub conflict_take_theirs: KeyEvent,
+    #[serde(default = "defaults::rebase_interactive")]
+    pub rebase_interactive: KeyEvent,
+    #[serde(default = "defaults::rebase_pick")]
+    pub rebase_pick: KeyEvent,
+    #[serde(default = "defaults::rebase_reword")]
+    pub rebase_reword: KeyEvent,
+    #[serde(default = "defaults::rebase_squash")]
+    pub rebase_squash: KeyEvent,
+    #[serde(default = "defaults::rebase_fixup")]
+    pub rebase_fixup: KeyEvent,
+    #[serde(default = "defaults::rebase_drop")]
+    pub rebase_drop: KeyEvent,
+    #[serde(default = "defaults::rebase_abort")]
+    pub rebase_abort: KeyEvent,
+    #[serde(default = "defaults::blame")]
+    pub blame: KeyEvent,
+    #[serde(default = "defaults::file_history")]
+    pub file_history: KeyEvent,
+    #[serde(default = "defaults::log_search")]
+    pub log_search: KeyEvent,
+    #[serde(default = "defaults::log_search_next")]
+    pub log_search_next: KeyEvent,
+    #[serde(default = "defaults::log_search_prev")]
+    pub log_search_prev: KeyEvent,
+    #[serde(default = "defaults::diff_toggle_split")]
+    pub diff_toggle_split: KeyEvent,
+    #[serde(default = "defaults::diff_toggle_whitespace")]
+    pub diff_toggle_whitespace: KeyEvent,
+    #[serde(default = "defaults::diff_context_more")]
+    pub diff_context_more: KeyEvent,
+    #[serde(default = "defaults::diff_context_less")]
+    pub diff_context_less: KeyEvent,
+    #[serde(default = "defaults::diff_toggle_renames")]
+    pub diff_toggle_renames: KeyEvent,
+    #[serde(default = "defaults::log_mark_commit")]
+    pub log_mark_commit: KeyEvent,
+    #[serde(default = "defaults::compare_commits")]
+    pub compare_commits: KeyEvent,
+    #[serde(default = "defaults::log_compare_workdir")]
+    pub log_compare_workdir: KeyEvent,
+    #[serde(default = "defaults::log_cherry_pick")]
+    pub log_cherry_pick: KeyEvent,
+    #[serde(default = "defaults::log_revert_commit")]
+    pub log_revert_commit: KeyEvent,
+    #[serde(default = "defaults::log_checkout_commit")]
+    pub log_checkout_commit: KeyEvent,
+    #[serde(default = "defaults::log_reset_commit")]
+    pub log_reset_commit: KeyEvent,
+    #[serde(default = "defaults::log_graph_style")]
+    pub log_graph_style: KeyEvent,
+    #[serde(default = "defaults::log_open_tags")]
+    pub log_open_tags: KeyEvent,
 }
 
 #[rustfmt::skip]
@@ -103,14 +247,55 @@ impl Default for KeyConfig {
 			stashing_save: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
 			stashing_toggle_untracked: KeyEvent { code: KeyCode::Char('u'), modifiers: KeyModifiers::empty()},
 			stashing_toggle_index: KeyEvent { code: KeyCode::Char('i'), modifiers: KeyModifiers::empty()},
+			stashing_mark_file: KeyEvent { code: KeyCode::Char('m'), modifiers: KeyModifiers::empty()},
 			stash_open: KeyEvent { code: KeyCode::Right, modifiers: KeyModifiers::empty()},
 			stash_drop: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
+			stash_pop: KeyEvent { code: KeyCode::Char('p'), modifiers: KeyModifiers::empty()},
+			stash_branch: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::empty()},
 			cmd_bar_toggle: KeyEvent { code: KeyCode::Char('.'), modifiers: KeyModifiers::empty()},
 			log_tag_commit: KeyEvent { code: KeyCode::Char('t'), modifiers: KeyModifiers::empty()},
 			commit_amend: KeyEvent { code: KeyCode::Char('a'), modifiers: KeyModifiers::CONTROL},
+			commit_history_prev: KeyEvent { code: KeyCode::Char('p'), modifiers: KeyModifiers::CONTROL},
+			commit_history_next: KeyEvent { code: KeyCode::Char('n'), modifiers: KeyModifiers::CONTROL},
+			commit_complete_type: KeyEvent { code: KeyCode::Tab, modifiers: KeyModifiers::empty()},
+			commit_wrap_body: KeyEvent { code: KeyCode::Char('w'), modifiers: KeyModifiers::CONTROL},
             copy: KeyEvent { code: KeyCode::Char('y'), modifiers: KeyModifiers::empty()},
             create_branch: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::empty()},
             push: KeyEvent { code: KeyCode::Char('p'), modifiers: KeyModifiers::empty()},
+            fetch: KeyEvent { code: KeyCode::Char('f'), modifiers: KeyModifiers::empty()},
+            pull: KeyEvent { code: KeyCode::Char('P'), modifiers: KeyModifiers::SHIFT},
+            select_branch: KeyEvent { code: KeyCode::Char('B'), modifiers: KeyModifiers::SHIFT},
+            rename_branch: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty()},
+            delete_branch: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
+            delete_tag: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
+            conflict_take_ours: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::empty()},
+            conflict_take_theirs: KeyEvent { code: KeyCode::Char('t'), modifiers: KeyModifiers::empty()},
+            rebase_interactive: KeyEvent { code: KeyCode::Char('R'), modifiers: KeyModifiers::SHIFT},
+            rebase_pick: KeyEvent { code: KeyCode::Char('p'), modifiers: KeyModifiers::empty()},
+            rebase_reword: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty()},
+            rebase_squash: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
+            rebase_fixup: KeyEvent { code: KeyCode::Char('f'), modifiers: KeyModifiers::empty()},
+            rebase_drop: KeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::empty()},
+            rebase_abort: KeyEvent { code: KeyCode::Char('A'), modifiers: KeyModifiers::SHIFT},
+            blame: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::CONTROL},
+            file_history: KeyEvent { code: KeyCode::Char('H'), modifiers: KeyModifiers::SHIFT},
+            log_search: KeyEvent { code: KeyCode::Char('/'), modifiers: KeyModifiers::empty()},
+            log_search_next: KeyEvent { code: KeyCode::Char('n'), modifiers: KeyModifiers::empty()},
+            log_search_prev: KeyEvent { code: KeyCode::Char('N'), modifiers: KeyModifiers::SHIFT},
+            diff_toggle_split: KeyEvent { code: KeyCode::Char('v'), modifiers: KeyModifiers::empty()},
+            diff_toggle_whitespace: KeyEvent { code: KeyCode::Char('W'), modifiers: KeyModifiers::SHIFT},
+            diff_context_more: KeyEvent { code: KeyCode::Char(']'), modifiers: KeyModifiers::empty()},
+            diff_context_less: KeyEvent { code: KeyCode::Char('['), modifiers: KeyModifiers::empty()},
+            diff_toggle_renames: KeyEvent { code: KeyCode::Char('M'), modifiers: KeyModifiers::SHIFT},
+            log_mark_commit: KeyEvent { code: KeyCode::Char('m'), modifiers: KeyModifiers::empty()},
+            compare_commits: KeyEvent { code: KeyCode::Char('C'), modifiers: KeyModifiers::SHIFT},
+            log_compare_workdir: KeyEvent { code: KeyCode::Char('W'), modifiers: KeyModifiers::SHIFT},
+            log_cherry_pick: KeyEvent { code: KeyCode::Char('P'), modifiers: KeyModifiers::SHIFT},
+            log_revert_commit: KeyEvent { code: KeyCode::Char('V'), modifiers: KeyModifiers::SHIFT},
+            log_checkout_commit: KeyEvent { code: KeyCode::Char('O'), modifiers: KeyModifiers::SHIFT},
+            log_reset_commit: KeyEvent { code: KeyCode::Char('X'), modifiers: KeyModifiers::SHIFT},
+            log_graph_style: KeyEvent { code: KeyCode::Char('G'), modifiers: KeyModifiers::SHIFT},
+            log_open_tags: KeyEvent { code: KeyCode::Char('T'), modifiers: KeyModifiers::SHIFT},
         }
     }
 }
@@ -151,7 +336,13 @@ impl KeyConfig {
     }
 
     pub fn init() -> Self {
-        Self::init_internal().unwrap_or_default()
+        Self::init_internal().unwrap_or_else(|e| {
+            log::error!(
+                "failed to load key config, using defaults: {}",
+                e
+            );
+            Self::default()
+        })
     }
 }
 
@@ -251,4 +442,66 @@ mod tests {
             true
         );
     }
+
+    #[test]
+    fn test_load_config_without_new_keys() {
+        // key config as written by the first release supporting it
+        let config = r#"
+(
+    tab_status: ( code: Char('1'), modifiers: ( bits: 0,),),
+    tab_log: ( code: Char('2'), modifiers: ( bits: 0,),),
+    tab_stashing: ( code: Char('3'), modifiers: ( bits: 0,),),
+    tab_stashes: ( code: Char('4'), modifiers: ( bits: 0,),),
+    tab_toggle: ( code: Tab, modifiers: ( bits: 0,),),
+    tab_toggle_reverse: ( code: BackTab, modifiers: ( bits: 0,),),
+    tab_toggle_reverse_windows: ( code: BackTab, modifiers: ( bits: 1,),),
+    focus_workdir: ( code: Char('w'), modifiers: ( bits: 0,),),
+    focus_stage: ( code: Char('s'), modifiers: ( bits: 0,),),
+    focus_right: ( code: Char('l'), modifiers: ( bits: 0,),),
+    focus_left: ( code: Char('h'), modifiers: ( bits: 0,),),
+    focus_above: ( code: Char('k'), modifiers: ( bits: 0,),),
+    focus_below: ( code: Char('j'), modifiers: ( bits: 0,),),
+    exit: ( code: Char('c'), modifiers: ( bits: 2,),),
+    exit_popup: ( code: Esc, modifiers: ( bits: 0,),),
+    open_commit: ( code: Char('c'), modifiers: ( bits: 0,),),
+    open_commit_editor: ( code: Char('E'), modifiers: ( bits: 0,),),
+    open_help: ( code: F(1), modifiers: ( bits: 0,),),
+    move_left: ( code: Char('h'), modifiers: ( bits: 0,),),
+    move_right: ( code: Char('l'), modifiers: ( bits: 0,),),
+    home: ( code: Home, modifiers: ( bits: 0,),),
+    end: ( code: End, modifiers: ( bits: 0,),),
+    move_up: ( code: Char('k'), modifiers: ( bits: 0,),),
+    move_down: ( code: Char('j'), modifiers: ( bits: 0,),),
+    page_up: ( code: Char('u'), modifiers: ( bits: 2,),),
+    page_down: ( code: Char('d'), modifiers: ( bits: 2,),),
+    shift_up: ( code: Char('K'), modifiers: ( bits: 0,),),
+    shift_down: ( code: Char('J'), modifiers: ( bits: 0,),),
+    enter: ( code: Enter, modifiers: ( bits: 0,),),
+    edit_file: ( code: Char('I'), modifiers: ( bits: 0,),),
+    status_stage_all: ( code: Char('a'), modifiers: ( bits: 0,),),
+    status_reset_item: ( code: Char('U'), modifiers: ( bits: 0,),),
+    status_ignore_file: ( code: Char('i'), modifiers: ( bits: 0,),),
+    stashing_save: ( code: Char('w'), modifiers: ( bits: 0,),),
+    stashing_toggle_untracked: ( code: Char('u'), modifiers: ( bits: 0,),),
+    stashing_toggle_index: ( code: Char('m'), modifiers: ( bits: 0,),),
+    stash_open: ( code: Char('l'), modifiers: ( bits: 0,),),
+    stash_drop: ( code: Char('D'), modifiers: ( bits: 0,),),
+    cmd_bar_toggle: ( code: Char('.'), modifiers: ( bits: 0,),),
+    log_tag_commit: ( code: Char('t'), modifiers: ( bits: 0,),),
+    commit_amend: ( code: Char('A'), modifiers: ( bits: 0,),),
+    copy: ( code: Char('y'), modifiers: ( bits: 0,),),
+    create_branch: ( code: Char('b'), modifiers: ( bits: 0,),),
+    push: ( code: Char('p'), modifiers: ( bits: 0,),),
+)
+"#;
+
+        let keys: KeyConfig = ron::de::from_str(config).unwrap();
+
+        assert_eq!(keys.push.code, KeyCode::Char('p'));
+        assert_eq!(
+            keys.select_branch,
+            KeyConfig::default().select_branch
+        );
+        assert_eq!(keys.log_search, KeyConfig::default().log_search);
+    }
 }
@@ -16,6 +16,7 @@ mod components;
 mod input;
 mod keys;
 mod notify_mutex;
+mod options;
 mod profiler;
 mod queue;
 mod spinner;
@@ -23,6 +24,7 @@ mod strings;
 mod tabs;
 mod ui;
 mod version;
+mod watcher;
 
 use crate::app::App;
 use anyhow::{anyhow, Result};
@@ -32,7 +34,7 @@ use clap::{
     crate_authors, crate_description, crate_name, crate_version,
     App as ClapApp, Arg,
 };
-use crossbeam_channel::{tick, unbounded, Receiver, Select};
+use crossbeam_channel::{never, tick, unbounded, Receiver, Select};
 use crossterm::{
     terminal::{
         disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
@@ -59,14 +61,18 @@ use tui::{
     backend::{Backend, CrosstermBackend},
     Terminal,
 };
+use watcher::RepoWatcher;
 
 static TICK_INTERVAL: Duration = Duration::from_secs(5);
+/// fallback in case the watcher misses changes (e.g. on network fs)
+static TICK_INTERVAL_WATCHED: Duration = Duration::from_secs(60);
 static SPINNER_INTERVAL: Duration = Duration::from_millis(80);
 
 ///
 #[derive(Clone, Copy)]
 pub enum QueueEvent {
     Tick,
+    Notify,
     SpinnerUpdate,
     GitEvent(AsyncNotification),
     InputEvent(InputEvent),
@@ -102,7 +108,14 @@ fn main() -> Result<()> {
     let input = Input::new();
 
     let rx_input = input.receiver();
-    let ticker = tick(TICK_INTERVAL);
+    let watcher = start_watcher();
+    let rx_watcher =
+        watcher.as_ref().map_or_else(never, RepoWatcher::receiver);
+    let ticker = tick(if watcher.is_some() {
+        TICK_INTERVAL_WATCHED
+    } else {
+        TICK_INTERVAL
+    });
     let spinner_ticker = tick(SPINNER_INTERVAL);
 
     let mut app = App::new(&tx_git, input);
@@ -118,6 +131,7 @@ fn main() -> Result<()> {
             select_event(
                 &rx_input,
                 &rx_git,
+                &rx_watcher,
                 &ticker,
                 &spinner_ticker,
             )?
@@ -142,6 +156,7 @@ fn main() -> Result<()> {
                     app.event(ev)?
                 }
                 QueueEvent::Tick => app.update()?,
+                QueueEvent::Notify => app.update_changes()?,
                 QueueEvent::GitEvent(ev)
                     if ev != AsyncNotification::FinishUnchanged =>
                 {
@@ -197,9 +212,30 @@ fn valid_path() -> Result<bool> {
         && !asyncgit::sync::is_bare_repo(asyncgit::CWD)?)
 }
 
+/// `None` if watching is not possible (we keep polling then)
+fn start_watcher() -> Option<RepoWatcher> {
+    fn start() -> Result<RepoWatcher> {
+        let work_dir =
+            asyncgit::sync::utils::repo_work_dir(asyncgit::CWD)?;
+        let git_dir =
+            asyncgit::sync::utils::repo_git_dir(asyncgit::CWD)?;
+
+        RepoWatcher::new(&work_dir, &git_dir)
+    }
+
+    match start() {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            log::error!("watcher failed, polling instead: {}", e);
+            None
+        }
+    }
+}
+
 fn select_event(
     rx_input: &Receiver<InputEvent>,
     rx_git: &Receiver<AsyncNotification>,
+    rx_watcher: &Receiver<()>,
     rx_ticker: &Receiver<Instant>,
     rx_spinner: &Receiver<Instant>,
 ) -> Result<QueueEvent> {
@@ -207,6 +243,7 @@ fn select_event(
 
     sel.recv(rx_input);
     sel.recv(rx_git);
+    sel.recv(rx_watcher);
     sel.recv(rx_ticker);
     sel.recv(rx_spinner);
 
@@ -216,8 +253,9 @@ fn select_event(
     let ev = match index {
         0 => oper.recv(rx_input).map(QueueEvent::InputEvent),
         1 => oper.recv(rx_git).map(QueueEvent::GitEvent),
-        2 => oper.recv(rx_ticker).map(|_| QueueEvent::Tick),
-        3 => oper.recv(rx_spinner).map(|_| QueueEvent::SpinnerUpdate),
+        2 => oper.recv(rx_watcher).map(|_| QueueEvent::Notify),
+        3 => oper.recv(rx_ticker).map(|_| QueueEvent::Tick),
+        4 => oper.recv(rx_spinner).map(|_| QueueEvent::SpinnerUpdate),
         _ => return Err(anyhow!("unknown select source")),
     }?;
 
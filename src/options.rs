@@ -0,0 +1,136 @@
+use crate::get_app_config_path;
+use anyhow::Result;
+use asyncgit::DiffOptions;
+use ron::{
+    de::from_bytes,
+    ser::{to_string_pretty, PrettyConfig},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+    rc::Rc,
+};
+
+/// upper bound of context lines selectable in the diff view
+const MAX_CONTEXT_LINES: u32 = 100;
+
+pub type SharedOptions = Rc<RefCell<Options>>;
+
+/// how the commit graph is drawn in the log
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GraphStyle {
+    Unicode,
+    Ascii,
+    Off,
+}
+
+impl Default for GraphStyle {
+    fn default() -> Self {
+        Self::Unicode
+    }
+}
+
+/// user changeable settings that are persisted across sessions
+#[derive(Default, Serialize, Deserialize, Debug)]
+pub struct Options {
+    #[serde(with = "DiffOptionsDef")]
+    diff: DiffOptions,
+    #[serde(default)]
+    log_graph: GraphStyle,
+}
+
+impl Options {
+    pub const fn diff_options(&self) -> DiffOptions {
+        self.diff
+    }
+
+    pub fn diff_toggle_whitespace(&mut self) {
+        self.diff.ignore_whitespace = !self.diff.ignore_whitespace;
+        self.save_or_warn();
+    }
+
+    pub fn diff_toggle_renames(&mut self) {
+        self.diff.find_renames = !self.diff.find_renames;
+        self.save_or_warn();
+    }
+
+    pub fn diff_context_change(&mut self, increase: bool) {
+        self.diff.context = if increase {
+            self.diff.context.saturating_add(1).min(MAX_CONTEXT_LINES)
+        } else {
+            self.diff.context.saturating_sub(1)
+        };
+        self.save_or_warn();
+    }
+
+    pub const fn log_graph(&self) -> GraphStyle {
+        self.log_graph
+    }
+
+    pub fn log_graph_cycle(&mut self) {
+        self.log_graph = match self.log_graph {
+            GraphStyle::Unicode => GraphStyle::Ascii,
+            GraphStyle::Ascii => GraphStyle::Off,
+            GraphStyle::Off => GraphStyle::Unicode,
+        };
+        self.save_or_warn();
+    }
+
+    fn save_or_warn(&self) {
+        if self.save().is_err() {
+            log::warn!("failed to store options to disk.")
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let options_file = Self::get_options_file()?;
+        let mut file = File::create(options_file)?;
+        let data = to_string_pretty(self, PrettyConfig::default())?;
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    fn get_options_file() -> Result<PathBuf> {
+        let app_home = get_app_config_path()?;
+        Ok(app_home.join("options.ron"))
+    }
+
+    fn read_file(options_file: PathBuf) -> Result<Self> {
+        let mut f = File::open(options_file)?;
+        let mut buffer = Vec::new();
+        f.read_to_end(&mut buffer)?;
+        Ok(from_bytes(&buffer)?)
+    }
+
+    fn init_internal() -> Result<Self> {
+        let file = Self::get_options_file()?;
+        if file.exists() {
+            Ok(Self::read_file(file)?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn init() -> Self {
+        Self::init_internal().unwrap_or_else(|e| {
+            log::error!(
+                "failed to load options, using defaults: {}",
+                e
+            );
+            Self::default()
+        })
+    }
+}
+
+/// we duplicate the `DiffOptions` definition from `asyncgit` to
+/// implement Serde serialisation without making asyncgit depend on it
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "DiffOptions")]
+struct DiffOptionsDef {
+    ignore_whitespace: bool,
+    context: u32,
+    find_renames: bool,
+}
@@ -1,5 +1,5 @@
 use crate::tabs::StashingOptions;
-use asyncgit::sync::{CommitId, CommitTags};
+use asyncgit::sync::{CommitId, CommitTags, ResetType};
 use bitflags::bitflags;
 use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
@@ -12,6 +12,8 @@ bitflags! {
         const DIFF = 0b010;
         /// commands might need updating (app::update_commands)
         const COMMANDS = 0b100;
+        /// tags changed, fetch them again instead of the cached ones
+        const TAGS = 0b1000;
     }
 }
 
@@ -27,7 +29,20 @@ pub struct ResetItem {
 pub enum Action {
     Reset(ResetItem),
     ResetHunk(String, u64),
+    /// path, hash of the diff and indices of the lines to reset
+    ResetLines(String, u64, Vec<usize>),
     StashDrop(CommitId),
+    DeleteBranch(String),
+    ///
+    DeleteTag(String),
+    /// moves the current branch to the commit
+    ResetBranch(CommitId, ResetType),
+    ///
+    CherryPick(CommitId),
+    ///
+    RevertCommit(CommitId),
+    /// checks out the commit as a detached HEAD
+    CheckoutCommit(CommitId),
 }
 
 ///
@@ -42,20 +57,49 @@ pub enum InternalEvent {
     Update(NeedsUpdate),
     /// open commit msg input
     OpenCommit,
-    ///
-    PopupStashing(StashingOptions),
+    /// stash options and the files to stash (everything if empty)
+    PopupStashing(StashingOptions, Vec<String>),
     ///
     TabSwitch,
     ///
     InspectCommit(CommitId, Option<CommitTags>),
     ///
     TagCommit(CommitId),
-    ///
-    CreateBranch,
-    ///
-    OpenExternalEditor(Option<String>),
+    /// creates a signed tag (commit, name, message) once the
+    /// terminal is released for gpg
+    SignTag(CommitId, String, String),
+    /// opens the tag list popup
+    ShowTags,
+    /// push a tag to the default remote
+    PushTag(String),
+    /// opens the create branch popup (to branch off the given commit
+    /// instead of HEAD)
+    CreateBranch(Option<CommitId>),
+    /// opens the create branch popup to pop this stash onto the new
+    /// branch
+    StashBranch(CommitId),
+    /// opens the branch list popup (with the marked commit to compare
+    /// branches with)
+    SelectBranch(Option<CommitId>),
+    /// branch reference and current name
+    RenameBranch(String, String),
+    /// file to edit (commit message if `None`) and line to jump to
+    OpenExternalEditor(Option<String>, Option<u32>),
     ///
     Push(String),
+    /// branch to fetch
+    Fetch(String),
+    /// branch to fetch and merge its upstream into
+    Pull(String),
+    /// plan rebasing this commit and its descendants
+    RebaseInteractive(CommitId),
+    /// blame file at commit (HEAD if `None`)
+    BlameFile(String, Option<CommitId>),
+    /// show commits that changed the file at this path
+    FileHistory(String),
+    /// compare the first commit with the second one (working dir if
+    /// `None`)
+    CompareCommits(CommitId, Option<CommitId>),
 }
 
 ///
@@ -1,11 +1,32 @@
 use crate::keys::{get_hint, SharedKeyConfig};
+use asyncgit::sync::ResetType;
 
 pub mod order {
     pub static NAV: i8 = 1;
 }
 
 pub static PUSH_POPUP_MSG: &str = "pushing...";
+pub static FETCH_POPUP_MSG: &str = "fetching...";
+pub static PULL_POPUP_MSG: &str = "pulling...";
+pub static REPO_STATE_MERGE: &str = " merging ";
+pub static REPO_STATE_REBASE: &str = " rebasing ";
+pub static REPO_STATE_OTHER: &str = " operation in progress ";
 
+pub fn push_popup_packing(
+    _key_config: &SharedKeyConfig,
+    current: usize,
+    total: usize,
+) -> String {
+    format!("Packing: {}/{} objects", current, total)
+}
+pub fn push_popup_pushing(
+    _key_config: &SharedKeyConfig,
+    current: usize,
+    total: usize,
+    bytes: &str,
+) -> String {
+    format!("Pushing: {}/{} objects ({})", current, total, bytes)
+}
 pub fn title_status(key_config: &SharedKeyConfig) -> String {
     format!(
         "Unstaged Changes [{}]",
@@ -15,6 +36,9 @@ pub fn title_status(key_config: &SharedKeyConfig) -> String {
 pub fn title_diff(_key_config: &SharedKeyConfig) -> String {
     "Diff: ".to_string()
 }
+pub fn title_conflicts(_key_config: &SharedKeyConfig) -> String {
+    "Conflicts".to_string()
+}
 pub fn title_index(key_config: &SharedKeyConfig) -> String {
     format!("Staged Changes [{}]", get_hint(key_config.focus_stage))
 }
@@ -48,6 +72,14 @@ pub fn commit_title(_key_config: &SharedKeyConfig) -> String {
 pub fn commit_title_amend(_key_config: &SharedKeyConfig) -> String {
     "Commit (Amend)".to_string()
 }
+pub fn commit_title_subject(
+    _key_config: &SharedKeyConfig,
+    title: &str,
+    subject_len: usize,
+    max: usize,
+) -> String {
+    format!("{} ({}/{})", title, subject_len, max)
+}
 pub fn commit_msg(_key_config: &SharedKeyConfig) -> String {
     "type commit message..".to_string()
 }
@@ -84,9 +116,26 @@ pub fn confirm_msg_resethunk(
 ) -> String {
     "confirm reset hunk?".to_string()
 }
+pub fn confirm_msg_resetlines(
+    _key_config: &SharedKeyConfig,
+    lines: usize,
+) -> String {
+    format!("confirm reset {} selected lines?", lines)
+}
 pub fn log_title(_key_config: &SharedKeyConfig) -> String {
     "Commit".to_string()
 }
+pub fn log_search_matches(_key_config: &SharedKeyConfig) -> String {
+    "matches".to_string()
+}
+pub fn log_search_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Search commits".to_string()
+}
+pub fn log_search_popup_msg(_key_config: &SharedKeyConfig) -> String {
+    "message, author or sha prefix".to_string()
+}
 pub fn tag_commit_popup_title(
     _key_config: &SharedKeyConfig,
 ) -> String {
@@ -95,6 +144,20 @@ pub fn tag_commit_popup_title(
 pub fn tag_commit_popup_msg(_key_config: &SharedKeyConfig) -> String {
     "type tag".to_string()
 }
+pub fn tag_message_popup_title(
+    _key_config: &SharedKeyConfig,
+    tag: &str,
+) -> String {
+    format!("Tag Message ({})", tag)
+}
+pub fn tag_message_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "type message to annotate the tag (optional)".to_string()
+}
+pub fn tag_list_popup_title(_key_config: &SharedKeyConfig) -> String {
+    "Tags".to_string()
+}
 pub fn stashlist_title(_key_config: &SharedKeyConfig) -> String {
     "Stashes".to_string()
 }
@@ -112,6 +175,15 @@ pub fn stashing_options_title(
 pub fn loading_text(_key_config: &SharedKeyConfig) -> String {
     "Loading ...".to_string()
 }
+pub fn diff_binary(_key_config: &SharedKeyConfig) -> String {
+    "binary file".to_string()
+}
+pub fn diff_too_large(_key_config: &SharedKeyConfig) -> String {
+    "file too large to diff".to_string()
+}
+pub fn diff_submodule(_key_config: &SharedKeyConfig) -> String {
+    "submodule".to_string()
+}
 pub fn create_branch_popup_title(
     _key_config: &SharedKeyConfig,
 ) -> String {
@@ -122,6 +194,165 @@ pub fn create_branch_popup_msg(
 ) -> String {
     "type branch name".to_string()
 }
+pub fn select_branch_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Branches".to_string()
+}
+pub fn rename_branch_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Rename Branch".to_string()
+}
+pub fn rename_branch_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "new branch name".to_string()
+}
+pub fn cred_popup_username_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Username".to_string()
+}
+pub fn cred_popup_username_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "username for the remote".to_string()
+}
+pub fn cred_popup_password_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Password".to_string()
+}
+pub fn cred_popup_password_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "password for the remote".to_string()
+}
+pub fn rebase_popup_title(_key_config: &SharedKeyConfig) -> String {
+    "Rebase (interactive)".to_string()
+}
+pub fn rebase_popup_paused_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Rebase paused: resolve conflicts in the status tab".to_string()
+}
+pub fn rebase_reword_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Reword".to_string()
+}
+pub fn rebase_reword_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "new commit message".to_string()
+}
+pub fn blame_title(_key_config: &SharedKeyConfig) -> String {
+    "Blame".to_string()
+}
+pub fn file_history_title(
+    _key_config: &SharedKeyConfig,
+    path: &str,
+) -> String {
+    format!("History: {}", path)
+}
+pub fn compare_title(
+    _key_config: &SharedKeyConfig,
+    from: &str,
+    to: Option<&str>,
+) -> String {
+    format!("Compare: {}..{}", from, to.unwrap_or("workdir"))
+}
+pub fn blame_failed_text(
+    _key_config: &SharedKeyConfig,
+    error: &str,
+) -> String {
+    format!("file cannot be blamed: {}", error)
+}
+pub fn confirm_title_delete_branch(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Delete Branch".to_string()
+}
+pub fn confirm_msg_delete_branch(
+    _key_config: &SharedKeyConfig,
+    branch_ref: &str,
+) -> String {
+    format!("confirm deleting branch: '{}' ?", branch_ref)
+}
+pub fn confirm_title_delete_tag(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Delete Tag".to_string()
+}
+pub fn confirm_msg_delete_tag(
+    _key_config: &SharedKeyConfig,
+    tag: &str,
+) -> String {
+    format!("confirm deleting tag: '{}' ?", tag)
+}
+pub fn confirm_title_reset_branch(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Reset Branch".to_string()
+}
+pub fn confirm_msg_reset_branch(
+    _key_config: &SharedKeyConfig,
+    commit: &str,
+    reset_type: ResetType,
+) -> String {
+    let (mode, effect) = match reset_type {
+        ResetType::Soft => {
+            ("soft", "moves the branch, keeps index and working dir")
+        }
+        ResetType::Mixed => (
+            "mixed",
+            "moves the branch and unstages all changes, keeps working dir",
+        ),
+        ResetType::Hard => (
+            "HARD",
+            "all uncommitted changes in index and working dir will be LOST!",
+        ),
+    };
+
+    format!("{} reset to {}:\n{}", mode, commit, effect)
+}
+pub fn confirm_title_cherry_pick(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Cherry-pick".to_string()
+}
+pub fn confirm_msg_cherry_pick(
+    _key_config: &SharedKeyConfig,
+    commit: &str,
+) -> String {
+    format!("apply the changes of {} onto HEAD?", commit)
+}
+pub fn confirm_title_revert_commit(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Revert Commit".to_string()
+}
+pub fn confirm_msg_revert_commit(
+    _key_config: &SharedKeyConfig,
+    commit: &str,
+) -> String {
+    format!("create a commit undoing the changes of {}?", commit)
+}
+pub fn confirm_title_checkout_commit(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Checkout Commit".to_string()
+}
+pub fn confirm_msg_checkout_commit(
+    _key_config: &SharedKeyConfig,
+    commit: &str,
+) -> String {
+    format!(
+        "checkout {}?\nHEAD will be detached from the current branch",
+        commit
+    )
+}
 
 pub mod commit {
     use crate::keys::SharedKeyConfig;
@@ -261,6 +492,53 @@ pub mod commands {
             CMD_GROUP_DIFF,
         )
     }
+    pub fn diff_toggle_split(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Side-by-side [{}]",
+                get_hint(key_config.diff_toggle_split),
+            ),
+            "toggle between unified and side-by-side diff",
+            CMD_GROUP_DIFF,
+        )
+    }
+    pub fn diff_toggle_whitespace(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Whitespace [{}]",
+                get_hint(key_config.diff_toggle_whitespace),
+            ),
+            "toggle ignoring changes in amount of whitespace (-b)",
+            CMD_GROUP_DIFF,
+        )
+    }
+    pub fn diff_context(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Context [{}{}]",
+                get_hint(key_config.diff_context_less),
+                get_hint(key_config.diff_context_more),
+            ),
+            "show less/more unchanged lines around changes",
+            CMD_GROUP_DIFF,
+        )
+    }
+    pub fn diff_toggle_renames(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Renames [{}]",
+                get_hint(key_config.diff_toggle_renames),
+            ),
+            "toggle detection of renamed and copied files in commits",
+            CMD_GROUP_DIFF,
+        )
+    }
     pub fn diff_hunk_add(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
@@ -291,6 +569,36 @@ pub mod commands {
             CMD_GROUP_DIFF,
         )
     }
+    pub fn diff_lines_add(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Add lines [{}]", get_hint(key_config.enter),),
+            "adds selected lines to stage",
+            CMD_GROUP_DIFF,
+        )
+    }
+    pub fn diff_lines_revert(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Revert lines [{}]",
+                get_hint(key_config.status_reset_item),
+            ),
+            "reverts selected lines",
+            CMD_GROUP_DIFF,
+        )
+    }
+    pub fn diff_lines_remove(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Remove lines [{}]", get_hint(key_config.enter),),
+            "removes selected lines from stage",
+            CMD_GROUP_DIFF,
+        )
+    }
     pub fn close_popup(key_config: &SharedKeyConfig) -> CommandText {
         CommandText::new(
             format!("Close [{}]", get_hint(key_config.exit_popup),),
@@ -306,6 +614,13 @@ pub mod commands {
         )
         .hide_help()
     }
+    pub fn validate_msg(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!("Validate [{}]", get_hint(key_config.enter),),
+            "validate msg",
+            CMD_GROUP_GENERAL,
+        )
+    }
     pub fn select_staging(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
@@ -376,6 +691,43 @@ pub mod commands {
             CMD_GROUP_COMMIT,
         )
     }
+    pub fn commit_history(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "History [{}{}]",
+                get_hint(key_config.commit_history_prev),
+                get_hint(key_config.commit_history_next),
+            ),
+            "recall previous/next of the recent commit messages",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn commit_complete_type(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Type [{}]",
+                get_hint(key_config.commit_complete_type),
+            ),
+            "complete (or cycle) the conventional commit type of the subject",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn commit_wrap_body(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Wrap [{}]",
+                get_hint(key_config.commit_wrap_body),
+            ),
+            "wrap the long lines of the body at 72 columns",
+            CMD_GROUP_COMMIT,
+        )
+    }
     pub fn edit_item(key_config: &SharedKeyConfig) -> CommandText {
         CommandText::new(
             format!("Edit Item [{}]", get_hint(key_config.edit_file),),
@@ -400,6 +752,39 @@ pub mod commands {
             CMD_GROUP_CHANGES,
         )
     }
+    pub fn conflict_take_ours(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Take Ours [{}]",
+                get_hint(key_config.conflict_take_ours),
+            ),
+            "replace conflicted file with the version of the current branch",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn conflict_take_theirs(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Take Theirs [{}]",
+                get_hint(key_config.conflict_take_theirs),
+            ),
+            "replace conflicted file with the version that was merged in",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn conflict_resolve(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Mark Resolved [{}]", get_hint(key_config.enter),),
+            "stage the current workdir version of the conflicted file",
+            CMD_GROUP_CHANGES,
+        )
+    }
     pub fn unstage_item(key_config: &SharedKeyConfig) -> CommandText {
         CommandText::new(
             format!("Unstage Item [{}]", get_hint(key_config.enter),),
@@ -472,6 +857,43 @@ pub mod commands {
             CMD_GROUP_GENERAL,
         )
     }
+    pub fn reset_branch_type(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Soft/Mixed/Hard [{}{}]",
+                get_hint(key_config.focus_left),
+                get_hint(key_config.focus_right),
+            ),
+            "change how far the branch is reset",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn log_graph_style(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Graph [{}]",
+                get_hint(key_config.log_graph_style),
+            ),
+            "switch commit graph between unicode, ascii and off",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_reset_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Reset [{}]",
+                get_hint(key_config.log_reset_commit),
+            ),
+            "reset current branch to selected commit",
+            CMD_GROUP_LOG,
+        )
+    }
     pub fn stashing_save(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
@@ -493,6 +915,18 @@ pub mod commands {
             CMD_GROUP_STASHING,
         )
     }
+    pub fn stashing_mark_file(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Mark [{}]",
+                get_hint(key_config.stashing_mark_file),
+            ),
+            "mark (or unmark) file or folder to stash only marked files",
+            CMD_GROUP_STASHING,
+        )
+    }
     pub fn stashing_toggle_untracked(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
@@ -532,6 +966,24 @@ pub mod commands {
             CMD_GROUP_STASHES,
         )
     }
+    pub fn stashlist_pop(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Pop [{}]", get_hint(key_config.stash_pop),),
+            "apply selected stash and drop it",
+            CMD_GROUP_STASHES,
+        )
+    }
+    pub fn stashlist_branch(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Branch [{}]", get_hint(key_config.stash_branch),),
+            "pop selected stash onto a new branch based on its commit",
+            CMD_GROUP_STASHES,
+        )
+    }
     pub fn stashlist_inspect(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
@@ -568,6 +1020,87 @@ pub mod commands {
             CMD_GROUP_LOG,
         )
     }
+    pub fn log_open_tags(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Tags [{}]", get_hint(key_config.log_open_tags),),
+            "open tag list (delete and push tags)",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_mark_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Mark [{}]",
+                get_hint(key_config.log_mark_commit),
+            ),
+            "mark (or unmark) commit to compare other commits with",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_compare_commits(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Compare [{}]",
+                get_hint(key_config.compare_commits),
+            ),
+            "compare marked commit with selected commit",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_compare_workdir(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Compare workdir [{}]",
+                get_hint(key_config.log_compare_workdir),
+            ),
+            "compare marked (or selected) commit with working dir",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_cherry_pick(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Cherry-pick [{}]",
+                get_hint(key_config.log_cherry_pick),
+            ),
+            "apply changes of selected commit onto HEAD",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_revert_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Revert [{}]",
+                get_hint(key_config.log_revert_commit),
+            ),
+            "commit the inverse of selected commit onto HEAD",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_checkout_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Checkout [{}]",
+                get_hint(key_config.log_checkout_commit),
+            ),
+            "checkout selected commit (detached HEAD)",
+            CMD_GROUP_LOG,
+        )
+    }
     pub fn tag_commit_confirm_msg(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
@@ -605,4 +1138,259 @@ pub mod commands {
             CMD_GROUP_GENERAL,
         )
     }
+    pub fn status_fetch(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!("Fetch [{}]", get_hint(key_config.fetch),),
+            "fetch current branch from its remote",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn status_pull(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!("Pull [{}]", get_hint(key_config.pull),),
+            "fetch and merge upstream into current branch",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn open_branch_select_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Branches [{}]",
+                get_hint(key_config.select_branch),
+            ),
+            "open select branch popup",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn navigate_branch_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Nav [{}{}]",
+                get_hint(key_config.move_up),
+                get_hint(key_config.move_down)
+            ),
+            "navigate branch list",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn select_branch_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Checkout [{}]", get_hint(key_config.enter),),
+            "checkout selected branch",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn rename_branch_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Rename Branch [{}]",
+                get_hint(key_config.rename_branch),
+            ),
+            "rename selected branch",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn delete_branch_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Delete [{}]",
+                get_hint(key_config.delete_branch),
+            ),
+            "delete selected branch",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn navigate_tag_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Nav [{}{}]",
+                get_hint(key_config.move_up),
+                get_hint(key_config.move_down)
+            ),
+            "navigate tag list",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn delete_tag_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Delete [{}]", get_hint(key_config.delete_tag),),
+            "delete selected tag",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn push_tag_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Push [{}]", get_hint(key_config.push),),
+            "push selected tag to the remote",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn compare_branch_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Compare [{}]",
+                get_hint(key_config.compare_commits),
+            ),
+            "compare marked commit with selected branch",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn rename_branch_confirm_msg(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Rename Branch [{}]", get_hint(key_config.enter),),
+            "rename branch",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn log_rebase_interactive(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Rebase [{}]",
+                get_hint(key_config.rebase_interactive),
+            ),
+            "interactively rebase selected commit and its descendants",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn rebase_set_action(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Pick/Reword/Squash/Fixup/Drop [{}{}{}{}{}]",
+                get_hint(key_config.rebase_pick),
+                get_hint(key_config.rebase_reword),
+                get_hint(key_config.rebase_squash),
+                get_hint(key_config.rebase_fixup),
+                get_hint(key_config.rebase_drop),
+            ),
+            "set what happens with the selected commit",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn rebase_move_step(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Move [{}{}]",
+                get_hint(key_config.shift_up),
+                get_hint(key_config.shift_down)
+            ),
+            "move selected commit up/down in the plan",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn rebase_start(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!("Start [{}]", get_hint(key_config.enter),),
+            "apply the rebase plan",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn rebase_continue(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Continue [{}]", get_hint(key_config.enter),),
+            "continue rebase once all conflicts are resolved",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn rebase_abort(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!("Abort [{}]", get_hint(key_config.rebase_abort),),
+            "abort rebase and restore the branch",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn blame_file(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!("Blame [{}]", get_hint(key_config.blame),),
+            "show which commit last changed each line of the file",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn blame_inspect_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Inspect [{}]", get_hint(key_config.enter),),
+            "inspect commit that last changed the selected line",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn file_history(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "History [{}]",
+                get_hint(key_config.file_history),
+            ),
+            "show the commits that changed the file",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn file_history_inspect_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!("Inspect [{}]", get_hint(key_config.enter),),
+            "inspect selected commit in detail",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_search(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!("Search [{}]", get_hint(key_config.log_search),),
+            "search commits by message, author or sha prefix",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_search_jump(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Next/Prev match [{}{}]",
+                get_hint(key_config.log_search_next),
+                get_hint(key_config.log_search_prev),
+            ),
+            "jump to next/previous search match",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_search_clear(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Clear search [{}]",
+                get_hint(key_config.exit_popup),
+            ),
+            "stop highlighting search matches",
+            CMD_GROUP_LOG,
+        )
+    }
 }
@@ -2,18 +2,20 @@ use crate::{
     components::{
         visibility_blocking, CommandBlocking, CommandInfo,
         CommitDetailsComponent, CommitList, Component,
-        DrawableComponent,
+        DrawableComponent, TextInputComponent,
     },
     keys::SharedKeyConfig,
-    queue::{InternalEvent, Queue},
+    options::{GraphStyle, SharedOptions},
+    queue::{Action, InternalEvent, Queue},
     strings,
     ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
     cached,
-    sync::{self, CommitId},
-    AsyncLog, AsyncNotification, AsyncTags, FetchStatus, CWD,
+    sync::{self, CommitId, CommitInfo, LogSearch, ResetType},
+    AsyncLog, AsyncLogSearch, AsyncNotification, AsyncTags,
+    FetchStatus, CWD,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
@@ -33,10 +35,17 @@ pub struct Revlog {
     list: CommitList,
     git_log: AsyncLog,
     git_tags: AsyncTags,
+    /// tags changed since they were fetched last
+    tags_outdated: bool,
+    git_search: AsyncLogSearch,
+    search: Option<LogSearch>,
+    search_matches: Vec<usize>,
+    search_input: TextInputComponent,
     queue: Queue,
     visible: bool,
     branch_name: cached::BranchName,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
 }
 
 impl Revlog {
@@ -46,7 +55,15 @@ impl Revlog {
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
+        let mut list = CommitList::new(
+            &strings::log_title(&key_config),
+            theme.clone(),
+            key_config.clone(),
+        );
+        list.set_graph_style(options.borrow().log_graph());
+
         Self {
             queue: queue.clone(),
             commit_details: CommitDetailsComponent::new(
@@ -54,24 +71,38 @@ impl Revlog {
                 sender,
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
-            list: CommitList::new(
-                &strings::log_title(&key_config),
+            list,
+            git_log: AsyncLog::new(sender),
+            git_tags: AsyncTags::new(sender),
+            tags_outdated: false,
+            git_search: AsyncLogSearch::new(sender),
+            search: None,
+            search_matches: Vec::new(),
+            search_input: TextInputComponent::new(
                 theme,
                 key_config.clone(),
+                &strings::log_search_popup_title(&key_config),
+                &strings::log_search_popup_msg(&key_config),
             ),
-            git_log: AsyncLog::new(sender),
-            git_tags: AsyncTags::new(sender),
             visible: false,
             branch_name: cached::BranchName::new(CWD),
             key_config,
+            options,
         }
     }
 
+    /// makes the next update fetch the tags regardless of their age
+    pub fn invalidate_tags(&mut self) {
+        self.tags_outdated = true;
+    }
+
     ///
     pub fn any_work_pending(&self) -> bool {
         self.git_log.is_pending()
             || self.git_tags.is_pending()
+            || self.git_search.is_pending()
             || self.commit_details.any_work_pending()
     }
 
@@ -81,6 +112,15 @@ impl Revlog {
             let log_changed =
                 self.git_log.fetch()? == FetchStatus::Started;
 
+            // match positions and graph refer to the previous log
+            if log_changed {
+                self.list.graph().clear();
+
+                if let Some(search) = self.search.clone() {
+                    self.git_search.fetch(search)?;
+                }
+            }
+
             self.list.set_count_total(self.git_log.count()?);
 
             let selection = self.list.selection();
@@ -91,7 +131,11 @@ impl Revlog {
                 self.fetch_commits()?;
             }
 
-            self.git_tags.request(Duration::from_secs(3), false)?;
+            self.git_tags.request(
+                Duration::from_secs(3),
+                self.tags_outdated,
+            )?;
+            self.tags_outdated = false;
 
             self.list.set_branch(
                 self.branch_name.lookup().map(Some).unwrap_or(None),
@@ -117,6 +161,9 @@ impl Revlog {
             match ev {
                 AsyncNotification::CommitFiles
                 | AsyncNotification::Log => self.update()?,
+                AsyncNotification::LogSearch => {
+                    self.update_search_matches()?
+                }
                 AsyncNotification::Tags => {
                     if let Some(tags) = self.git_tags.last()? {
                         self.list.set_tags(tags);
@@ -141,12 +188,179 @@ impl Revlog {
         );
 
         if let Ok(commits) = commits {
+            self.update_graph(want_min, &commits)?;
             self.list.items().set_items(want_min, commits);
         }
 
         Ok(())
     }
 
+    /// extends the graph (which is built from the top) to cover the
+    /// slice of `commits` starting at `start`
+    fn update_graph(
+        &mut self,
+        start: usize,
+        commits: &[CommitInfo],
+    ) -> Result<()> {
+        if self.options.borrow().log_graph() == GraphStyle::Off {
+            return Ok(());
+        }
+
+        let graph_len = self.list.graph().len();
+        if graph_len < start {
+            let gap = sync::get_commits_info(
+                CWD,
+                &self
+                    .git_log
+                    .get_slice(graph_len, start - graph_len)?,
+                0,
+            )?;
+
+            for c in &gap {
+                self.list.graph().push(c.id, &c.parents);
+            }
+        }
+
+        let graph_len = self.list.graph().len();
+        for c in commits.iter().skip(graph_len.saturating_sub(start))
+        {
+            self.list.graph().push(c.id, &c.parents);
+        }
+
+        Ok(())
+    }
+
+    fn toggle_graph(&mut self) -> Result<()> {
+        self.options.borrow_mut().log_graph_cycle();
+        self.list.set_graph_style(self.options.borrow().log_graph());
+        self.fetch_commits()
+    }
+
+    fn start_search(&mut self) -> Result<()> {
+        let search = LogSearch::new(self.search_input.get_text());
+        self.search_input.hide();
+
+        if search.is_empty() {
+            return self.clear_search();
+        }
+
+        self.search = Some(search.clone());
+        self.git_search.fetch(search)?;
+        self.update_search_matches()
+    }
+
+    fn clear_search(&mut self) -> Result<()> {
+        self.search = None;
+        self.git_search.clear()?;
+        self.update_search_matches()
+    }
+
+    fn update_search_matches(&mut self) -> Result<()> {
+        if self.search.is_some() {
+            let matches = self.git_search.matches()?;
+
+            self.search_matches =
+                matches.iter().map(|(pos, _)| *pos).collect();
+            self.list.set_highlights(Some(
+                matches.into_iter().map(|(_, id)| id).collect(),
+            ));
+        } else {
+            self.search_matches.clear();
+            self.list.set_highlights(None);
+        }
+
+        Ok(())
+    }
+
+    fn jump_to_match(&mut self, forward: bool) -> Result<()> {
+        let selection = self.list.selection();
+
+        let target = if forward {
+            self.search_matches
+                .iter()
+                .find(|pos| **pos > selection)
+                .or_else(|| self.search_matches.first())
+        } else {
+            self.search_matches
+                .iter()
+                .rev()
+                .find(|pos| **pos < selection)
+                .or_else(|| self.search_matches.last())
+        };
+
+        if let Some(target) = target.copied() {
+            self.list.select_entry(target);
+            self.update()?;
+        }
+
+        Ok(())
+    }
+
+    fn toggle_mark(&mut self) -> bool {
+        self.selected_commit().map_or(false, |id| {
+            let marked = if self.list.marked() == Some(id) {
+                None
+            } else {
+                Some(id)
+            };
+            self.list.set_marked(marked);
+            true
+        })
+    }
+
+    fn compare_with_marked(&self) -> bool {
+        match (self.list.marked(), self.selected_commit()) {
+            (Some(marked), Some(selected)) if marked != selected => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::CompareCommits(
+                        marked,
+                        Some(selected),
+                    ),
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn compare_with_workdir(&self) -> bool {
+        self.list
+            .marked()
+            .or_else(|| self.selected_commit())
+            .map_or(false, |id| {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::CompareCommits(id, None),
+                );
+                true
+            })
+    }
+
+    /// runs `action` on the selected commit and reports errors (like
+    /// conflicts) in the error popup
+    fn confirm_selected(
+        &self,
+        action: fn(CommitId) -> Action,
+    ) -> bool {
+        self.selected_commit().map_or(false, |id| {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::ConfirmAction(action(id)));
+            true
+        })
+    }
+
+    fn reset_to_selected(&self) -> bool {
+        self.selected_commit().map_or(false, |id| {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ConfirmAction(Action::ResetBranch(
+                    id,
+                    ResetType::Mixed,
+                )),
+            );
+            true
+        })
+    }
+
     fn selected_commit(&self) -> Option<CommitId> {
         self.list.selected_entry().map(|e| e.id)
     }
@@ -187,6 +401,8 @@ impl DrawableComponent for Revlog {
             self.list.draw(f, area)?;
         }
 
+        self.search_input.draw(f, area)?;
+
         Ok(())
     }
 }
@@ -194,6 +410,20 @@ impl DrawableComponent for Revlog {
 impl Component for Revlog {
     fn event(&mut self, ev: Event) -> Result<bool> {
         if self.visible {
+            if self.search_input.is_visible() {
+                if self.search_input.event(ev)? {
+                    return Ok(true);
+                }
+
+                if let Event::Key(e) = ev {
+                    if e == self.key_config.enter {
+                        self.start_search()?;
+                    }
+                }
+
+                return Ok(true);
+            }
+
             let event_used = self.list.event(ev)?;
 
             if event_used {
@@ -214,6 +444,11 @@ impl Component for Revlog {
                             Ok(true)
                         },
                     );
+                } else if k == self.key_config.log_open_tags {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::ShowTags);
+                    return Ok(true);
                 } else if k == self.key_config.focus_right
                     && self.commit_details.is_visible()
                 {
@@ -231,10 +466,75 @@ impl Component for Revlog {
                             Ok(true)
                         },
                     );
+                } else if k == self.key_config.rebase_interactive {
+                    return self.selected_commit().map_or(
+                        Ok(false),
+                        |id| {
+                            self.queue.borrow_mut().push_back(
+                                InternalEvent::RebaseInteractive(id),
+                            );
+                            Ok(true)
+                        },
+                    );
+                } else if k == self.key_config.log_search {
+                    self.search_input.show()?;
+                    return Ok(true);
+                } else if k == self.key_config.log_search_next
+                    && self.search.is_some()
+                {
+                    self.jump_to_match(true)?;
+                    return Ok(true);
+                } else if k == self.key_config.log_search_prev
+                    && self.search.is_some()
+                {
+                    self.jump_to_match(false)?;
+                    return Ok(true);
+                } else if k == self.key_config.exit_popup
+                    && self.search.is_some()
+                {
+                    self.clear_search()?;
+                    return Ok(true);
+                } else if k == self.key_config.exit_popup
+                    && self.list.marked().is_some()
+                {
+                    self.list.set_marked(None);
+                    return Ok(true);
+                } else if k == self.key_config.log_mark_commit {
+                    return Ok(self.toggle_mark());
+                } else if k == self.key_config.compare_commits {
+                    return Ok(self.compare_with_marked());
+                } else if k == self.key_config.log_compare_workdir {
+                    return Ok(self.compare_with_workdir());
                 } else if k == self.key_config.create_branch {
-                    self.queue
-                        .borrow_mut()
-                        .push_back(InternalEvent::CreateBranch);
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::CreateBranch(
+                            self.selected_commit(),
+                        ),
+                    );
+                    return Ok(true);
+                } else if k == self.key_config.log_cherry_pick {
+                    return Ok(
+                        self.confirm_selected(Action::CherryPick)
+                    );
+                } else if k == self.key_config.log_revert_commit {
+                    return Ok(
+                        self.confirm_selected(Action::RevertCommit)
+                    );
+                } else if k == self.key_config.log_checkout_commit {
+                    return Ok(
+                        self.confirm_selected(Action::CheckoutCommit)
+                    );
+                } else if k == self.key_config.log_graph_style {
+                    self.toggle_graph()?;
+                    return Ok(true);
+                } else if k == self.key_config.log_reset_commit {
+                    return Ok(self.reset_to_selected());
+                } else if k == self.key_config.select_branch {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::SelectBranch(
+                            self.list.marked(),
+                        ),
+                    );
                     return Ok(true);
                 }
             }
@@ -248,10 +548,40 @@ impl Component for Revlog {
         out: &mut Vec<CommandInfo>,
         force_all: bool,
     ) -> CommandBlocking {
+        if self.search_input.is_visible() {
+            self.search_input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::log_search(&self.key_config),
+                true,
+                true,
+            ));
+
+            return visibility_blocking(self);
+        }
+
         if self.visible || force_all {
             self.list.commands(out, force_all);
         }
 
+        out.push(CommandInfo::new(
+            strings::commands::log_search(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_search_jump(&self.key_config),
+            !self.search_matches.is_empty(),
+            (self.visible && self.search.is_some()) || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_search_clear(&self.key_config),
+            true,
+            (self.visible && self.search.is_some()) || force_all,
+        ));
+
         out.push(CommandInfo::new(
             strings::commands::log_details_toggle(&self.key_config),
             true,
@@ -265,12 +595,75 @@ impl Component for Revlog {
                 || force_all,
         ));
 
+        out.push(CommandInfo::new(
+            strings::commands::log_mark_commit(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_compare_commits(&self.key_config),
+            self.list.marked().is_some()
+                && self.list.marked() != self.selected_commit(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_compare_workdir(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
         out.push(CommandInfo::new(
             strings::commands::log_tag_commit(&self.key_config),
             true,
             self.visible || force_all,
         ));
 
+        out.push(CommandInfo::new(
+            strings::commands::log_open_tags(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_cherry_pick(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_revert_commit(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_checkout_commit(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_graph_style(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_reset_commit(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_rebase_interactive(
+                &self.key_config,
+            ),
+            true,
+            self.visible || force_all,
+        ));
+
         out.push(CommandInfo::new(
             strings::commands::open_branch_create_popup(
                 &self.key_config,
@@ -279,6 +672,14 @@ impl Component for Revlog {
             self.visible || force_all,
         ));
 
+        out.push(CommandInfo::new(
+            strings::commands::open_branch_select_popup(
+                &self.key_config,
+            ),
+            true,
+            self.visible || force_all,
+        ));
+
         visibility_blocking(self)
     }
 
@@ -288,6 +689,7 @@ impl Component for Revlog {
 
     fn hide(&mut self) {
         self.visible = false;
+        self.search_input.hide();
         self.git_log.set_background();
     }
 
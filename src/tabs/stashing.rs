@@ -49,14 +49,17 @@ impl Stashing {
         theme: SharedTheme,
         key_config: SharedKeyConfig,
     ) -> Self {
+        let mut index = FileTreeComponent::new(
+            &strings::stashing_files_title(&key_config),
+            true,
+            Some(queue.clone()),
+            theme.clone(),
+            key_config.clone(),
+        );
+        index.enable_marking();
+
         Self {
-            index: FileTreeComponent::new(
-                &strings::stashing_files_title(&key_config),
-                true,
-                Some(queue.clone()),
-                theme.clone(),
-                key_config.clone(),
-            ),
+            index,
             visible: false,
             options: StashingOptions {
                 keep_index: false,
@@ -186,6 +189,13 @@ impl Component for Stashing {
                 self.visible && !self.index.is_empty(),
                 self.visible || force_all,
             ));
+            out.push(CommandInfo::new(
+                strings::commands::stashing_mark_file(
+                    &self.key_config,
+                ),
+                self.visible && !self.index.is_empty(),
+                self.visible || force_all,
+            ));
             out.push(CommandInfo::new(
                 strings::commands::stashing_toggle_indexed(
                     &self.key_config,
@@ -216,9 +226,15 @@ impl Component for Stashing {
                     && !self.index.is_empty()
                 {
                     self.queue.borrow_mut().push_back(
-                        InternalEvent::PopupStashing(self.options),
+                        InternalEvent::PopupStashing(
+                            self.options,
+                            self.index.marked(),
+                        ),
                     );
 
+                    Ok(true)
+                } else if k == self.key_config.stashing_mark_file {
+                    self.index.toggle_mark();
                     Ok(true)
                 } else if k == self.key_config.stashing_toggle_index {
                     self.options.keep_index =
@@ -44,9 +44,19 @@ impl StashList {
     ///
     pub fn update(&mut self) -> Result<()> {
         if self.visible {
-            let stashes = sync::get_stashes(CWD)?;
-            let commits =
-                sync::get_commits_info(CWD, stashes.as_slice(), 100)?;
+            let stashes = sync::get_stashes_info(CWD)?;
+            let ids: Vec<CommitId> =
+                stashes.iter().map(|stash| stash.id).collect();
+            let mut commits =
+                sync::get_commits_info(CWD, ids.as_slice(), 100)?;
+
+            // show the stash names like `git stash list` does
+            for (commit, stash) in commits.iter_mut().zip(stashes) {
+                commit.message = format!(
+                    "stash@{{{}}}: {}",
+                    stash.index, stash.message
+                );
+            }
 
             self.list.set_count_total(commits.len());
             self.list.items().set_items(0, commits);
@@ -75,6 +85,34 @@ impl StashList {
         }
     }
 
+    fn pop_stash(&mut self) {
+        if let Some(e) = self.list.selected_entry() {
+            match sync::stash_pop(CWD, e.id) {
+                Ok(_) => {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::TabSwitch);
+                }
+                Err(e) => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "stash pop error:\n{}",
+                            e,
+                        )),
+                    );
+                }
+            }
+        }
+    }
+
+    fn branch_stash(&mut self) {
+        if let Some(e) = self.list.selected_entry() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::StashBranch(e.id));
+        }
+    }
+
     fn drop_stash(&mut self) {
         if let Some(e) = self.list.selected_entry() {
             self.queue.borrow_mut().push_back(
@@ -125,6 +163,16 @@ impl Component for StashList {
                 selection_valid,
                 true,
             ));
+            out.push(CommandInfo::new(
+                strings::commands::stashlist_pop(&self.key_config),
+                selection_valid,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::stashlist_branch(&self.key_config),
+                selection_valid,
+                true,
+            ));
             out.push(CommandInfo::new(
                 strings::commands::stashlist_drop(&self.key_config),
                 selection_valid,
@@ -151,6 +199,10 @@ impl Component for StashList {
             if let Event::Key(k) = ev {
                 if k == self.key_config.enter {
                     self.apply_stash()
+                } else if k == self.key_config.stash_pop {
+                    self.pop_stash()
+                } else if k == self.key_config.stash_branch {
+                    self.branch_stash()
                 } else if k == self.key_config.stash_drop {
                     self.drop_stash()
                 } else if k == self.key_config.stash_open {
@@ -3,22 +3,30 @@ use crate::{
     components::{
         command_pump, event_pump, visibility_blocking,
         ChangesComponent, CommandBlocking, CommandInfo, Component,
-        DiffComponent, DrawableComponent, FileTreeItemKind,
+        ConflictsComponent, DiffComponent, DrawableComponent,
+        FileTreeItemKind,
     },
     keys::SharedKeyConfig,
+    options::SharedOptions,
     queue::{InternalEvent, Queue, ResetItem},
     strings::{self, order},
     ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{self, status::StatusType},
+    sync::{self, status::StatusType, RepoState},
     AsyncDiff, AsyncNotification, AsyncStatus, DiffParams, DiffType,
-    StatusParams, CWD,
+    StatusItem, StatusItemType, StatusParams, CWD,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
-use tui::layout::{Constraint, Direction, Layout};
+use std::{borrow::Cow, cmp, convert::TryFrom};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Paragraph, Text},
+    Frame,
+};
 
 ///
 #[derive(PartialEq)]
@@ -26,6 +34,7 @@ enum Focus {
     WorkDir,
     Diff,
     Stage,
+    Conflicts,
 }
 
 ///
@@ -33,6 +42,7 @@ enum Focus {
 enum DiffTarget {
     Stage,
     WorkingDir,
+    Conflicts,
 }
 
 pub struct Status {
@@ -41,13 +51,17 @@ pub struct Status {
     diff_target: DiffTarget,
     index: ChangesComponent,
     index_wd: ChangesComponent,
+    conflicts: ConflictsComponent,
     diff: DiffComponent,
     git_diff: AsyncDiff,
     git_status_workdir: AsyncStatus,
     git_status_stage: AsyncStatus,
     queue: Queue,
     git_action_executed: bool,
+    repo_state: RepoState,
+    theme: SharedTheme,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
 }
 
 impl DrawableComponent for Status {
@@ -74,10 +88,29 @@ impl DrawableComponent for Status {
             )
             .split(rect);
 
+        let left = if self.conflicts.is_empty() {
+            chunks[0]
+        } else {
+            let conflict_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Percentage(30),
+                        Constraint::Percentage(70),
+                    ]
+                    .as_ref(),
+                )
+                .split(chunks[0]);
+
+            self.conflicts.draw(f, conflict_chunks[0])?;
+
+            conflict_chunks[1]
+        };
+
         let left_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
-                if self.diff_target == DiffTarget::WorkingDir {
+                if self.diff_target != DiffTarget::Stage {
                     [
                         Constraint::Percentage(60),
                         Constraint::Percentage(40),
@@ -90,9 +123,10 @@ impl DrawableComponent for Status {
                 }
                 .as_ref(),
             )
-            .split(chunks[0]);
+            .split(left);
 
         self.index_wd.draw(f, left_chunks[0])?;
+        self.draw_repo_state(f, left_chunks[0]);
         self.index.draw(f, left_chunks[1])?;
         self.diff.draw(f, chunks[1])?;
 
@@ -101,7 +135,7 @@ impl DrawableComponent for Status {
 }
 
 impl Status {
-    accessors!(self, [index, index_wd, diff]);
+    accessors!(self, [index, index_wd, conflicts, diff]);
 
     ///
     pub fn new(
@@ -109,6 +143,7 @@ impl Status {
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
             queue: queue.clone(),
@@ -131,24 +166,58 @@ impl Status {
                 theme.clone(),
                 key_config.clone(),
             ),
+            conflicts: ConflictsComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
             diff: DiffComponent::new(
                 queue.clone(),
-                theme,
+                theme.clone(),
                 key_config.clone(),
+                options.clone(),
+                sender,
                 false,
             ),
             git_diff: AsyncDiff::new(sender),
             git_status_workdir: AsyncStatus::new(sender.clone()),
             git_status_stage: AsyncStatus::new(sender.clone()),
             git_action_executed: false,
+            repo_state: RepoState::Clean,
+            theme,
             key_config,
+            options,
         }
     }
 
+    /// merges and rebases in progress (of gitui or other tools) are
+    /// named on the bottom border of the workdir list
+    fn draw_repo_state<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
+        let txt = match self.repo_state {
+            RepoState::Clean => return,
+            RepoState::Merge => strings::REPO_STATE_MERGE,
+            RepoState::Rebase => strings::REPO_STATE_REBASE,
+            RepoState::Other => strings::REPO_STATE_OTHER,
+        };
+
+        let width = u16::try_from(txt.len()).unwrap_or_default();
+        let area = Rect {
+            x: r.x.saturating_add(1),
+            y: r.y.saturating_add(r.height.saturating_sub(1)),
+            width: cmp::min(width, r.width.saturating_sub(2)),
+            height: cmp::min(1, r.height),
+        };
+
+        let txt =
+            [Text::Styled(Cow::from(txt), self.theme.text_danger())];
+        f.render_widget(Paragraph::new(txt.iter()), area);
+    }
+
     fn can_focus_diff(&self) -> bool {
         match self.focus {
             Focus::WorkDir => self.index_wd.is_file_seleted(),
             Focus::Stage => self.index.is_file_seleted(),
+            Focus::Conflicts => self.conflicts.is_file_seleted(),
             Focus::Diff => false,
         }
     }
@@ -166,9 +235,14 @@ impl Status {
                     self.set_diff_target(DiffTarget::Stage);
                     self.diff.focus(false);
                 }
+                Focus::Conflicts => {
+                    self.set_diff_target(DiffTarget::Conflicts);
+                    self.diff.focus(false);
+                }
                 Focus::Diff => {
                     self.index.focus(false);
                     self.index_wd.focus(false);
+                    self.conflicts.focus(false);
 
                     self.diff.focus(true);
                 }
@@ -184,19 +258,32 @@ impl Status {
 
     fn set_diff_target(&mut self, target: DiffTarget) {
         self.diff_target = target;
-        let is_stage = self.diff_target == DiffTarget::Stage;
 
-        self.index_wd.focus_select(!is_stage);
-        self.index.focus_select(is_stage);
+        self.index_wd.focus_select(target == DiffTarget::WorkingDir);
+        self.index.focus_select(target == DiffTarget::Stage);
+        self.conflicts.focus_select(target == DiffTarget::Conflicts);
+    }
+
+    const fn focus_of_diff_target(&self) -> Focus {
+        match self.diff_target {
+            DiffTarget::Stage => Focus::Stage,
+            DiffTarget::WorkingDir => Focus::WorkDir,
+            DiffTarget::Conflicts => Focus::Conflicts,
+        }
     }
 
     pub fn selected_path(&self) -> Option<(String, bool)> {
-        let (idx, is_stage) = match self.diff_target {
-            DiffTarget::Stage => (&self.index, true),
-            DiffTarget::WorkingDir => (&self.index_wd, false),
+        let (selection, is_stage) = match self.diff_target {
+            DiffTarget::Stage => (self.index.selection(), true),
+            DiffTarget::WorkingDir => {
+                (self.index_wd.selection(), false)
+            }
+            DiffTarget::Conflicts => {
+                (self.conflicts.selection(), false)
+            }
         };
 
-        if let Some(item) = idx.selection() {
+        if let Some(item) = selection {
             if let FileTreeItemKind::File(i) = item.kind {
                 return Some((i.path, is_stage));
             }
@@ -243,11 +330,23 @@ impl Status {
     }
 
     fn update_status(&mut self) -> Result<()> {
+        self.repo_state = sync::repo_state(CWD)?;
+
         let stage_status = self.git_status_stage.last()?;
-        self.index.set_items(&stage_status.items)?;
+        let (_, stage_items) = split_conflicts(&stage_status.items);
+        self.index.set_items(&stage_items)?;
 
         let workdir_status = self.git_status_workdir.last()?;
-        self.index_wd.set_items(&workdir_status.items)?;
+        let (conflicts, workdir_items) =
+            split_conflicts(&workdir_status.items);
+        self.index_wd.set_items(&workdir_items)?;
+        self.conflicts.set_items(&conflicts)?;
+
+        if conflicts.is_empty()
+            && self.diff_target == DiffTarget::Conflicts
+        {
+            self.switch_focus(Focus::WorkDir)?;
+        }
 
         self.update_diff()?;
 
@@ -255,12 +354,12 @@ impl Status {
             self.git_action_executed = false;
 
             if self.focus == Focus::WorkDir
-                && workdir_status.items.is_empty()
-                && !stage_status.items.is_empty()
+                && workdir_items.is_empty()
+                && !stage_items.is_empty()
             {
                 self.switch_focus(Focus::Stage)?;
             } else if self.focus == Focus::Stage
-                && stage_status.items.is_empty()
+                && stage_items.is_empty()
             {
                 self.switch_focus(Focus::WorkDir)?;
             }
@@ -281,6 +380,7 @@ impl Status {
             let diff_params = DiffParams {
                 path: path.clone(),
                 diff_type,
+                options: self.options.borrow().diff_options(),
             };
 
             if self.diff.current() == (path.clone(), is_stage) {
@@ -289,6 +389,11 @@ impl Status {
                 if let Some((params, last)) = self.git_diff.last()? {
                     if params == diff_params {
                         self.diff.update(path, is_stage, last)?;
+                    } else if let Some(diff) =
+                        self.git_diff.request(diff_params)?
+                    {
+                        // the diff options changed in the meantime
+                        self.diff.update(path, is_stage, diff)?;
                     }
                 }
             } else {
@@ -333,6 +438,26 @@ impl Status {
                 .push_back(InternalEvent::Push(branch));
         }
     }
+
+    fn fetch(&self, merge: bool) {
+        if let Some(branch) = self.index_wd.branch_name() {
+            self.queue.borrow_mut().push_back(if merge {
+                InternalEvent::Pull(branch)
+            } else {
+                InternalEvent::Fetch(branch)
+            });
+        }
+    }
+}
+
+/// separates conflicted items (shown in their own list) from the rest
+fn split_conflicts(
+    items: &[StatusItem],
+) -> (Vec<StatusItem>, Vec<StatusItem>) {
+    items
+        .iter()
+        .cloned()
+        .partition(|item| item.status == StatusItemType::Conflicted)
 }
 
 impl Component for Status {
@@ -360,6 +485,24 @@ impl Component for Status {
                 },
                 self.visible || force_all,
             ));
+            out.push(CommandInfo::new(
+                strings::commands::blame_file(&self.key_config),
+                if focus_on_diff {
+                    true
+                } else {
+                    self.can_focus_diff()
+                },
+                self.visible || force_all,
+            ));
+            out.push(CommandInfo::new(
+                strings::commands::file_history(&self.key_config),
+                if focus_on_diff {
+                    true
+                } else {
+                    self.can_focus_diff()
+                },
+                self.visible || force_all,
+            ));
             out.push(CommandInfo::new(
                 strings::commands::diff_focus_left(&self.key_config),
                 true,
@@ -379,11 +522,28 @@ impl Component for Status {
             true,
             true,
         ));
+        out.push(CommandInfo::new(
+            strings::commands::open_branch_select_popup(
+                &self.key_config,
+            ),
+            true,
+            true,
+        ));
         out.push(CommandInfo::new(
             strings::commands::status_push(&self.key_config),
             self.index_wd.branch_name().is_some(),
             true,
         ));
+        out.push(CommandInfo::new(
+            strings::commands::status_fetch(&self.key_config),
+            self.index_wd.branch_name().is_some(),
+            true,
+        ));
+        out.push(CommandInfo::new(
+            strings::commands::status_pull(&self.key_config),
+            self.index_wd.branch_name().is_some(),
+            true,
+        ));
 
         out.push(
             CommandInfo::new(
@@ -433,12 +593,39 @@ impl Component for Status {
                 } else if k == self.key_config.edit_file
                     && (self.can_focus_diff()
                         || self.focus == Focus::Diff)
+                {
+                    if let Some((path, _)) = self.selected_path() {
+                        let line = if self.focus == Focus::Diff {
+                            self.diff.selected_new_lineno()
+                        } else {
+                            None
+                        };
+
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::OpenExternalEditor(
+                                Some(path),
+                                line,
+                            ),
+                        );
+                    }
+                    Ok(true)
+                } else if k == self.key_config.blame
+                    && (self.can_focus_diff()
+                        || self.focus == Focus::Diff)
+                {
+                    if let Some((path, _)) = self.selected_path() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::BlameFile(path, None),
+                        );
+                    }
+                    Ok(true)
+                } else if k == self.key_config.file_history
+                    && (self.can_focus_diff()
+                        || self.focus == Focus::Diff)
                 {
                     if let Some((path, _)) = self.selected_path() {
                         self.queue.borrow_mut().push_back(
-                            InternalEvent::OpenExternalEditor(Some(
-                                path,
-                            )),
+                            InternalEvent::FileHistory(path),
                         );
                     }
                     Ok(true)
@@ -447,28 +634,45 @@ impl Component for Status {
                 {
                     self.switch_focus(Focus::Diff)
                 } else if k == self.key_config.focus_left {
-                    self.switch_focus(match self.diff_target {
-                        DiffTarget::Stage => Focus::Stage,
-                        DiffTarget::WorkingDir => Focus::WorkDir,
-                    })
+                    self.switch_focus(self.focus_of_diff_target())
                 } else if k == self.key_config.move_down
                     && self.focus == Focus::WorkDir
                     && !self.index.is_empty()
                 {
                     self.switch_focus(Focus::Stage)
+                } else if k == self.key_config.move_down
+                    && self.focus == Focus::Conflicts
+                {
+                    self.switch_focus(Focus::WorkDir)
                 } else if k == self.key_config.move_up
                     && self.focus == Focus::Stage
                     && !self.index_wd.is_empty()
                 {
                     self.switch_focus(Focus::WorkDir)
+                } else if k == self.key_config.move_up
+                    && self.focus == Focus::WorkDir
+                    && !self.conflicts.is_empty()
+                {
+                    self.switch_focus(Focus::Conflicts)
                 } else if k == self.key_config.create_branch {
                     self.queue
                         .borrow_mut()
-                        .push_back(InternalEvent::CreateBranch);
+                        .push_back(InternalEvent::CreateBranch(None));
+                    Ok(true)
+                } else if k == self.key_config.select_branch {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::SelectBranch(None));
                     Ok(true)
                 } else if k == self.key_config.push {
                     self.push();
                     Ok(true)
+                } else if k == self.key_config.fetch {
+                    self.fetch(false);
+                    Ok(true)
+                } else if k == self.key_config.pull {
+                    self.fetch(true);
+                    Ok(true)
                 } else {
                     Ok(false)
                 };
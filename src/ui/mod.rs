@@ -1,6 +1,7 @@
 mod scrollbar;
 mod scrolllist;
 pub mod style;
+pub mod syntax;
 
 pub use scrollbar::draw_scrollbar;
 pub use scrolllist::draw_list;
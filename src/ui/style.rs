@@ -32,6 +32,16 @@ pub struct Theme {
     diff_line_add: Color,
     #[serde(with = "ColorDef")]
     diff_line_delete: Color,
+    #[serde(with = "ColorDef", default = "default_add_emphasis")]
+    diff_line_add_emphasis: Color,
+    #[serde(with = "ColorDef", default = "default_delete_emphasis")]
+    diff_line_delete_emphasis: Color,
+    #[serde(with = "ColorDef", default = "default_emphasis_fg")]
+    diff_line_emphasis_fg: Color,
+    #[serde(with = "ColorDef", default = "default_add_bg")]
+    diff_line_add_bg: Color,
+    #[serde(with = "ColorDef", default = "default_delete_bg")]
+    diff_line_delete_bg: Color,
     #[serde(with = "ColorDef")]
     diff_file_added: Color,
     #[serde(with = "ColorDef")]
@@ -48,6 +58,12 @@ pub struct Theme {
     commit_author: Color,
     #[serde(with = "ColorDef")]
     danger_fg: Color,
+    #[serde(with = "ColorDef", default = "default_search_match")]
+    search_match: Color,
+    /// name of the syntect theme for syntax highlighting diffs,
+    /// empty to disable it
+    #[serde(default = "default_syntax")]
+    syntax: String,
 }
 
 impl Theme {
@@ -55,6 +71,10 @@ impl Theme {
         Style::default().fg(self.selection_bg)
     }
 
+    pub fn push_gauge(&self) -> Style {
+        Style::default().fg(self.selection_bg)
+    }
+
     pub fn block(&self, focus: bool) -> Style {
         if focus {
             Style::default()
@@ -115,6 +135,9 @@ impl Theme {
                 Style::default().fg(self.diff_file_moved)
             }
             StatusItemType::Typechange => Style::default(),
+            StatusItemType::Conflicted => {
+                Style::default().fg(self.danger_fg)
+            }
         };
 
         self.apply_select(style, selected)
@@ -173,6 +196,49 @@ impl Theme {
         self.apply_select(style, selected)
     }
 
+    /// changed words inside of an added/deleted line, their
+    /// background stays visible in a selection
+    pub fn diff_line_emphasis(&self, typ: DiffLineType) -> Style {
+        match typ {
+            DiffLineType::Add => Style::default()
+                .fg(self.diff_line_emphasis_fg)
+                .bg(self.diff_line_add_emphasis),
+            DiffLineType::Delete => Style::default()
+                .fg(self.diff_line_emphasis_fg)
+                .bg(self.diff_line_delete_emphasis),
+            _ => Style::default(),
+        }
+    }
+
+    /// syntax highlighted part of a diff line, added/deleted lines
+    /// are told apart by their background instead of the foreground
+    pub fn diff_line_syntax(
+        &self,
+        typ: DiffLineType,
+        fg: Color,
+        selected: bool,
+    ) -> Style {
+        let style = match typ {
+            DiffLineType::Add => {
+                Style::default().fg(fg).bg(self.diff_line_add_bg)
+            }
+            DiffLineType::Delete => {
+                Style::default().fg(fg).bg(self.diff_line_delete_bg)
+            }
+            _ => Style::default().fg(fg),
+        };
+
+        self.apply_select(style, selected)
+    }
+
+    pub fn syntax(&self) -> Option<&str> {
+        if self.syntax.is_empty() {
+            None
+        } else {
+            Some(self.syntax.as_str())
+        }
+    }
+
     pub fn text_danger(&self) -> Style {
         Style::default().fg(self.danger_fg)
     }
@@ -208,6 +274,14 @@ impl Theme {
             selected,
         )
     }
+    pub fn search_match(&self, selected: bool) -> Style {
+        self.apply_select(
+            Style::default()
+                .fg(self.search_match)
+                .modifier(Modifier::BOLD),
+            selected,
+        )
+    }
 
     fn save(&self) -> Result<()> {
         let theme_file = Self::get_theme_file()?;
@@ -243,7 +317,13 @@ impl Theme {
     }
 
     pub fn init() -> Self {
-        Self::init_internal().unwrap_or_default()
+        Self::init_internal().unwrap_or_else(|e| {
+            log::error!(
+                "failed to load theme, using defaults: {}",
+                e
+            );
+            Self::default()
+        })
     }
 }
 
@@ -257,6 +337,11 @@ impl Default for Theme {
             disabled_fg: Color::DarkGray,
             diff_line_add: Color::Green,
             diff_line_delete: Color::Red,
+            diff_line_add_emphasis: default_add_emphasis(),
+            diff_line_delete_emphasis: default_delete_emphasis(),
+            diff_line_emphasis_fg: default_emphasis_fg(),
+            diff_line_add_bg: default_add_bg(),
+            diff_line_delete_bg: default_delete_bg(),
             diff_file_added: Color::LightGreen,
             diff_file_removed: Color::LightRed,
             diff_file_moved: Color::LightMagenta,
@@ -265,10 +350,43 @@ impl Default for Theme {
             commit_time: Color::LightCyan,
             commit_author: Color::Green,
             danger_fg: Color::Red,
+            search_match: default_search_match(),
+            syntax: default_syntax(),
         }
     }
 }
 
+// defaults of the colors added after the first release of the theme
+// file, an existing `theme.ron` lacking them still has to load
+
+const fn default_add_emphasis() -> Color {
+    Color::Green
+}
+
+const fn default_delete_emphasis() -> Color {
+    Color::Red
+}
+
+const fn default_emphasis_fg() -> Color {
+    Color::Black
+}
+
+const fn default_add_bg() -> Color {
+    Color::Indexed(22)
+}
+
+const fn default_delete_bg() -> Color {
+    Color::Indexed(52)
+}
+
+const fn default_search_match() -> Color {
+    Color::Yellow
+}
+
+fn default_syntax() -> String {
+    String::from("base16-eighties.dark")
+}
+
 /// we duplicate the Color definition from `tui` crate to implement Serde serialisation
 /// this enum can be removed once [tui-#292](https://github.com/fdehau/tui-rs/issues/292) is resolved
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
@@ -294,3 +412,37 @@ enum ColorDef {
     Rgb(u8, u8, u8),
     Indexed(u8),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_theme_without_new_colors() {
+        // theme as written by the first release supporting it
+        let theme = r#"(
+            selected_tab: Yellow,
+            command_fg: White,
+            selection_bg: Blue,
+            cmdbar_extra_lines_bg: Blue,
+            disabled_fg: DarkGray,
+            diff_line_add: Green,
+            diff_line_delete: Red,
+            diff_file_added: LightGreen,
+            diff_file_removed: LightRed,
+            diff_file_moved: LightMagenta,
+            diff_file_modified: Yellow,
+            commit_hash: Magenta,
+            commit_time: LightCyan,
+            commit_author: Green,
+            danger_fg: Red,
+        )"#;
+
+        let theme: Theme = ron::de::from_str(theme).unwrap();
+
+        assert_eq!(theme.search_match, Color::Yellow);
+        assert_eq!(theme.diff_line_add_bg, Color::Indexed(22));
+        assert_eq!(theme.diff_line_emphasis_fg, Color::Black);
+        assert_eq!(theme.syntax, default_syntax());
+    }
+}
@@ -0,0 +1,176 @@
+//! syntax highlighting of diff content, only available with the
+//! `syntect` feature
+
+use asyncgit::{AsyncNotification, FileDiff};
+use crossbeam_channel::Sender;
+use std::{
+    collections::HashMap,
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+use tui::style::Color;
+
+/// foreground colors of the parts (byte ranges) of a line
+pub type LineHighlights = Vec<(Range<usize>, Color)>;
+
+/// number of highlighted diffs kept around
+const CACHE_SIZE: usize = 32;
+
+enum Job {
+    Pending,
+    Done(Option<Arc<Vec<LineHighlights>>>),
+}
+
+/// highlights diffs on a worker thread (it is too slow for the ui
+/// thread), the results are cached by file and diff hash
+pub struct AsyncHighlighter {
+    cache: Arc<Mutex<HashMap<(String, u64), Job>>>,
+    sender: Sender<AsyncNotification>,
+}
+
+impl AsyncHighlighter {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            sender: sender.clone(),
+        }
+    }
+
+    /// the highlights of `diff` (with hash `hash`) if they are done,
+    /// otherwise they get computed and `AsyncNotification::Diff` is
+    /// sent once they are ready
+    pub fn get(
+        &self,
+        path: &str,
+        hash: u64,
+        diff: &FileDiff,
+        theme_name: &str,
+    ) -> Option<Arc<Vec<LineHighlights>>> {
+        if !cfg!(feature = "syntect") {
+            return None;
+        }
+
+        let key = (path.to_string(), hash);
+
+        let mut cache = self.cache.lock().ok()?;
+        match cache.get(&key) {
+            Some(Job::Done(res)) => return res.clone(),
+            Some(Job::Pending) => return None,
+            None => (),
+        }
+
+        if cache.len() >= CACHE_SIZE {
+            cache.retain(|_, job| matches!(job, Job::Pending));
+        }
+        cache.insert(key.clone(), Job::Pending);
+        drop(cache);
+
+        let cache = Arc::clone(&self.cache);
+        let sender = self.sender.clone();
+        let diff = diff.clone();
+        let theme_name = theme_name.to_string();
+
+        rayon_core::spawn(move || {
+            let res = highlight_diff(&key.0, &diff, &theme_name)
+                .map(Arc::new);
+
+            if let Ok(mut cache) = cache.lock() {
+                cache.insert(key, Job::Done(res));
+            }
+
+            sender
+                .send(AsyncNotification::Diff)
+                .expect("error sending diff");
+        });
+
+        None
+    }
+}
+
+/// diffs bigger than this are not highlighted to keep the ui snappy
+#[cfg(feature = "syntect")]
+const MAX_LINES: usize = 5000;
+
+#[cfg(feature = "syntect")]
+thread_local! {
+    static SYNTAX_SET: syntect::parsing::SyntaxSet =
+        syntect::parsing::SyntaxSet::load_defaults_newlines();
+    static THEME_SET: syntect::highlighting::ThemeSet =
+        syntect::highlighting::ThemeSet::load_defaults();
+}
+
+/// highlights every line of `diff` (in the order of the flattened
+/// hunks) using the syntax matching the extension of `path` and the
+/// syntect theme called `theme_name`
+#[cfg(feature = "syntect")]
+pub fn highlight_diff(
+    path: &str,
+    diff: &FileDiff,
+    theme_name: &str,
+) -> Option<Vec<LineHighlights>> {
+    use asyncgit::DiffLineType;
+    use syntect::easy::HighlightLines;
+
+    if diff.lines > MAX_LINES {
+        return None;
+    }
+
+    let extension =
+        std::path::Path::new(path).extension()?.to_str()?;
+
+    SYNTAX_SET.with(|syntax_set| {
+        THEME_SET.with(|theme_set| {
+            let syntax =
+                syntax_set.find_syntax_by_extension(extension)?;
+            let theme = theme_set.themes.get(theme_name)?;
+
+            let mut res = Vec::with_capacity(diff.lines);
+
+            for hunk in &diff.hunks {
+                // every hunk starts parsing from scratch as its
+                // context is unknown
+                let mut highlighter =
+                    HighlightLines::new(syntax, theme);
+
+                for line in &hunk.lines {
+                    if line.line_type == DiffLineType::Header {
+                        res.push(LineHighlights::new());
+                        continue;
+                    }
+
+                    let mut pos = 0;
+                    let parts = highlighter
+                        .highlight(&line.content, syntax_set)
+                        .into_iter()
+                        .map(|(style, part)| {
+                            let range = pos..pos + part.len();
+                            pos = range.end;
+                            (
+                                range,
+                                Color::Rgb(
+                                    style.foreground.r,
+                                    style.foreground.g,
+                                    style.foreground.b,
+                                ),
+                            )
+                        })
+                        .collect();
+
+                    res.push(parts);
+                }
+            }
+
+            Some(res)
+        })
+    })
+}
+
+#[cfg(not(feature = "syntect"))]
+pub const fn highlight_diff(
+    _path: &str,
+    _diff: &FileDiff,
+    _theme_name: &str,
+) -> Option<Vec<LineHighlights>> {
+    None
+}
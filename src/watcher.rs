@@ -0,0 +1,239 @@
+use anyhow::Result;
+use asyncgit::sync;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use notify::{watcher, DebouncedEvent, RecursiveMode, Watcher};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+/// changes within this time are reported as one
+static DEBOUNCE_DURATION: Duration = Duration::from_millis(500);
+
+/// entries of the git dir that change with the index or refs,
+/// anything else in there (objects, locks, logs..) is ignored
+static GIT_DIR_ENTRIES: &[&str] =
+    &["index", "HEAD", "packed-refs", "refs"];
+
+/// watches the working dir and git dir (inotify on linux) and sends
+/// a notification whenever the workdir, index or refs changed
+pub struct RepoWatcher {
+    receiver: Receiver<()>,
+}
+
+impl RepoWatcher {
+    ///
+    pub fn new(work_dir: &str, git_dir: &str) -> Result<Self> {
+        let (tx_notify, rx_notify) = mpsc::channel();
+        let mut notify_watcher =
+            watcher(tx_notify, DEBOUNCE_DURATION)?;
+
+        let work_dir = PathBuf::from(work_dir);
+        let git_dir = PathBuf::from(git_dir);
+
+        notify_watcher.watch(&work_dir, RecursiveMode::Recursive)?;
+        // the git dir might live outside of the workdir
+        if !git_dir.starts_with(&work_dir) {
+            notify_watcher
+                .watch(&git_dir, RecursiveMode::Recursive)?;
+        }
+
+        let (tx, rx) = unbounded();
+
+        thread::spawn(move || {
+            // the watcher stops watching once it is dropped
+            let _notify_watcher = notify_watcher;
+
+            Self::forward_changes(
+                &rx_notify, &tx, &work_dir, &git_dir,
+            );
+        });
+
+        Ok(Self { receiver: rx })
+    }
+
+    ///
+    pub fn receiver(&self) -> Receiver<()> {
+        self.receiver.clone()
+    }
+
+    fn forward_changes(
+        rx: &mpsc::Receiver<DebouncedEvent>,
+        tx: &Sender<()>,
+        work_dir: &Path,
+        git_dir: &Path,
+    ) {
+        while let Ok(ev) = rx.recv() {
+            let mut changes = Changes::default();
+            changes.add_event(&ev, work_dir, git_dir);
+
+            // a single refresh covers everything queued up meanwhile
+            while let Ok(ev) = rx.try_recv() {
+                changes.add_event(&ev, work_dir, git_dir);
+            }
+
+            if changes.is_relevant(work_dir) && tx.send(()).is_err() {
+                break;
+            }
+        }
+
+        log::info!("watcher stopped");
+    }
+}
+
+/// what a batch of events touched
+#[derive(Default, Debug, PartialEq)]
+struct Changes {
+    /// index, refs or anything we cannot tell about changed
+    repo: bool,
+    /// changed paths in the workdir (relative to it)
+    workdir_paths: Vec<PathBuf>,
+}
+
+impl Changes {
+    fn add_event(
+        &mut self,
+        ev: &DebouncedEvent,
+        work_dir: &Path,
+        git_dir: &Path,
+    ) {
+        match ev {
+            DebouncedEvent::Create(path)
+            | DebouncedEvent::Write(path)
+            | DebouncedEvent::Chmod(path)
+            | DebouncedEvent::Remove(path) => {
+                self.add_path(path, work_dir, git_dir)
+            }
+            DebouncedEvent::Rename(from, to) => {
+                self.add_path(from, work_dir, git_dir);
+                self.add_path(to, work_dir, git_dir);
+            }
+            DebouncedEvent::Rescan => self.repo = true,
+            DebouncedEvent::Error(e, path) => {
+                log::error!("watcher error: {} ({:?})", e, path);
+            }
+            DebouncedEvent::NoticeWrite(_)
+            | DebouncedEvent::NoticeRemove(_) => (),
+        }
+    }
+
+    fn add_path(
+        &mut self,
+        path: &Path,
+        work_dir: &Path,
+        git_dir: &Path,
+    ) {
+        // the git dir usually lives inside the workdir
+        if let Ok(path) = path.strip_prefix(git_dir) {
+            self.repo |=
+                path.components().next().map_or(false, |entry| {
+                    GIT_DIR_ENTRIES
+                        .iter()
+                        .any(|e| entry.as_os_str() == OsStr::new(e))
+                });
+        } else if let Ok(path) = path.strip_prefix(work_dir) {
+            // the workdir itself changes along with its entries
+            if path.components().next().is_some() {
+                self.workdir_paths.push(path.to_path_buf());
+            }
+        } else {
+            self.repo = true;
+        }
+    }
+
+    /// gitignored files (build output..) do not show up in the status,
+    /// changes to them alone do not need a refresh
+    fn is_relevant(&self, work_dir: &Path) -> bool {
+        if self.repo {
+            return true;
+        }
+
+        if self.workdir_paths.is_empty() {
+            return false;
+        }
+
+        sync::any_path_not_ignored(
+            &work_dir.to_string_lossy(),
+            &self.workdir_paths,
+        )
+        .unwrap_or_else(|e| {
+            log::error!(
+                "watcher failed to check ignored paths: {}",
+                e
+            );
+            true
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(paths: &[&str]) -> Changes {
+        let mut changes = Changes::default();
+        for path in paths {
+            changes.add_path(
+                Path::new(path),
+                Path::new("/repo"),
+                Path::new("/repo/.git"),
+            );
+        }
+        changes
+    }
+
+    #[test]
+    fn test_git_dir_paths() {
+        assert_eq!(
+            changes(&["/repo/.git/objects/ab/cdef"]).repo,
+            false
+        );
+        assert_eq!(changes(&["/repo/.git/index.lock"]).repo, false);
+        assert_eq!(changes(&["/repo/.git/logs/HEAD"]).repo, false);
+        assert_eq!(changes(&["/repo/.git/index"]).repo, true);
+        assert_eq!(
+            changes(&["/repo/.git/refs/heads/master"]).repo,
+            true
+        );
+        assert_eq!(changes(&["/repo/.git/HEAD"]).repo, true);
+    }
+
+    #[test]
+    fn test_workdir_paths() {
+        let c =
+            changes(&["/repo/src/main.rs", "/repo", "/repo/.git"]);
+
+        assert_eq!(c.repo, false);
+        assert_eq!(
+            c.workdir_paths,
+            vec![PathBuf::from("src/main.rs")]
+        );
+
+        let c = changes(&["/repo", "/repo/.git"]);
+        assert_eq!(c, Changes::default());
+        assert_eq!(c.is_relevant(Path::new("/repo")), false);
+    }
+
+    #[test]
+    fn test_outside_paths() {
+        let c = changes(&["/elsewhere/file"]);
+
+        assert_eq!(c.repo, true);
+        assert!(c.workdir_paths.is_empty());
+    }
+
+    #[test]
+    fn test_rescan() {
+        let mut c = Changes::default();
+        c.add_event(
+            &DebouncedEvent::Rescan,
+            Path::new("/repo"),
+            Path::new("/repo/.git"),
+        );
+
+        assert_eq!(c.is_relevant(Path::new("/repo")), true);
+    }
+}